@@ -1249,6 +1249,7 @@ impl DocxConverter {
             title,
             images,
             warnings,
+            ..Default::default()
         };
 
         let pending = PendingImageResolution {
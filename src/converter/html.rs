@@ -2,18 +2,140 @@
 //!
 //! Parses HTML using the `scraper` crate (html5ever) and walks the DOM tree
 //! to produce Markdown. Supports headings, paragraphs, tables, lists, links,
-//! blockquotes, code blocks, bold/italic, and images.
-
-use crate::converter::{ConversionOptions, ConversionResult, Converter};
+//! blockquotes, code blocks, bold/italic, and images. Non-UTF-8 pages are
+//! decoded using the charset declared by a BOM or `<meta>` tag. Images from
+//! `data:` URIs, relative local files and the embedded parts of a saved page
+//! ([`mhtml`](super::mhtml)) go through the shared image pipeline (extraction,
+//! byte budget, LLM descriptions).
+
+use crate::converter::ooxml_utils::{
+    ImageInfo, PendingImageResolution, resolve_image_placeholders,
+};
+use crate::converter::{
+    ComplexTables, ConversionOptions, ConversionResult, ConversionWarning, Converter,
+    DocumentMetadata, HtmlOptions, LinkPolicy, WarningCode,
+};
 use crate::error::ConvertError;
 use crate::markdown;
 
+use std::collections::HashMap;
+use std::path::{Component, PathBuf};
+use std::rc::Rc;
+
+use base64::Engine;
+
+use ego_tree::NodeId;
 use ego_tree::iter::Edge;
-use scraper::{Html, Node};
+use scraper::{ElementRef, Html, Node};
+use url::Url;
 
 /// Converts HTML files to Markdown.
 pub struct HtmlConverter;
 
+// ---- Internal conversion (parse + image extraction, no resolution) ----
+
+impl HtmlConverter {
+    /// Convert HTML to Markdown with image placeholders, collecting the bytes
+    /// of `data:` URI and local images without resolving descriptions.
+    ///
+    /// Returns the conversion result (with unresolved placeholders in markdown)
+    /// and pending image data for later resolution (sync or async).
+    pub(crate) fn convert_inner(
+        &self,
+        data: &[u8],
+        options: &ConversionOptions,
+    ) -> Result<(ConversionResult, PendingImageResolution), ConvertError> {
+        let (text, encoding_warning) = decode_html(data, None);
+        self.convert_document(
+            &text,
+            encoding_warning.into_iter().collect(),
+            options,
+            &HashMap::new(),
+        )
+    }
+
+    /// Convert an already decoded HTML document. `resources` holds the
+    /// embedded parts of a saved page (MHTML), keyed by absolute URL or
+    /// `cid:` reference; `<img>` sources found there are loaded from it.
+    pub(crate) fn convert_document(
+        &self,
+        text: &str,
+        mut warnings: Vec<ConversionWarning>,
+        options: &ConversionOptions,
+        resources: &HashMap<String, EmbeddedResource>,
+    ) -> Result<(ConversionResult, PendingImageResolution), ConvertError> {
+        let document = Html::parse_document(text);
+
+        let title = extract_title(&document);
+        let base = document_base(&document, options.html.base_url.as_deref(), &mut warnings);
+        let metadata =
+            extract_metadata(&document, base.as_ref(), options.html.links, &mut warnings);
+        let root = if options.html.readability {
+            find_main_content(&document).unwrap_or_else(|| document.root_element())
+        } else {
+            document.root_element()
+        };
+        let (mut md, plain, html_images) = walk_dom(root, &options.html, base, resources);
+        if options.html.front_matter {
+            md.insert_str(0, &front_matter(title.as_deref(), &metadata));
+        }
+
+        // Load image bytes if requested or if describer needs them
+        let need_image_bytes = options.extract_images || options.image_describer.is_some();
+        let mut images: Vec<(String, Vec<u8>)> = Vec::new();
+        let mut image_bytes_map: HashMap<String, Vec<u8>> = HashMap::new();
+        if need_image_bytes {
+            let mut total_image_bytes: usize = 0;
+            for image in &html_images {
+                let remaining = options
+                    .max_total_image_bytes
+                    .saturating_sub(total_image_bytes);
+                let Some((name, bytes)) =
+                    load_image(image, options, resources, remaining, &mut warnings)
+                else {
+                    continue;
+                };
+                // Over-budget images are skipped; smaller later ones may still fit
+                if bytes.len() > remaining {
+                    warnings.push(ConversionWarning {
+                        code: WarningCode::ResourceLimitReached,
+                        message: format!(
+                            "total image bytes exceeded limit ({})",
+                            options.max_total_image_bytes
+                        ),
+                        location: Some(image.info.filename.clone()),
+                    });
+                    continue;
+                }
+                total_image_bytes += bytes.len();
+                // The same image referenced twice is extracted once
+                if options.extract_images && !images.iter().any(|(n, _)| *n == name) {
+                    images.push((name, bytes.clone()));
+                }
+                image_bytes_map.insert(image.info.bytes_key.clone(), bytes);
+            }
+        }
+
+        let result = ConversionResult {
+            markdown: md,
+            plain_text: plain,
+            title,
+            images,
+            warnings,
+            metadata,
+        };
+
+        let pending = PendingImageResolution {
+            infos: html_images.into_iter().map(|image| image.info).collect(),
+            bytes: image_bytes_map,
+        };
+
+        Ok((result, pending))
+    }
+}
+
+// ---- Converter trait impl ----
+
 impl Converter for HtmlConverter {
     fn supported_extensions(&self) -> &[&str] {
         &["html", "htm"]
@@ -22,21 +144,396 @@ impl Converter for HtmlConverter {
     fn convert(
         &self,
         data: &[u8],
-        _options: &ConversionOptions,
+        options: &ConversionOptions,
     ) -> Result<ConversionResult, ConvertError> {
-        let text = String::from_utf8(data.to_vec())?;
-        let text = text.strip_prefix('\u{FEFF}').unwrap_or(&text);
-        let document = Html::parse_document(text);
+        let (mut result, pending) = self.convert_inner(data, options)?;
+        resolve_image_placeholders(
+            &mut result.markdown,
+            &mut result.plain_text,
+            &pending.infos,
+            &pending.bytes,
+            options.image_describer.as_deref(),
+            &mut result.warnings,
+        );
+        Ok(result)
+    }
+}
 
-        let title = extract_title(&document);
-        let (md, plain) = walk_dom(&document);
+// ---- Images ----
 
-        Ok(ConversionResult {
-            markdown: md,
-            plain_text: plain,
-            title,
-            ..Default::default()
-        })
+/// An image whose bytes can be loaded: the placeholder info emitted into the
+/// Markdown plus where the bytes come from.
+struct HtmlImage {
+    info: ImageInfo,
+    source: ImageSource,
+}
+
+enum ImageSource {
+    /// Full `data:` URI from the `src` attribute.
+    DataUri(String),
+    /// Path relative to [`HtmlOptions::image_dir`].
+    LocalFile(PathBuf),
+    /// Key into the embedded resources of a saved page.
+    Embedded(String),
+}
+
+/// A resource stored inside the document container (an MHTML part).
+pub(crate) struct EmbeddedResource {
+    /// Lowercased media type without parameters, e.g. `image/png`.
+    pub(crate) content_type: String,
+    /// Decoded bytes, shared by every key the part is reachable under.
+    pub(crate) data: Rc<Vec<u8>>,
+}
+
+/// Media type of a `data:` URI if it holds an image (`data:image/png;base64,...`).
+fn data_uri_image_mime(src: &str) -> Option<String> {
+    let rest = src
+        .get(..5)?
+        .eq_ignore_ascii_case("data:")
+        .then(|| &src[5..])?;
+    let header = &rest[..rest.find(',')?];
+    let mime = header.split(';').next()?.trim().to_ascii_lowercase();
+    mime.starts_with("image/").then_some(mime)
+}
+
+/// Decode the payload of a `data:` URI (base64 or percent-encoded).
+fn decode_data_uri(src: &str) -> Option<Vec<u8>> {
+    let (header, payload) = src.split_once(',')?;
+    let is_base64 = header
+        .rsplit(';')
+        .next()
+        .is_some_and(|p| p.trim().eq_ignore_ascii_case("base64"));
+    if is_base64 {
+        let compact: Vec<u8> = percent_decode(payload)
+            .into_iter()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        base64::engine::general_purpose::STANDARD
+            .decode(&compact)
+            .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(&compact))
+            .ok()
+    } else {
+        Some(percent_decode(payload))
+    }
+}
+
+/// Decode `%XX` escapes; invalid escapes are kept literally.
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = s.get(i + 1..i + 3)
+            && let Ok(b) = u8::from_str_radix(hex, 16)
+        {
+            out.push(b);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Relative file path named by an image `src`, if it is one. URLs with a
+/// scheme, absolute paths and paths leaving the directory (`..`) are rejected.
+fn local_image_path(src: &str) -> Option<PathBuf> {
+    let path = src.split(['?', '#']).next()?.trim();
+    if path.is_empty() || path.starts_with(['/', '\\']) {
+        return None;
+    }
+    // A colon before the first slash is a URL scheme (or a drive letter)
+    if path
+        .split('/')
+        .next()
+        .is_some_and(|first| first.contains(':'))
+    {
+        return None;
+    }
+    let decoded = String::from_utf8(percent_decode(path)).ok()?;
+    let relative = PathBuf::from(decoded);
+    relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        .then_some(relative)
+}
+
+/// Load the bytes of an image, returning the name used in
+/// `ConversionResult::images`. Local files that do not exist are skipped
+/// silently (pages are often saved without their assets). Local files and
+/// embedded parts larger than the `remaining` image byte budget are not read.
+fn load_image(
+    image: &HtmlImage,
+    options: &ConversionOptions,
+    resources: &HashMap<String, EmbeddedResource>,
+    remaining: usize,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Option<(String, Vec<u8>)> {
+    match &image.source {
+        ImageSource::Embedded(key) => {
+            let resource = resources.get(key)?;
+            if resource.data.len() > remaining {
+                warnings.push(ConversionWarning {
+                    code: WarningCode::ResourceLimitReached,
+                    message: format!(
+                        "total image bytes exceeded limit ({})",
+                        options.max_total_image_bytes
+                    ),
+                    location: Some(image.info.filename.clone()),
+                });
+                return None;
+            }
+            Some((image.info.filename.clone(), resource.data.to_vec()))
+        }
+        ImageSource::DataUri(src) => match decode_data_uri(src) {
+            Some(bytes) => Some((image.info.filename.clone(), bytes)),
+            None => {
+                warnings.push(ConversionWarning {
+                    code: WarningCode::MalformedSegment,
+                    message: "invalid data URI image".to_string(),
+                    location: Some(image.info.filename.clone()),
+                });
+                None
+            }
+        },
+        ImageSource::LocalFile(relative) => {
+            // Symlinks are followed only if they stay inside the image directory
+            let dir = options.html.image_dir.as_deref()?.canonicalize().ok()?;
+            let path = dir.join(relative).canonicalize().ok()?;
+            if !path.starts_with(&dir) {
+                warnings.push(ConversionWarning {
+                    code: WarningCode::SkippedElement,
+                    message: format!(
+                        "image '{}' resolves outside the image directory",
+                        relative.display()
+                    ),
+                    location: Some(image.info.filename.clone()),
+                });
+                return None;
+            }
+            let metadata = std::fs::metadata(&path).ok().filter(|m| m.is_file())?;
+            if metadata.len() > remaining as u64 {
+                warnings.push(ConversionWarning {
+                    code: WarningCode::ResourceLimitReached,
+                    message: format!(
+                        "total image bytes exceeded limit ({})",
+                        options.max_total_image_bytes
+                    ),
+                    location: Some(image.info.filename.clone()),
+                });
+                return None;
+            }
+            match std::fs::read(&path) {
+                // Named like the Markdown link, so relative paths stay unique
+                Ok(bytes) => Some((image.info.filename.clone(), bytes)),
+                Err(e) => {
+                    warnings.push(ConversionWarning {
+                        code: WarningCode::SkippedElement,
+                        message: format!("failed to read image '{}': {e}", path.display()),
+                        location: Some(image.info.filename.clone()),
+                    });
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Last path segment of an absolute URL, percent-decoded. `cid:` references
+/// have no usable name.
+fn url_file_name(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let segment = url.path_segments()?.next_back()?;
+    let name = String::from_utf8(percent_decode(segment)).ok()?;
+    (!name.is_empty()).then_some(name)
+}
+
+// ---- Character encoding ----
+
+/// How many leading bytes are scanned for a `<meta>` charset declaration.
+const CHARSET_SNIFF_BYTES: usize = 4096;
+
+/// Decode an HTML document to UTF-8.
+///
+/// The encoding comes from a byte order mark, then from the `transport`
+/// charset declared outside the document (the `Content-Type` of an MHTML
+/// part), then from a `<meta charset>` or `<meta http-equiv="Content-Type">`
+/// declaration near the start of the file. Undeclared documents go through
+/// [`decode_text`](super::decode_text), which accepts UTF-8 and otherwise
+/// falls back to windows-1252 with a warning.
+pub(crate) fn decode_html(
+    data: &[u8],
+    transport: Option<&'static encoding_rs::Encoding>,
+) -> (String, Option<ConversionWarning>) {
+    let declared = encoding_rs::Encoding::for_bom(data)
+        .or_else(|| transport.map(|e| (e, 0)))
+        .or_else(|| {
+            sniff_meta_charset(&data[..data.len().min(CHARSET_SNIFF_BYTES)]).map(|e| (e, 0))
+        });
+    let Some((encoding, skip)) = declared else {
+        return super::decode_text(data);
+    };
+
+    let (decoded, had_errors) = encoding.decode_without_bom_handling(&data[skip..]);
+    let warning = had_errors.then(|| ConversionWarning {
+        code: WarningCode::MalformedSegment,
+        message: format!(
+            "replacement characters inserted during {} decoding",
+            encoding.name()
+        ),
+        location: None,
+    });
+    let text = decoded.strip_prefix('\u{FEFF}').unwrap_or(&decoded);
+    (text.to_string(), warning)
+}
+
+/// Find the encoding declared by the first `<meta charset>` or
+/// `<meta http-equiv="Content-Type" content="...; charset=...">` in `head`.
+fn sniff_meta_charset(head: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    // Tag and attribute names are ASCII, so a lossy view is enough to find them
+    let text = String::from_utf8_lossy(head);
+    let lower = text.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(pos) = lower[from..].find("<meta") {
+        let start = from + pos + "<meta".len();
+        let end = lower[start..].find('>').map_or(lower.len(), |e| start + e);
+        from = end;
+
+        let attrs = parse_tag_attributes(&text[start..end]);
+        let attr = |name: &str| {
+            attrs
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        let label = match attr("charset") {
+            Some(charset) => Some(charset.to_string()),
+            None if attr("http-equiv").is_some_and(|v| v.eq_ignore_ascii_case("content-type")) => {
+                attr("content").and_then(charset_from_content_type)
+            }
+            None => None,
+        };
+        if let Some(encoding) =
+            label.and_then(|l| encoding_rs::Encoding::for_label(l.trim().as_bytes()))
+        {
+            // A page that could declare UTF-16 in ASCII is not UTF-16 (HTML spec)
+            return Some(
+                if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+                    encoding_rs::UTF_8
+                } else {
+                    encoding
+                },
+            );
+        }
+    }
+    None
+}
+
+/// Extract the `charset` parameter from a Content-Type value such as
+/// `text/html; charset="Shift_JIS"`.
+fn charset_from_content_type(content: &str) -> Option<String> {
+    let lower = content.to_ascii_lowercase();
+    let pos = lower.find("charset")?;
+    let rest = content[pos + "charset".len()..].trim_start();
+    let value = rest.strip_prefix('=')?.trim_start();
+    let value = value.trim_start_matches(['"', '\'']);
+    let end = value
+        .find(|c: char| c == ';' || c == '"' || c == '\'' || c.is_whitespace())
+        .unwrap_or(value.len());
+    (end > 0).then(|| value[..end].to_string())
+}
+
+/// Parse `name="value"` pairs from the inside of a start tag. Names are
+/// lowercased; values may be double-quoted, single-quoted, or bare.
+fn parse_tag_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = tag.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() || c == '/' {
+            chars.next();
+            continue;
+        }
+        let mut name_end = start;
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() || c == '=' || c == '/' {
+                break;
+            }
+            name_end = i + c.len_utf8();
+            chars.next();
+        }
+        let name = tag[start..name_end].to_ascii_lowercase();
+        while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut value = String::new();
+        if chars.peek().is_some_and(|&(_, c)| c == '=') {
+            chars.next();
+            while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+                chars.next();
+            }
+            match chars.peek().map(|&(_, c)| c) {
+                Some(quote @ ('"' | '\'')) => {
+                    chars.next();
+                    for (_, c) in chars.by_ref() {
+                        if c == quote {
+                            break;
+                        }
+                        value.push(c);
+                    }
+                }
+                _ => {
+                    while let Some(&(_, c)) = chars.peek() {
+                        if c.is_whitespace() {
+                            break;
+                        }
+                        value.push(c);
+                        chars.next();
+                    }
+                }
+            }
+        }
+        if !name.is_empty() {
+            attrs.push((name, value));
+        }
+    }
+
+    attrs
+}
+
+/// Determine the base URL for relative links: the first `<base href>`,
+/// resolved against `base_url` when given, otherwise `base_url` itself.
+/// An unparsable `base_url` is reported as a warning and ignored.
+fn document_base(
+    document: &Html,
+    base_url: Option<&str>,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Option<Url> {
+    let configured = base_url.and_then(|raw| match Url::parse(raw) {
+        Ok(url) => Some(url),
+        Err(e) => {
+            warnings.push(ConversionWarning {
+                code: WarningCode::MalformedSegment,
+                message: format!("ignoring invalid base_url '{raw}': {e}"),
+                location: None,
+            });
+            None
+        }
+    });
+
+    let declared = scraper::Selector::parse("base[href]").ok().and_then(|sel| {
+        document
+            .select(&sel)
+            .next()
+            .and_then(|el| el.value().attr("href"))
+            .map(str::trim)
+    });
+    match (configured, declared) {
+        (Some(base), Some(href)) => base.join(href).ok().or(Some(base)),
+        (None, Some(href)) => Url::parse(href).ok(),
+        (configured, None) => configured,
     }
 }
 
@@ -62,6 +559,145 @@ fn extract_title(document: &Html) -> Option<String> {
     None
 }
 
+/// Collect `<meta>` tags, Open Graph properties, the canonical link and JSON-LD
+/// blocks. The first occurrence of each field wins.
+fn extract_metadata(
+    document: &Html,
+    base: Option<&Url>,
+    links: LinkPolicy,
+    warnings: &mut Vec<ConversionWarning>,
+) -> DocumentMetadata {
+    use scraper::Selector;
+    let mut meta = DocumentMetadata::default();
+
+    if let Ok(sel) = Selector::parse("meta[content]") {
+        for el in document.select(&sel) {
+            let content = el.value().attr("content").unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+            let key = el
+                .value()
+                .attr("property")
+                .or_else(|| el.value().attr("name"))
+                .unwrap_or("")
+                .trim()
+                .to_ascii_lowercase();
+            match key.as_str() {
+                "description" => {
+                    meta.description.get_or_insert_with(|| content.to_string());
+                }
+                "author" => {
+                    meta.author.get_or_insert_with(|| content.to_string());
+                }
+                "keywords" if meta.keywords.is_empty() => {
+                    meta.keywords = content
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|k| !k.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                "article:published_time" => {
+                    meta.published.get_or_insert_with(|| content.to_string());
+                }
+                _ if key.starts_with("og:") => {
+                    meta.open_graph
+                        .entry(key)
+                        .or_insert_with(|| content.to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Ok(sel) = Selector::parse("link[href]") {
+        let canonical = document.select(&sel).find(|el| {
+            el.value().attr("rel").is_some_and(|rel| {
+                rel.split_ascii_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("canonical"))
+            })
+        });
+        if let Some(href) = canonical.and_then(|el| el.value().attr("href")) {
+            let href = href.trim();
+            meta.canonical_url = Some(match base {
+                Some(base) if links != LinkPolicy::Keep => base
+                    .join(href)
+                    .map_or_else(|_| href.to_string(), |url| url.to_string()),
+                _ => href.to_string(),
+            });
+        }
+    }
+
+    if let Ok(sel) = Selector::parse("script") {
+        let json_ld = document.select(&sel).filter(|el| {
+            el.value()
+                .attr("type")
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case("application/ld+json"))
+        });
+        for (index, el) in json_ld.enumerate() {
+            let raw = el.text().collect::<String>();
+            match serde_json::from_str::<serde_json::Value>(&raw) {
+                Ok(value) => meta.json_ld.push(value.to_string()),
+                Err(e) => warnings.push(ConversionWarning {
+                    code: WarningCode::MalformedSegment,
+                    message: format!("skipping invalid JSON-LD block: {e}"),
+                    location: Some(format!("ld+json script {}", index + 1)),
+                }),
+            }
+        }
+    }
+
+    meta
+}
+
+/// Render the title and metadata as a YAML front matter block, or an empty
+/// string if there is nothing to write.
+fn front_matter(title: Option<&str>, meta: &DocumentMetadata) -> String {
+    if title.is_none() && meta.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("---\n");
+    let scalars = [
+        ("title", title),
+        ("description", meta.description.as_deref()),
+        ("author", meta.author.as_deref()),
+        ("published", meta.published.as_deref()),
+        ("canonical_url", meta.canonical_url.as_deref()),
+    ];
+    for (key, value) in scalars {
+        if let Some(value) = value {
+            out.push_str(&format!("{key}: {}\n", yaml_string(value)));
+        }
+    }
+    if !meta.keywords.is_empty() {
+        out.push_str("keywords:\n");
+        for keyword in &meta.keywords {
+            out.push_str(&format!("  - {}\n", yaml_string(keyword)));
+        }
+    }
+    if !meta.open_graph.is_empty() {
+        out.push_str("open_graph:\n");
+        for (key, value) in &meta.open_graph {
+            out.push_str(&format!("  {}: {}\n", yaml_string(key), yaml_string(value)));
+        }
+    }
+    if !meta.json_ld.is_empty() {
+        // Compact JSON is valid YAML flow syntax.
+        out.push_str("json_ld:\n");
+        for block in &meta.json_ld {
+            out.push_str(&format!("  - {block}\n"));
+        }
+    }
+    out.push_str("---\n\n");
+    out
+}
+
+/// Double-quoted YAML scalar. JSON string escaping is a subset of YAML's.
+fn yaml_string(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
 // ---- State types ----
 
 struct WalkerState {
@@ -75,7 +711,27 @@ struct WalkerState {
     plain_trailing_newlines: usize,
     pending_heading: Option<PendingHeading>,
     pending_link: Option<PendingLink>,
-    table_collector: Option<TableCollector>,
+    /// Open `<figcaption>`/`<summary>` elements, innermost last.
+    pending_wraps: Vec<PendingWrap>,
+    /// Open tables, innermost last. Nested tables are rendered into the
+    /// enclosing cell when they close.
+    table_stack: Vec<TableCollector>,
+    complex_tables: ComplexTables,
+    links: LinkPolicy,
+    /// Base URL that relative `href`/`src` values are resolved against.
+    base: Option<Url>,
+    /// Link targets referenced so far under [`LinkPolicy::Reference`];
+    /// link `[text][n]` points at `link_refs[n - 1]`.
+    link_refs: Vec<String>,
+    /// Reference number of each URL in `link_refs`.
+    link_ref_numbers: HashMap<String, usize>,
+    /// Directory relative image paths are read from, if any.
+    image_dir: Option<PathBuf>,
+    /// Media types of the embedded resources images can be loaded from,
+    /// keyed like the resource map.
+    resource_types: HashMap<String, String>,
+    /// Images emitted with `__img_N__` placeholders.
+    images: Vec<HtmlImage>,
 }
 
 struct ListContext {
@@ -89,22 +745,86 @@ struct PendingHeading {
     plain_start_pos: usize,
 }
 
+/// Block whose Markdown text is wrapped in `marker` when it closes
+/// (`<figcaption>` in italics, `<summary>` in bold). Headings inside it are
+/// written as plain lines, and its lines are joined into one.
+struct PendingWrap {
+    marker: &'static str,
+    start_pos: usize,
+}
+
 struct PendingLink {
     href: String,
     start_pos: usize,
 }
 
+#[derive(Default)]
 struct TableCollector {
-    headers: Vec<String>,
-    rows: Vec<Vec<String>>,
-    current_row: Vec<String>,
-    current_cell: String,
+    rows: Vec<TableRow>,
+    current_row: Vec<TableCell>,
+    current_cell: TableCell,
     in_header: bool,
     in_cell: bool,
+    /// A cell of this table contains another table.
+    has_nested: bool,
+    /// Document buffers set aside while the current cell is collected.
+    outer: Option<OuterBuffers>,
+}
+
+/// The walker's Markdown and plain-text buffers, with their trailing newline
+/// counts and blockquote depth, parked while a table cell is being written.
+struct OuterBuffers {
+    output: String,
+    plain_output: String,
+    trailing_newlines: usize,
+    plain_trailing_newlines: usize,
+    blockquote_depth: usize,
+}
+
+struct TableRow {
+    cells: Vec<TableCell>,
+    /// Row sits in `<thead>` or consists of `<th>` cells only.
+    header: bool,
+}
+
+struct TableCell {
+    /// Inline Markdown of the cell; `<br>` is kept as a newline.
+    markdown: String,
+    /// Plain cell text. Nested tables appear as inline `header: value` text
+    /// in both forms.
+    text: String,
+    /// HTML-escaped cell content; nested tables appear as HTML tables.
+    html: String,
+    colspan: usize,
+    rowspan: usize,
+    header: bool,
+}
+
+impl Default for TableCell {
+    fn default() -> Self {
+        Self {
+            markdown: String::new(),
+            text: String::new(),
+            html: String::new(),
+            colspan: 1,
+            rowspan: 1,
+            header: false,
+        }
+    }
+}
+
+impl TableCell {
+    fn content(&self, plain: bool) -> &str {
+        if plain { &self.text } else { &self.markdown }
+    }
 }
 
 impl WalkerState {
-    fn new() -> Self {
+    fn new(
+        options: &HtmlOptions,
+        base: Option<Url>,
+        resource_types: HashMap<String, String>,
+    ) -> Self {
         Self {
             output: String::new(),
             plain_output: String::new(),
@@ -116,7 +836,16 @@ impl WalkerState {
             plain_trailing_newlines: 0,
             pending_heading: None,
             pending_link: None,
-            table_collector: None,
+            pending_wraps: Vec::new(),
+            table_stack: Vec::new(),
+            complex_tables: options.complex_tables,
+            links: options.links,
+            base,
+            link_refs: Vec::new(),
+            link_ref_numbers: HashMap::new(),
+            image_dir: options.image_dir.clone(),
+            resource_types,
+            images: Vec::new(),
         }
     }
 
@@ -220,25 +949,222 @@ impl WalkerState {
     }
 
     fn in_table_cell(&self) -> bool {
-        self.table_collector.as_ref().is_some_and(|tc| tc.in_cell)
+        self.table_stack.last().is_some_and(|tc| tc.in_cell)
+    }
+
+    /// Remove and return the Markdown written since `pos`.
+    fn take_output_from(&mut self, pos: usize) -> String {
+        let text = self.output.split_off(pos);
+        self.trailing_newlines = self
+            .output
+            .bytes()
+            .rev()
+            .take_while(|&b| b == b'\n')
+            .count();
+        text
+    }
+
+    // ---- Link helpers ----
+
+    /// Resolve a relative `href`/`src` against the base URL, unless links are
+    /// kept verbatim. Values that cannot be resolved are returned unchanged.
+    fn resolve_url(&self, raw: &str) -> String {
+        let raw = raw.trim();
+        match &self.base {
+            Some(base) if self.links != LinkPolicy::Keep && !raw.is_empty() => base
+                .join(raw)
+                .map_or_else(|_| raw.to_string(), |url| url.to_string()),
+            _ => raw.to_string(),
+        }
+    }
+
+    /// Number of the reference definition for `url`, adding it if new.
+    fn link_reference(&mut self, url: &str) -> usize {
+        if let Some(&number) = self.link_ref_numbers.get(url) {
+            return number;
+        }
+        self.link_refs.push(url.to_string());
+        self.link_ref_numbers
+            .insert(url.to_string(), self.link_refs.len());
+        self.link_refs.len()
+    }
+
+    // ---- Image helpers ----
+
+    /// Register an image whose bytes can be loaded later and return the
+    /// placeholder and file name to emit, or `None` for ordinary remote images.
+    fn register_image(&mut self, src: &str, alt: &str) -> Option<(String, String)> {
+        let (filename, source) = if let Some(mime) = data_uri_image_mime(src) {
+            let filename = self.numbered_image_name(&mime);
+            (filename, ImageSource::DataUri(src.to_string()))
+        } else if let Some((key, mime)) = self.embedded_resource(src) {
+            let filename = self.embedded_image_name(&key, &mime);
+            (filename, ImageSource::Embedded(key))
+        } else {
+            self.image_dir.as_ref()?;
+            let relative = local_image_path(src)?;
+            (self.resolve_url(src), ImageSource::LocalFile(relative))
+        };
+
+        let placeholder = format!("__img_{}__", self.images.len());
+        self.images.push(HtmlImage {
+            info: ImageInfo {
+                placeholder: placeholder.clone(),
+                original_alt: alt.to_string(),
+                filename: filename.clone(),
+                bytes_key: placeholder.clone(),
+            },
+            source,
+        });
+        Some((placeholder, filename))
+    }
+
+    /// `image_{n}.{ext}` name for an image that has no file name of its own.
+    fn numbered_image_name(&self, mime: &str) -> String {
+        let ext = super::image::ext_from_mime(mime);
+        let n = self.images.len() + 1;
+        if ext.is_empty() {
+            format!("image_{n}")
+        } else {
+            format!("image_{n}.{ext}")
+        }
+    }
+
+    /// File name for an embedded image: the last path segment of its URL, or
+    /// a numbered name for `cid:` references and names already taken by
+    /// another part. The same part always gets the same name.
+    fn embedded_image_name(&self, key: &str, mime: &str) -> String {
+        if let Some(image) = self
+            .images
+            .iter()
+            .find(|image| matches!(&image.source, ImageSource::Embedded(k) if k == key))
+        {
+            return image.info.filename.clone();
+        }
+        match url_file_name(key) {
+            Some(name) if !self.images.iter().any(|image| image.info.filename == name) => name,
+            _ => self.numbered_image_name(mime),
+        }
+    }
+
+    /// Key and media type of the embedded resource `src` refers to. The
+    /// source is looked up as an absolute URL first, then verbatim.
+    fn embedded_resource(&self, src: &str) -> Option<(String, String)> {
+        if self.resource_types.is_empty() {
+            return None;
+        }
+        let src = src.trim();
+        let absolute = match &self.base {
+            Some(base) => base.join(src).ok(),
+            None => Url::parse(src).ok(),
+        };
+        [absolute.map(String::from), Some(src.to_string())]
+            .into_iter()
+            .flatten()
+            .find_map(|key| {
+                self.resource_types
+                    .get(&key)
+                    .map(|mime| (key.clone(), mime.clone()))
+            })
+    }
+
+    // ---- Table cell helpers ----
+
+    /// Start writing a table cell. The document buffers are parked so that
+    /// inline handlers (emphasis, links, images) write into the cell. Block
+    /// elements inside the cell are flattened to lines (see `handle_open`),
+    /// and an enclosing blockquote's `> ` prefix is suspended.
+    fn begin_cell(&mut self) {
+        let outer = OuterBuffers {
+            output: std::mem::take(&mut self.output),
+            plain_output: std::mem::take(&mut self.plain_output),
+            trailing_newlines: std::mem::take(&mut self.trailing_newlines),
+            plain_trailing_newlines: std::mem::take(&mut self.plain_trailing_newlines),
+            blockquote_depth: std::mem::take(&mut self.blockquote_depth),
+        };
+        if let Some(tc) = self.table_stack.last_mut() {
+            tc.outer = Some(outer);
+            tc.in_cell = true;
+        }
+    }
+
+    /// Finish the current cell and restore the document buffers. Returns the
+    /// cell's Markdown and plain text.
+    fn end_cell(&mut self) -> (String, String) {
+        let Some(outer) = self.table_stack.last_mut().and_then(|tc| tc.outer.take()) else {
+            return (String::new(), String::new());
+        };
+        let markdown = std::mem::replace(&mut self.output, outer.output);
+        let plain = std::mem::replace(&mut self.plain_output, outer.plain_output);
+        self.trailing_newlines = outer.trailing_newlines;
+        self.plain_trailing_newlines = outer.plain_trailing_newlines;
+        self.blockquote_depth = outer.blockquote_depth;
+        (markdown, plain)
+    }
+
+    /// Append markup to the HTML form of the open table cell, if any.
+    fn push_cell_html(&mut self, s: &str) {
+        if let Some(tc) = self.table_stack.last_mut()
+            && tc.in_cell
+        {
+            tc.current_cell.html.push_str(s);
+        }
     }
 }
 
 // ---- DOM walker ----
 
-fn walk_dom(document: &Html) -> (String, String) {
-    let mut state = WalkerState::new();
+/// Convert the subtree under `root`. In readability mode, boilerplate subtrees
+/// (see [`is_boilerplate`]) below the root are left out. Relative URLs are
+/// resolved against `base` according to [`HtmlOptions::links`].
+fn walk_dom(
+    root: ElementRef,
+    options: &HtmlOptions,
+    base: Option<Url>,
+    resources: &HashMap<String, EmbeddedResource>,
+) -> (String, String, Vec<HtmlImage>) {
+    let resource_types = resources
+        .iter()
+        .map(|(key, resource)| (key.clone(), resource.content_type.clone()))
+        .collect();
+    let mut state = WalkerState::new(options, base, resource_types);
+    let mut pruned: Option<NodeId> = None;
 
-    for edge in document.root_element().traverse() {
+    for edge in root.traverse() {
         match edge {
-            Edge::Open(node) => handle_open(&mut state, &node),
-            Edge::Close(node) => handle_close(&mut state, &node),
+            Edge::Open(node) => {
+                if pruned.is_none()
+                    && options.readability
+                    && node.id() != root.id()
+                    && is_boilerplate(&node)
+                {
+                    pruned = Some(node.id());
+                }
+                if pruned.is_none() {
+                    handle_open(&mut state, &node);
+                }
+            }
+            Edge::Close(node) => {
+                if pruned.is_none() {
+                    handle_close(&mut state, &node);
+                } else if pruned == Some(node.id()) {
+                    pruned = None;
+                }
+            }
         }
     }
 
     // Final cleanup: trim trailing whitespace
     let md = state.output.trim().to_string();
-    let md = if md.is_empty() { md } else { md + "\n" };
+    let mut md = if md.is_empty() { md } else { md + "\n" };
+
+    // Reference-style link definitions go after the content
+    if !state.link_refs.is_empty() {
+        md.push('\n');
+        for (i, url) in state.link_refs.iter().enumerate() {
+            md.push_str(&format!("[{}]: {}\n", i + 1, url));
+        }
+    }
 
     let plain = state.plain_output.trim().to_string();
     let plain = if plain.is_empty() {
@@ -247,7 +1173,304 @@ fn walk_dom(document: &Html) -> (String, String) {
         plain + "\n"
     };
 
-    (md, plain)
+    (md, plain, state.images)
+}
+
+// ---- Readability ----
+
+/// Paragraphs shorter than this (in characters) do not contribute to scores.
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// How many ancestors of a paragraph receive a share of its score.
+const SCORED_ANCESTORS: usize = 5;
+
+/// Siblings scoring at least this fraction of the best candidate are kept
+/// together by moving up to their common parent.
+const SIBLING_SCORE_RATIO: f64 = 0.2;
+
+/// Weight added or subtracted for semantic tags and class/id hints.
+const HINT_WEIGHT: f64 = 25.0;
+
+/// Class/id tokens that suggest page chrome rather than content.
+const NEGATIVE_HINTS: &[&str] = &[
+    "ad",
+    "ads",
+    "advert",
+    "banner",
+    "breadcrumb",
+    "breadcrumbs",
+    "comment",
+    "comments",
+    "consent",
+    "cookie",
+    "cookies",
+    "footer",
+    "masthead",
+    "menu",
+    "modal",
+    "nav",
+    "navbar",
+    "navigation",
+    "newsletter",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "subscribe",
+    "widget",
+];
+
+/// Class/id tokens that suggest the main content.
+const POSITIVE_HINTS: &[&str] = &[
+    "article", "blog", "body", "content", "entry", "main", "post", "story", "text",
+];
+
+/// Pick the element holding the main content of the page.
+///
+/// Every paragraph-like element adds a score based on its length and comma
+/// count to its nearest ancestors (with decreasing weight). Candidates start
+/// with a bonus for `<main>`, `<article>` and `role="main"` and for class/id
+/// hints, and their final score is scaled down by their link density. The
+/// winner is widened to its parent while that parent has several strong
+/// children, and to its enclosing `<main>`/`<article>` if there is one.
+///
+/// Returns `None` when nothing could be scored and the page has no semantic
+/// main element, in which case the whole document should be converted.
+fn find_main_content(document: &Html) -> Option<ElementRef<'_>> {
+    let mut raw_scores: HashMap<NodeId, f64> = HashMap::new();
+    let mut pruned: Option<NodeId> = None;
+
+    for edge in document.root_element().traverse() {
+        match edge {
+            Edge::Open(node) => {
+                if pruned.is_some() {
+                    continue;
+                }
+                let Some(el) = ElementRef::wrap(node) else {
+                    continue;
+                };
+                if is_boilerplate(&node)
+                    || matches!(
+                        el.value().name(),
+                        "script" | "style" | "head" | "noscript" | "template"
+                    )
+                {
+                    pruned = Some(node.id());
+                    continue;
+                }
+                if !is_paragraph_like(el) {
+                    continue;
+                }
+                let text = collapse_whitespace(&el.text().collect::<String>());
+                let len = text.trim().chars().count();
+                if len < MIN_PARAGRAPH_CHARS {
+                    continue;
+                }
+                let commas = text.matches(',').count() as f64;
+                let score = 1.0 + commas + (len as f64 / 100.0).min(3.0);
+                for (level, ancestor) in el
+                    .ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .take(SCORED_ANCESTORS)
+                    .enumerate()
+                {
+                    let divisor = match level {
+                        0 => 1.0,
+                        1 => 2.0,
+                        n => n as f64 * 3.0,
+                    };
+                    *raw_scores
+                        .entry(ancestor.id())
+                        .or_insert_with(|| initial_score(ancestor)) += score / divisor;
+                }
+            }
+            Edge::Close(node) => {
+                if pruned == Some(node.id()) {
+                    pruned = None;
+                }
+            }
+        }
+    }
+
+    // Walk candidates in document order so ties resolve deterministically.
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+    let mut best: Option<(ElementRef, f64)> = None;
+    for node in document.root_element().descendants() {
+        let Some(raw) = raw_scores.get(&node.id()) else {
+            continue;
+        };
+        let Some(el) = ElementRef::wrap(node) else {
+            continue;
+        };
+        let score = raw * (1.0 - link_density(el));
+        scores.insert(node.id(), score);
+        if best.is_none_or(|(_, s)| score > s) {
+            best = Some((el, score));
+        }
+    }
+
+    let Some((mut top, top_score)) = best else {
+        return document
+            .root_element()
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .find(|el| is_semantic_main(*el));
+    };
+
+    let threshold = top_score * SIBLING_SCORE_RATIO;
+    while !is_semantic_main(top) {
+        let Some(parent) = top.parent().and_then(ElementRef::wrap) else {
+            break;
+        };
+        if parent.value().name() == "html" {
+            break;
+        }
+        let strong_children = parent
+            .children()
+            .filter(|c| scores.get(&c.id()).is_some_and(|s| *s >= threshold))
+            .count();
+        if strong_children < 2 {
+            break;
+        }
+        top = parent;
+    }
+
+    if let Some(container) = top
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|el| is_semantic_main(*el))
+    {
+        top = container;
+    }
+
+    Some(top)
+}
+
+/// Starting score of a candidate: semantic tag bonus plus class/id hints.
+fn initial_score(el: ElementRef) -> f64 {
+    let tag_weight = match el.value().name() {
+        "main" | "article" => HINT_WEIGHT,
+        "div" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "address" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    let role_weight = if el.value().attr("role") == Some("main") {
+        HINT_WEIGHT
+    } else {
+        0.0
+    };
+    tag_weight + role_weight + hint_weight(el)
+}
+
+/// `+HINT_WEIGHT` for a positive class/id token, `-HINT_WEIGHT` for a negative one.
+fn hint_weight(el: ElementRef) -> f64 {
+    let tokens = hint_tokens(el);
+    let mut weight = 0.0;
+    if tokens.iter().any(|t| POSITIVE_HINTS.contains(&t.as_str())) {
+        weight += HINT_WEIGHT;
+    }
+    if tokens.iter().any(|t| NEGATIVE_HINTS.contains(&t.as_str())) {
+        weight -= HINT_WEIGHT;
+    }
+    weight
+}
+
+/// Lowercased words of the `class` and `id` attributes (`"site-nav"` → `site`, `nav`).
+fn hint_tokens(el: ElementRef) -> Vec<String> {
+    let value = el.value();
+    [value.attr("class"), value.attr("id")]
+        .into_iter()
+        .flatten()
+        .flat_map(|attr| attr.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_ascii_lowercase())
+        .collect()
+}
+
+/// Whether the element is `<main>`, `<article>` or has `role="main"`.
+fn is_semantic_main(el: ElementRef) -> bool {
+    matches!(el.value().name(), "main" | "article") || el.value().attr("role") == Some("main")
+}
+
+/// Whether the node is page chrome that readability mode leaves out:
+/// `<nav>`, `<aside>`, `<footer>`, landmark roles other than `main`, or an
+/// element whose class/id has only negative hints (`cookie-banner`, `sidebar`).
+fn is_boilerplate(node: &ego_tree::NodeRef<Node>) -> bool {
+    let Some(el) = ElementRef::wrap(*node) else {
+        return false;
+    };
+    let name = el.value().name();
+    if matches!(name, "html" | "body") || is_semantic_main(el) {
+        return false;
+    }
+    if matches!(name, "nav" | "aside" | "footer") {
+        return true;
+    }
+    if matches!(
+        el.value().attr("role"),
+        Some("navigation" | "banner" | "contentinfo" | "complementary")
+    ) {
+        return true;
+    }
+    hint_weight(el) < 0.0
+}
+
+/// Elements whose text is scored as a paragraph: `<p>`, `<pre>`, `<td>`,
+/// `<blockquote>`, and `<div>`s without block-level children.
+fn is_paragraph_like(el: ElementRef) -> bool {
+    match el.value().name() {
+        "p" | "pre" | "td" | "blockquote" => true,
+        "div" => !el.children().filter_map(ElementRef::wrap).any(|child| {
+            matches!(
+                child.value().name(),
+                "address"
+                    | "article"
+                    | "aside"
+                    | "blockquote"
+                    | "div"
+                    | "dl"
+                    | "figure"
+                    | "footer"
+                    | "form"
+                    | "h1"
+                    | "h2"
+                    | "h3"
+                    | "h4"
+                    | "h5"
+                    | "h6"
+                    | "header"
+                    | "main"
+                    | "nav"
+                    | "ol"
+                    | "p"
+                    | "pre"
+                    | "section"
+                    | "table"
+                    | "ul"
+            )
+        }),
+        _ => false,
+    }
+}
+
+/// Fraction of the element's text that sits inside links.
+fn link_density(el: ElementRef) -> f64 {
+    let total: usize = el.text().map(|t| t.trim().chars().count()).sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let linked: usize = el
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|d| d.value().name() == "a")
+        .flat_map(|a| a.text())
+        .map(|t| t.trim().chars().count())
+        .sum();
+    (linked as f64 / total as f64).min(1.0)
 }
 
 // ---- Element handlers (open) ----
@@ -262,6 +1485,28 @@ fn handle_open(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                     state.skip_depth += 1;
                 }
                 _ if state.skip_depth > 0 => {}
+                // GFM cells hold one line of inline content: block elements
+                // become plain lines, joined with <br> when the cell is rendered
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "blockquote" | "hr" | "li"
+                    if state.in_table_cell() =>
+                {
+                    state.both_ensure_newline();
+                }
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" if !state.pending_wraps.is_empty() => {
+                    state.both_ensure_newline();
+                }
+                "ul" | "ol" if state.in_table_cell() => {
+                    state.both_ensure_newline();
+                    state.list_stack.push(ListContext {
+                        ordered: tag == "ol",
+                        item_count: 0,
+                    });
+                }
+                "pre" if state.in_table_cell() => {
+                    state.in_pre = true;
+                    state.push_str("`");
+                    state.push_cell_html("<code>");
+                }
                 "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
                     state.both_ensure_blank_line();
                     let level = tag[1..].parse::<u8>().unwrap_or(1);
@@ -275,7 +1520,13 @@ fn handle_open(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                     state.both_ensure_blank_line();
                 }
                 "a" => {
-                    let href = el.attr("href").unwrap_or("").to_string();
+                    let href = match (state.links, el.attr("href")) {
+                        (LinkPolicy::Text, _) | (_, None) => String::new(),
+                        (_, Some(raw)) => state.resolve_url(raw),
+                    };
+                    if !href.is_empty() {
+                        state.push_cell_html(&format!("<a href=\"{}\">", escape_html(&href)));
+                    }
                     state.pending_link = Some(PendingLink {
                         href,
                         start_pos: state.output.len(),
@@ -283,20 +1534,38 @@ fn handle_open(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                 }
                 "img" => {
                     let alt = el.attr("alt").unwrap_or("");
-                    let src = el.attr("src").unwrap_or("");
-                    state.push_str(&format!("![{}]({})", alt, src));
-                    state.plain_push_str(alt);
+                    let raw_src = el.attr("src").unwrap_or("").trim();
+                    let src =
+                        if let Some((placeholder, filename)) = state.register_image(raw_src, alt) {
+                            // Alt text is filled in when placeholders are resolved
+                            state.push_str(&format!("![{placeholder}]({filename})"));
+                            state.plain_push_str(&placeholder);
+                            filename
+                        } else {
+                            let src = state.resolve_url(raw_src);
+                            state.push_str(&format!("![{}]({})", alt, src));
+                            state.plain_push_str(alt);
+                            src
+                        };
+                    state.push_cell_html(&format!(
+                        "<img src=\"{}\" alt=\"{}\">",
+                        escape_html(&src),
+                        escape_html(alt)
+                    ));
                 }
                 "strong" | "b" => {
                     state.push_str("**");
+                    state.push_cell_html("<strong>");
                     // plain text: no markers
                 }
                 "em" | "i" => {
                     state.push_str("*");
+                    state.push_cell_html("<em>");
                     // plain text: no markers
                 }
                 "code" if !state.in_pre => {
                     state.push_str("`");
+                    state.push_cell_html("<code>");
                     // plain text: no backtick
                 }
                 "pre" => {
@@ -345,35 +1614,37 @@ fn handle_open(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                     state.plain_push_str(&indent);
                 }
                 "table" => {
-                    state.both_ensure_blank_line();
-                    state.table_collector = Some(TableCollector {
-                        headers: Vec::new(),
-                        rows: Vec::new(),
-                        current_row: Vec::new(),
-                        current_cell: String::new(),
-                        in_header: false,
-                        in_cell: false,
-                    });
+                    if let Some(parent) = state.table_stack.last_mut() {
+                        parent.has_nested = true;
+                    } else {
+                        state.both_ensure_blank_line();
+                    }
+                    state.table_stack.push(TableCollector::default());
                 }
                 "thead" => {
-                    if let Some(tc) = &mut state.table_collector {
+                    if let Some(tc) = state.table_stack.last_mut() {
                         tc.in_header = true;
                     }
                 }
                 "tbody" => {
-                    if let Some(tc) = &mut state.table_collector {
+                    if let Some(tc) = state.table_stack.last_mut() {
                         tc.in_header = false;
                     }
                 }
                 "tr" => {
-                    if let Some(tc) = &mut state.table_collector {
+                    if let Some(tc) = state.table_stack.last_mut() {
                         tc.current_row = Vec::new();
                     }
                 }
                 "th" | "td" => {
-                    if let Some(tc) = &mut state.table_collector {
-                        tc.current_cell = String::new();
-                        tc.in_cell = true;
+                    if let Some(tc) = state.table_stack.last_mut() {
+                        tc.current_cell = TableCell {
+                            colspan: span_attr(el.attr("colspan"), MAX_COLSPAN).max(1),
+                            rowspan: span_attr(el.attr("rowspan"), MAX_ROWSPAN),
+                            header: tag == "th",
+                            ..Default::default()
+                        };
+                        state.begin_cell();
                     }
                 }
                 "blockquote" => {
@@ -387,10 +1658,14 @@ fn handle_open(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                     state.plain_ensure_blank_line();
                 }
                 "br" => {
-                    if state.in_pre {
+                    if state.in_pre && state.in_table_cell() {
+                        state.both_push_char(' ');
+                    } else if state.in_pre {
                         state.both_push_char('\n');
                     } else if state.in_table_cell() {
-                        // In table cells, just add a space instead of a newline
+                        // Kept as a newline; escape_cell turns it into <br>
+                        state.both_push_char('\n');
+                        state.push_cell_html("<br>");
                     } else {
                         state.both_push_char('\n');
                         // Add blockquote prefix after br (markdown only)
@@ -400,6 +1675,33 @@ fn handle_open(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                         }
                     }
                 }
+                "dl" | "figure" | "details" | "address" => {
+                    state.both_ensure_blank_line();
+                }
+                "dt" => {
+                    state.both_ensure_blank_line();
+                }
+                "dd" => {
+                    state.both_ensure_newline();
+                    state.push_str(":   ");
+                    state.plain_push_str("  ");
+                }
+                "figcaption" | "summary" => {
+                    state.both_ensure_blank_line();
+                    state.pending_wraps.push(PendingWrap {
+                        marker: if tag == "summary" { "**" } else { "*" },
+                        start_pos: state.output.len(),
+                    });
+                }
+                _ if inline_markers(&tag).is_some() => {
+                    if let Some((open, _)) = inline_markers(&tag) {
+                        state.push_str(open);
+                    }
+                    if tag == "q" {
+                        state.plain_push_str("\"");
+                    }
+                    state.push_cell_html(&format!("<{tag}>"));
+                }
                 "input" => {
                     let input_type = el.attr("type").unwrap_or("");
                     if input_type == "checkbox" {
@@ -429,6 +1731,23 @@ fn handle_close(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                 state.skip_depth = state.skip_depth.saturating_sub(1);
             }
             _ if state.skip_depth > 0 => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "blockquote" | "li"
+                if state.in_table_cell() =>
+            {
+                state.both_ensure_newline();
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" if !state.pending_wraps.is_empty() => {
+                state.both_ensure_newline();
+            }
+            "ul" | "ol" if state.in_table_cell() => {
+                state.list_stack.pop();
+                state.both_ensure_newline();
+            }
+            "pre" if state.in_table_cell() => {
+                state.push_str("`");
+                state.push_cell_html("</code>");
+                state.in_pre = false;
+            }
             "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
                 if let Some(pending) = state.pending_heading.take() {
                     // Markdown: format as heading
@@ -462,6 +1781,10 @@ fn handle_close(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
             "p" if !state.in_table_cell() => {
                 state.both_ensure_blank_line();
             }
+            "p" => {
+                // Paragraphs inside a cell become line breaks
+                state.both_push_char('\n');
+            }
             "a" => {
                 if let Some(pending) = state.pending_link.take() {
                     // Markdown: format as link
@@ -476,7 +1799,14 @@ fn handle_close(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                     if pending.href.is_empty() {
                         state.push_str(text.trim());
                     } else {
-                        state.push_str(&format!("[{}]({})", text.trim(), pending.href));
+                        let link = if state.links == LinkPolicy::Reference {
+                            let n = state.link_reference(&pending.href);
+                            format!("[{}][{}]", text.trim(), n)
+                        } else {
+                            format!("[{}]({})", text.trim(), pending.href)
+                        };
+                        state.push_str(&link);
+                        state.push_cell_html("</a>");
                     }
 
                     // Plain text: just the link text (already accumulated)
@@ -485,14 +1815,17 @@ fn handle_close(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
             }
             "strong" | "b" => {
                 state.push_str("**");
+                state.push_cell_html("</strong>");
                 // plain text: no closing marker
             }
             "em" | "i" => {
                 state.push_str("*");
+                state.push_cell_html("</em>");
                 // plain text: no closing marker
             }
             "code" if !state.in_pre => {
                 state.push_str("`");
+                state.push_cell_html("</code>");
                 // plain text: no closing backtick
             }
             "pre" => {
@@ -511,30 +1844,41 @@ fn handle_close(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                 state.both_ensure_newline();
             }
             "table" => {
-                if let Some(tc) = state.table_collector.take() {
-                    let table_md = render_table(&tc, false);
-                    state.push_str(&table_md);
-                    let table_plain = render_table(&tc, true);
-                    state.plain_push_str(&table_plain);
+                if let Some(tc) = state.table_stack.pop() {
+                    if let Some(parent) = state.table_stack.last_mut() {
+                        // Nested table: inline it into the enclosing cell
+                        if parent.in_cell {
+                            parent.current_cell.html.push_str(&render_table_html(&tc));
+                            state.push_str(&format!(" {} ", render_table_inline(&tc, false)));
+                            state.plain_push_str(&format!(" {} ", render_table_inline(&tc, true)));
+                        }
+                    } else {
+                        let table_md = render_table(&tc, state.complex_tables, false);
+                        state.push_str(&table_md);
+                        let table_plain = render_table(&tc, state.complex_tables, true);
+                        state.plain_push_str(&table_plain);
+                    }
                 }
             }
             "thead" => {
                 // in_header handled by tbody open or next row
             }
             "tr" => {
-                if let Some(tc) = &mut state.table_collector {
-                    let row = std::mem::take(&mut tc.current_row);
-                    if tc.in_header {
-                        tc.headers = row;
-                    } else {
-                        tc.rows.push(row);
-                    }
+                if let Some(tc) = state.table_stack.last_mut() {
+                    let cells = std::mem::take(&mut tc.current_row);
+                    let header =
+                        tc.in_header || (!cells.is_empty() && cells.iter().all(|c| c.header));
+                    tc.rows.push(TableRow { cells, header });
                 }
             }
             "th" | "td" => {
-                if let Some(tc) = &mut state.table_collector {
-                    let cell = std::mem::take(&mut tc.current_cell);
-                    tc.current_row.push(cell.trim().to_string());
+                let (markdown, plain) = state.end_cell();
+                if let Some(tc) = state.table_stack.last_mut() {
+                    let mut cell = std::mem::take(&mut tc.current_cell);
+                    cell.markdown = cell_lines(&markdown).join("\n");
+                    cell.text = cell_lines(&plain).join(" ");
+                    cell.html = cell.html.trim().to_string();
+                    tc.current_row.push(cell);
                     tc.in_cell = false;
                 }
             }
@@ -542,11 +1886,61 @@ fn handle_close(state: &mut WalkerState, node: &ego_tree::NodeRef<Node>) {
                 state.blockquote_depth = state.blockquote_depth.saturating_sub(1);
                 state.both_ensure_newline();
             }
+            "dl" | "figure" | "details" | "address" => {
+                state.both_ensure_blank_line();
+            }
+            "dt" | "dd" => {
+                state.both_ensure_newline();
+            }
+            "figcaption" | "summary" => {
+                if let Some(pending) = state.pending_wraps.pop() {
+                    let text = state.take_output_from(pending.start_pos);
+                    let text = cell_lines(&text).join(" ");
+                    if !text.is_empty() {
+                        state.push_str(&format!("{0}{text}{0}", pending.marker));
+                    }
+                }
+                state.both_ensure_blank_line();
+            }
+            "abbr" => {
+                // Expand the abbreviation: WHO (World Health Organization)
+                if let Some(title) = el.attr("title").map(str::trim)
+                    && !title.is_empty()
+                {
+                    state.both_push_str(&format!(" ({title})"));
+                }
+            }
+            _ if inline_markers(&tag).is_some() => {
+                if let Some((_, close)) = inline_markers(&tag) {
+                    state.push_str(close);
+                }
+                if tag == "q" {
+                    state.plain_push_str("\"");
+                }
+                state.push_cell_html(&format!("</{tag}>"));
+            }
             _ => {}
         }
     }
 }
 
+/// Markdown wrapped around inline elements without a dedicated handler.
+/// GFM has strikethrough but no syntax for insertions, super-/subscripts,
+/// keys or highlights, so those stay inline HTML.
+fn inline_markers(tag: &str) -> Option<(&'static str, &'static str)> {
+    match tag {
+        "del" | "s" | "strike" => Some(("~~", "~~")),
+        "cite" => Some(("*", "*")),
+        "q" => Some(("\"", "\"")),
+        "ins" => Some(("<ins>", "</ins>")),
+        "sup" => Some(("<sup>", "</sup>")),
+        "sub" => Some(("<sub>", "</sub>")),
+        "kbd" => Some(("<kbd>", "</kbd>")),
+        "mark" => Some(("<mark>", "</mark>")),
+        _ => None,
+    }
+}
+
 // ---- Text processing helpers ----
 
 fn handle_text(state: &mut WalkerState, text: &scraper::node::Text) {
@@ -556,17 +1950,17 @@ fn handle_text(state: &mut WalkerState, text: &scraper::node::Text) {
 
     let raw = text.text.as_ref();
 
-    // Inside a table cell: accumulate into the cell buffer (shared for both outputs)
-    if let Some(tc) = &mut state.table_collector {
-        if tc.in_cell {
-            tc.current_cell.push_str(raw);
+    // Inside a table cell the buffers belong to the cell (see begin_cell);
+    // the HTML form is collected alongside for the complex-table fallback.
+    if let Some(tc) = state.table_stack.last_mut() {
+        if !tc.in_cell {
+            // Text outside cells but inside table (e.g. whitespace between tags) — ignore
             return;
         }
-        // Text outside cells but inside table (e.g. whitespace between tags) — ignore
-        return;
+        tc.current_cell.html.push_str(&escape_html(raw));
     }
 
-    if state.in_pre {
+    if state.in_pre && !state.in_table_cell() {
         state.both_push_str(raw);
         return;
     }
@@ -595,10 +1989,10 @@ fn handle_text(state: &mut WalkerState, text: &scraper::node::Text) {
         return;
     }
 
-    // Skip leading space if output already ends with whitespace
+    // Skip leading space if output already ends with whitespace or a line break
     let md_collapsed = if collapsed.starts_with(' ') && !state.output.is_empty() {
         let last = state.output.bytes().last().unwrap_or(b'\n');
-        if last == b' ' || last == b'\t' {
+        if last == b' ' || last == b'\t' || last == b'\n' {
             &collapsed[1..]
         } else {
             &collapsed
@@ -609,7 +2003,7 @@ fn handle_text(state: &mut WalkerState, text: &scraper::node::Text) {
 
     let plain_collapsed = if collapsed.starts_with(' ') && !state.plain_output.is_empty() {
         let last = state.plain_output.bytes().last().unwrap_or(b'\n');
-        if last == b' ' || last == b'\t' {
+        if last == b' ' || last == b'\t' || last == b'\n' {
             &collapsed[1..]
         } else {
             &collapsed
@@ -662,32 +2056,252 @@ fn collapse_whitespace(s: &str) -> String {
     result
 }
 
+// ---- Table layout ----
+
+/// Upper bounds for `colspan`/`rowspan`, as in the HTML table model.
+const MAX_COLSPAN: usize = 1000;
+const MAX_ROWSPAN: usize = 65534;
+
+/// Widest a table grid is laid out, as a multiple of the most cells in any row,
+/// so a stray `colspan="1000"` does not pad every row with empty cells.
+const MAX_WIDTH_PER_CELL: usize = 2;
+
+/// Parse a `colspan`/`rowspan` attribute. Missing or invalid values mean 1;
+/// `rowspan="0"` (span to the end of the table) is kept as 0.
+fn span_attr(value: Option<&str>, max: usize) -> usize {
+    value
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .map_or(1, |n| n.min(max))
+}
+
+/// One position of the expanded table grid.
+#[derive(Clone, Copy)]
+struct GridSlot {
+    /// Index of the covering cell as (row, cell within row).
+    cell: (usize, usize),
+    /// Offset of this slot from the cell's top-left corner as (rows, columns).
+    offset: (usize, usize),
+}
+
+/// Place every cell on a rectangular grid, expanding `colspan`/`rowspan`.
+/// Row spans never add rows past the end of the table, and column spans are
+/// cut short at [`MAX_WIDTH_PER_CELL`] times the most cells in a row (every
+/// cell still gets at least one column).
+fn layout_grid(rows: &[TableRow]) -> Vec<Vec<Option<GridSlot>>> {
+    let width_cap = rows.iter().map(|r| r.cells.len()).max().unwrap_or(0) * MAX_WIDTH_PER_CELL;
+    let mut grid: Vec<Vec<Option<GridSlot>>> = vec![Vec::new(); rows.len()];
+    for (r, row) in rows.iter().enumerate() {
+        let mut col = 0;
+        for (i, cell) in row.cells.iter().enumerate() {
+            while grid[r].get(col).is_some_and(|slot| slot.is_some()) {
+                col += 1;
+            }
+            let colspan = cell.colspan.min(width_cap.saturating_sub(col)).max(1);
+            let rowspan = match cell.rowspan {
+                0 => rows.len() - r,
+                n => n.min(rows.len() - r),
+            };
+            for dr in 0..rowspan {
+                let line = &mut grid[r + dr];
+                if line.len() < col + colspan {
+                    line.resize(col + colspan, None);
+                }
+                for dc in 0..colspan {
+                    line[col + dc] = Some(GridSlot {
+                        cell: (r, i),
+                        offset: (dr, dc),
+                    });
+                }
+            }
+            col += colspan;
+        }
+    }
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    for line in &mut grid {
+        line.resize(width, None);
+    }
+    grid
+}
+
+/// Number of leading header rows. Without any, the first row acts as the
+/// header; a table made only of header rows keeps just the first one.
+fn header_row_count(rows: &[TableRow]) -> usize {
+    let leading = rows.iter().take_while(|r| r.header).count();
+    leading.clamp(1, rows.len().saturating_sub(1).max(1))
+}
+
+/// Whether GFM cannot represent the table: it holds nested tables or has a
+/// multi-row header.
+fn is_complex_table(tc: &TableCollector) -> bool {
+    tc.has_nested || header_row_count(&tc.rows) > 1
+}
+
 /// Render a completed table collector into a table string.
 ///
 /// When `plain` is true, produces tab-separated output; otherwise produces
-/// a pipe-delimited Markdown table.
-fn render_table(tc: &TableCollector, plain: bool) -> String {
-    // If no explicit headers (no <thead>), use first row as headers
-    let (headers, data_rows) = if tc.headers.is_empty() && !tc.rows.is_empty() {
-        (tc.rows[0].clone(), &tc.rows[1..])
-    } else {
-        (tc.headers.clone(), tc.rows.as_slice())
-    };
-
-    if headers.is_empty() {
+/// a pipe-delimited Markdown table. Complex tables (see [`is_complex_table`])
+/// are rendered according to `complex_tables`.
+fn render_table(tc: &TableCollector, complex_tables: ComplexTables, plain: bool) -> String {
+    if tc.rows.is_empty() {
         return String::new();
     }
+    if is_complex_table(tc) {
+        return match (complex_tables, plain) {
+            (ComplexTables::Html, false) => render_table_html(tc) + "\n",
+            (_, plain) => render_table_key_value(tc, plain),
+        };
+    }
 
-    let header_refs: Vec<&str> = headers.iter().map(|s| s.as_str()).collect();
-    let row_refs: Vec<Vec<&str>> = data_rows
+    let grid = layout_grid(&tc.rows);
+    if grid.first().is_none_or(|line| line.is_empty()) {
+        return String::new();
+    }
+    // Spanned positions stay blank; the value sits in the top-left slot.
+    let texts: Vec<Vec<&str>> = grid
         .iter()
-        .map(|row| row.iter().map(|s| s.as_str()).collect())
+        .map(|line| {
+            line.iter()
+                .map(|slot| match slot {
+                    Some(GridSlot {
+                        cell: (r, i),
+                        offset: (0, 0),
+                    }) => tc.rows[*r].cells[*i].content(plain),
+                    _ => "",
+                })
+                .collect()
+        })
         .collect();
+    let (headers, data_rows) = (&texts[0], &texts[1..]);
     if plain {
-        markdown::build_table_plain(&header_refs, &row_refs)
+        markdown::build_table_plain(headers, data_rows)
     } else {
-        markdown::build_table(&header_refs, &row_refs)
+        markdown::build_table(headers, data_rows)
+    }
+}
+
+/// Column labels built from the header rows (`Q1 / Jan` for stacked headers);
+/// unlabeled columns are called `Column N`.
+fn column_labels(tc: &TableCollector, grid: &[Vec<Option<GridSlot>>], plain: bool) -> Vec<String> {
+    let header_rows = header_row_count(&tc.rows);
+    let width = grid.first().map_or(0, Vec::len);
+    (0..width)
+        .map(|col| {
+            let mut parts: Vec<&str> = Vec::new();
+            for line in &grid[..header_rows] {
+                if let Some(GridSlot { cell: (r, i), .. }) = line[col] {
+                    let text = tc.rows[r].cells[i].content(plain);
+                    if !text.is_empty() && parts.last() != Some(&text) {
+                        parts.push(text);
+                    }
+                }
+            }
+            if parts.is_empty() {
+                format!("Column {}", col + 1)
+            } else {
+                parts.join(" / ")
+            }
+        })
+        .collect()
+}
+
+/// `header: value` pairs for each data row. Values of row-spanning cells are
+/// repeated in every row they cover; column-spanning cells appear once.
+fn key_value_rows(tc: &TableCollector, plain: bool) -> Vec<Vec<(String, String)>> {
+    let grid = layout_grid(&tc.rows);
+    let labels = column_labels(tc, &grid, plain);
+    grid[header_row_count(&tc.rows)..]
+        .iter()
+        .map(|line| {
+            line.iter()
+                .zip(&labels)
+                .filter_map(|(slot, label)| match slot {
+                    Some(GridSlot {
+                        cell: (r, i),
+                        offset: (_, 0),
+                    }) => {
+                        let text = tc.rows[*r].cells[*i].content(plain);
+                        (!text.is_empty()).then(|| (label.clone(), text.to_string()))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|pairs| !pairs.is_empty())
+        .collect()
+}
+
+/// One line per data row: `Name: Alice; City: Seoul`, as list items in Markdown.
+fn render_table_key_value(tc: &TableCollector, plain: bool) -> String {
+    let mut out = String::new();
+    for pairs in key_value_rows(tc, plain) {
+        let line = pairs
+            .iter()
+            .map(|(label, value)| format!("{label}: {value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if !plain {
+            out.push_str("- ");
+        }
+        out.push_str(&line.replace('\n', " "));
+        out.push('\n');
+    }
+    out
+}
+
+/// A nested table flattened to one line: `(a: 1, b: 2 / a: 3, b: 4)`.
+fn render_table_inline(tc: &TableCollector, plain: bool) -> String {
+    let rows: Vec<String> = key_value_rows(tc, plain)
+        .iter()
+        .map(|pairs| {
+            pairs
+                .iter()
+                .map(|(label, value)| format!("{label}: {value}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect();
+    format!("({})", rows.join(" / "))
+}
+
+/// Re-emit the table as HTML, keeping spans, header cells and nested tables.
+fn render_table_html(tc: &TableCollector) -> String {
+    let mut out = String::from("<table>\n");
+    for row in &tc.rows {
+        out.push_str("<tr>");
+        for cell in &row.cells {
+            let tag = if cell.header { "th" } else { "td" };
+            out.push('<');
+            out.push_str(tag);
+            if cell.colspan > 1 {
+                out.push_str(&format!(" colspan=\"{}\"", cell.colspan));
+            }
+            if cell.rowspan != 1 {
+                out.push_str(&format!(" rowspan=\"{}\"", cell.rowspan));
+            }
+            out.push('>');
+            out.push_str(&cell.html);
+            out.push_str(&format!("</{tag}>"));
+        }
+        out.push_str("</tr>\n");
     }
+    out.push_str("</table>\n");
+    out
+}
+
+/// Non-empty, trimmed lines of a finished cell or caption buffer.
+fn cell_lines(buffer: &str) -> Vec<&str> {
+    buffer
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 #[cfg(test)]
@@ -702,6 +2316,15 @@ mod tests {
             .unwrap()
     }
 
+    /// Convert with default options except for the given HTML options.
+    fn convert_html_with(html: &str, options: HtmlOptions) -> ConversionResult {
+        let options = ConversionOptions {
+            html: options,
+            ..Default::default()
+        };
+        HtmlConverter.convert(html.as_bytes(), &options).unwrap()
+    }
+
     #[test]
     fn test_html_supported_extensions() {
         let converter = HtmlConverter;
@@ -815,73 +2438,287 @@ mod tests {
     fn test_html_unordered_list() {
         let html = "<ul><li>Apple</li><li>Banana</li><li>Cherry</li></ul>";
         let result = convert_html(html);
-        assert!(result.markdown.contains("- Apple"));
-        assert!(result.markdown.contains("- Banana"));
-        assert!(result.markdown.contains("- Cherry"));
+        assert!(result.markdown.contains("- Apple"));
+        assert!(result.markdown.contains("- Banana"));
+        assert!(result.markdown.contains("- Cherry"));
+    }
+
+    #[test]
+    fn test_html_ordered_list() {
+        let html = "<ol><li>First</li><li>Second</li><li>Third</li></ol>";
+        let result = convert_html(html);
+        assert!(result.markdown.contains("1. First"));
+        assert!(result.markdown.contains("2. Second"));
+        assert!(result.markdown.contains("3. Third"));
+    }
+
+    #[test]
+    fn test_html_nested_list() {
+        let html = r#"<ul>
+            <li>Outer
+                <ul>
+                    <li>Inner A</li>
+                    <li>Inner B</li>
+                </ul>
+            </li>
+            <li>Outer 2</li>
+        </ul>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains("- Outer"));
+        assert!(result.markdown.contains("  - Inner A"));
+        assert!(result.markdown.contains("  - Inner B"));
+        assert!(result.markdown.contains("- Outer 2"));
+    }
+
+    #[test]
+    fn test_html_table_basic() {
+        let html = r#"<table>
+            <thead><tr><th>Name</th><th>Age</th></tr></thead>
+            <tbody>
+                <tr><td>Alice</td><td>30</td></tr>
+                <tr><td>Bob</td><td>25</td></tr>
+            </tbody>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains("| Name | Age |"));
+        assert!(result.markdown.contains("|---|---|"));
+        assert!(result.markdown.contains("| Alice | 30 |"));
+        assert!(result.markdown.contains("| Bob | 25 |"));
+    }
+
+    #[test]
+    fn test_html_table_no_thead() {
+        let html = r#"<table>
+            <tr><td>Name</td><td>Age</td></tr>
+            <tr><td>Alice</td><td>30</td></tr>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains("| Name | Age |"));
+        assert!(result.markdown.contains("| Alice | 30 |"));
+    }
+
+    #[test]
+    fn test_html_table_empty_cells() {
+        let html = r#"<table>
+            <thead><tr><th>A</th><th>B</th><th>C</th></tr></thead>
+            <tbody><tr><td>1</td><td></td><td>3</td></tr></tbody>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains("| 1 |  | 3 |"));
+    }
+
+    #[test]
+    fn test_html_table_colspan_rowspan_expanded() {
+        let html = r#"<table>
+            <tr><th>Region</th><th>Quarter</th><th>Sales</th></tr>
+            <tr><td rowspan="2">East</td><td>Q1</td><td>10</td></tr>
+            <tr><td>Q2</td><td>12</td></tr>
+            <tr><td colspan="2">Total</td><td>22</td></tr>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains("| Region | Quarter | Sales |"));
+        assert!(result.markdown.contains("| East | Q1 | 10 |"));
+        assert!(result.markdown.contains("|  | Q2 | 12 |"));
+        assert!(result.markdown.contains("| Total |  | 22 |"));
+        assert!(result.plain_text.contains("\tQ2\t12"));
+    }
+
+    #[test]
+    fn test_html_table_huge_colspan_clamped() {
+        let html = r#"<table>
+            <tr><th colspan="1000">Report</th></tr>
+            <tr><td>a</td><td>b</td></tr>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(
+            result
+                .markdown
+                .contains("| Report |  |  |  |\n|---|---|---|---|\n| a | b |  |  |"),
+            "{}",
+            result.markdown
+        );
+    }
+
+    #[test]
+    fn test_html_table_rowspan_zero_spans_to_end() {
+        let html = r#"<table>
+            <tr><th>A</th><th>B</th></tr>
+            <tr><td rowspan="0">x</td><td>1</td></tr>
+            <tr><td>2</td></tr>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains("| x | 1 |"));
+        assert!(result.markdown.contains("|  | 2 |"));
+    }
+
+    #[test]
+    fn test_html_table_multi_row_header_falls_back_to_html() {
+        let html = r#"<table>
+            <thead>
+                <tr><th rowspan="2">Item</th><th colspan="2">Q1</th></tr>
+                <tr><th>Jan</th><th>Feb</th></tr>
+            </thead>
+            <tbody><tr><td>Pens &amp; ink</td><td>3</td><td>4</td></tr></tbody>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(
+            result
+                .markdown
+                .contains(r#"<tr><th rowspan="2">Item</th><th colspan="2">Q1</th></tr>"#)
+        );
+        assert!(
+            result
+                .markdown
+                .contains("<tr><td>Pens &amp; ink</td><td>3</td><td>4</td></tr>")
+        );
+        assert!(!result.markdown.contains("|---|"));
+        assert!(
+            result
+                .plain_text
+                .contains("Item: Pens & ink; Q1 / Jan: 3; Q1 / Feb: 4")
+        );
+    }
+
+    #[test]
+    fn test_html_nested_table_html_fallback() {
+        let html = r#"<table>
+            <tr><th>Name</th><th>Details</th></tr>
+            <tr><td>Alice</td><td><table><tr><th>City</th></tr><tr><td>Seoul</td></tr></table></td></tr>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains(
+            "<tr><td>Alice</td><td><table>\n<tr><th>City</th></tr>\n<tr><td>Seoul</td></tr>\n</table></td></tr>"
+        ));
+        // The nested table is not merged into the outer table's rows
+        assert_eq!(result.markdown.matches("<tr>").count(), 4);
+        assert!(
+            result
+                .plain_text
+                .contains("Name: Alice; Details: (City: Seoul)")
+        );
+    }
+
+    #[test]
+    fn test_html_nested_table_key_value_layout() {
+        let html = r#"<table>
+            <tr><th>Name</th><th>Details</th></tr>
+            <tr><td>Alice</td><td><table><tr><th>City</th><th>Age</th></tr><tr><td>Seoul</td><td>30</td></tr></table></td></tr>
+            <tr><td>Bob</td><td></td></tr>
+        </table>"#;
+        let result = convert_html_with(
+            html,
+            HtmlOptions {
+                complex_tables: ComplexTables::KeyValue,
+                ..Default::default()
+            },
+        );
+        assert!(
+            result
+                .markdown
+                .contains("- Name: Alice; Details: (City: Seoul, Age: 30)\n- Name: Bob\n")
+        );
+        assert!(!result.markdown.contains("<table>"));
+    }
+
+    #[test]
+    fn test_html_table_cell_inline_markdown() {
+        let html = r#"<table>
+            <tr><th>Item</th><th>Link</th></tr>
+            <tr>
+                <td><strong>Bold</strong> and <em>italic</em> with <code>a|b</code></td>
+                <td><a href="https://example.com/docs">Docs</a> <img src="icon.png" alt="icon"></td>
+            </tr>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains(
+            "| **Bold** and *italic* with `a\\|b` | [Docs](https://example.com/docs) ![icon](icon.png) |"
+        ));
+        assert!(
+            result
+                .plain_text
+                .contains("Bold and italic with a|b\tDocs icon")
+        );
+        // Inline markers do not leak outside the table
+        assert!(!result.markdown.starts_with("**"));
+    }
+
+    #[test]
+    fn test_html_table_cell_line_breaks() {
+        let html = r#"<table>
+            <tr><th>Address</th></tr>
+            <tr><td>1 Main St<br>Springfield</td></tr>
+            <tr><td><p>First</p><p>Second</p></td></tr>
+        </table>"#;
+        let result = convert_html(html);
+        assert!(result.markdown.contains("| 1 Main St<br>Springfield |"));
+        assert!(result.markdown.contains("| First<br>Second |"));
+        assert!(result.plain_text.contains("1 Main St Springfield\n"));
     }
 
     #[test]
-    fn test_html_ordered_list() {
-        let html = "<ol><li>First</li><li>Second</li><li>Third</li></ol>";
+    fn test_html_table_cell_block_markup_flattened() {
+        let html = r#"<table>
+            <tr><th>Kind</th><th>Content</th></tr>
+            <tr><td><h2>Title</h2></td><td><ul><li>one</li><li>two</li></ul></td></tr>
+            <tr><td><pre>let x =
+    1;</pre></td><td><blockquote>quoted</blockquote></td></tr>
+        </table>"#;
         let result = convert_html(html);
-        assert!(result.markdown.contains("1. First"));
-        assert!(result.markdown.contains("2. Second"));
-        assert!(result.markdown.contains("3. Third"));
+        assert!(
+            result.markdown.contains("| Title | one<br>two |"),
+            "{}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("| `let x = 1;` | quoted |"));
+        assert!(!result.markdown.contains("> "));
+        assert!(!result.markdown.contains("##"));
+        assert!(!result.markdown.contains("- one"));
+        assert!(!result.markdown.contains("```"));
+        assert!(result.plain_text.contains("one two"));
     }
 
     #[test]
-    fn test_html_nested_list() {
-        let html = r#"<ul>
-            <li>Outer
-                <ul>
-                    <li>Inner A</li>
-                    <li>Inner B</li>
-                </ul>
-            </li>
-            <li>Outer 2</li>
-        </ul>"#;
+    fn test_html_summary_and_figcaption_block_markup_flattened() {
+        let html = r#"<details><summary><h2>Title</h2></summary><p>Body</p></details>
+            <figure><figcaption><p>Line one</p><p>Line two</p></figcaption></figure>"#;
         let result = convert_html(html);
-        assert!(result.markdown.contains("- Outer"));
-        assert!(result.markdown.contains("  - Inner A"));
-        assert!(result.markdown.contains("  - Inner B"));
-        assert!(result.markdown.contains("- Outer 2"));
+        assert!(
+            result.markdown.contains("**Title**\n"),
+            "{}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("*Line one Line two*\n"));
+        assert!(!result.markdown.contains('#'));
     }
 
     #[test]
-    fn test_html_table_basic() {
-        let html = r#"<table>
-            <thead><tr><th>Name</th><th>Age</th></tr></thead>
-            <tbody>
-                <tr><td>Alice</td><td>30</td></tr>
-                <tr><td>Bob</td><td>25</td></tr>
-            </tbody>
-        </table>"#;
-        let result = convert_html(html);
-        assert!(result.markdown.contains("| Name | Age |"));
-        assert!(result.markdown.contains("|---|---|"));
-        assert!(result.markdown.contains("| Alice | 30 |"));
-        assert!(result.markdown.contains("| Bob | 25 |"));
+    fn test_html_nested_wraps_each_closed() {
+        let result = convert_html("<summary>Outer <figcaption>inner</figcaption> tail</summary>");
+        assert!(
+            result.markdown.contains("**Outer *inner* tail**"),
+            "{}",
+            result.markdown
+        );
     }
 
     #[test]
-    fn test_html_table_no_thead() {
-        let html = r#"<table>
-            <tr><td>Name</td><td>Age</td></tr>
-            <tr><td>Alice</td><td>30</td></tr>
-        </table>"#;
+    fn test_html_table_cell_source_newlines_collapsed() {
+        let html = "<table><tr><th>A</th></tr><tr><td>\n  one\n  two\n</td></tr></table>";
         let result = convert_html(html);
-        assert!(result.markdown.contains("| Name | Age |"));
-        assert!(result.markdown.contains("| Alice | 30 |"));
+        assert!(result.markdown.contains("| one two |"));
     }
 
     #[test]
-    fn test_html_table_empty_cells() {
+    fn test_html_complex_table_keeps_inline_markup() {
         let html = r#"<table>
-            <thead><tr><th>A</th><th>B</th><th>C</th></tr></thead>
-            <tbody><tr><td>1</td><td></td><td>3</td></tr></tbody>
+            <tr><th colspan="2">Links</th></tr>
+            <tr><th>Name</th><th>URL</th></tr>
+            <tr><td><b>Home</b></td><td><a href="/?a=1&amp;b=2">site</a></td></tr>
         </table>"#;
         let result = convert_html(html);
-        assert!(result.markdown.contains("| 1 |  | 3 |"));
+        assert!(result.markdown.contains(
+            r#"<tr><td><strong>Home</strong></td><td><a href="/?a=1&amp;b=2">site</a></td></tr>"#
+        ));
     }
 
     #[test]
@@ -1100,4 +2937,731 @@ mod tests {
         assert!(result.markdown.contains("Another"));
         assert!(result.markdown.contains("Bold without close"));
     }
+
+    // -- Character encoding tests --
+
+    fn encode(html: &str, encoding: &'static encoding_rs::Encoding) -> Vec<u8> {
+        encoding.encode(html).0.into_owned()
+    }
+
+    #[test]
+    fn test_html_meta_charset_shift_jis() {
+        let html = r#"<html><head><meta charset="Shift_JIS"><title>日本語</title></head><body><p>こんにちは世界</p></body></html>"#;
+        let data = encode(html, encoding_rs::SHIFT_JIS);
+        let result = HtmlConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("こんにちは世界"));
+        assert_eq!(result.title.as_deref(), Some("日本語"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_html_http_equiv_euc_kr_and_windows_1251() {
+        let html = r#"<html><head><META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=euc-kr"></head><body><p>안녕하세요</p></body></html>"#;
+        let result = HtmlConverter
+            .convert(
+                &encode(html, encoding_rs::EUC_KR),
+                &ConversionOptions::default(),
+            )
+            .unwrap();
+        assert!(result.markdown.contains("안녕하세요"));
+
+        let html = r#"<html><head><meta http-equiv=content-type content='text/html;charset=windows-1251'></head><body><p>Привет</p></body></html>"#;
+        let result = HtmlConverter
+            .convert(
+                &encode(html, encoding_rs::WINDOWS_1251),
+                &ConversionOptions::default(),
+            )
+            .unwrap();
+        assert!(result.markdown.contains("Привет"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_html_gb2312_label() {
+        let html = r#"<meta charset="gb2312"><p>中文内容</p>"#;
+        let result = HtmlConverter
+            .convert(
+                &encode(html, encoding_rs::GBK),
+                &ConversionOptions::default(),
+            )
+            .unwrap();
+        assert!(result.markdown.contains("中文内容"));
+    }
+
+    #[test]
+    fn test_html_utf16_bom() {
+        let mut data = vec![0xFF, 0xFE];
+        for unit in "<p>Grüße</p>".encode_utf16() {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+        let result = HtmlConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("Grüße"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_html_undeclared_non_utf8_falls_back_with_warning() {
+        let data = b"<p>caf\xe9</p>";
+        let result = HtmlConverter
+            .convert(data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("café"));
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].message.contains("windows-1252"));
+    }
+
+    #[test]
+    fn test_html_utf8_without_declaration_has_no_warning() {
+        let result = convert_html("\u{FEFF}<p>naïve</p>");
+        assert!(result.markdown.contains("naïve"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_sniff_meta_charset_variants() {
+        assert_eq!(
+            sniff_meta_charset(br#"<meta name="x"><meta charset=utf-8>"#),
+            Some(encoding_rs::UTF_8)
+        );
+        assert_eq!(
+            sniff_meta_charset(br#"<meta charset="utf-16le">"#),
+            Some(encoding_rs::UTF_8)
+        );
+        assert_eq!(sniff_meta_charset(b"<p>no meta</p>"), None);
+        assert_eq!(
+            charset_from_content_type(r#"text/html; charset="Big5""#).as_deref(),
+            Some("Big5")
+        );
+    }
+
+    const NEWS_PAGE: &str = r#"<html><head><title>Rust 2024 Released</title></head><body>
+        <header><a href="/">Daily News</a><nav><a href="/world">World</a> <a href="/tech">Tech</a></nav></header>
+        <div class="cookie-banner"><p>We use cookies to improve your experience, please accept them all.</p></div>
+        <main>
+          <article>
+            <h1>Rust 2024 Released</h1>
+            <p>The Rust team announced the 2024 edition today, bringing async closures, let chains, and more.</p>
+            <p>Upgrading is mostly automatic, thanks to cargo fix, and most crates compile unchanged.</p>
+            <aside><p>Related: Rust 2021 retrospective, a look back at the previous edition.</p></aside>
+          </article>
+        </main>
+        <footer><p>Copyright Daily News, all rights reserved, since the year 1999.</p></footer>
+    </body></html>"#;
+
+    #[test]
+    fn test_html_readability_keeps_main_article_only() {
+        let result = convert_html_with(
+            NEWS_PAGE,
+            HtmlOptions {
+                readability: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(result.title.as_deref(), Some("Rust 2024 Released"));
+        assert!(result.markdown.starts_with("# Rust 2024 Released\n"));
+        assert!(result.markdown.contains("async closures"));
+        assert!(result.markdown.contains("cargo fix"));
+        assert!(!result.markdown.contains("World"));
+        assert!(!result.markdown.contains("cookies"));
+        assert!(!result.markdown.contains("retrospective"));
+        assert!(!result.markdown.contains("Copyright"));
+        assert!(!result.plain_text.contains("Copyright"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_html_readability_off_by_default() {
+        let result = convert_html(NEWS_PAGE);
+        assert!(result.markdown.contains("World"));
+        assert!(result.markdown.contains("Copyright"));
+    }
+
+    #[test]
+    fn test_html_readability_scores_without_semantic_tags() {
+        let html = r#"<body>
+            <div id="top"><p><a href="/a">Home page link</a> <a href="/b">Archive of old posts</a> <a href="/c">About</a></p></div>
+            <div id="story">
+              <p>First paragraph of the story, with enough text to count as content.</p>
+              <p>Second paragraph of the story, which continues the narrative further.</p>
+            </div>
+            <div class="sidebar"><p>Popular this week, trending, most shared, top rated articles.</p></div>
+        </body>"#;
+        let result = convert_html_with(
+            html,
+            HtmlOptions {
+                readability: true,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("First paragraph"));
+        assert!(result.markdown.contains("Second paragraph"));
+        assert!(!result.markdown.contains("Archive"));
+        assert!(!result.markdown.contains("Popular"));
+    }
+
+    #[test]
+    fn test_html_readability_keeps_sibling_content_blocks() {
+        let html = r#"<body><div class="wrapper">
+            <div><p>The introduction explains the problem, the context, and the goal.</p></div>
+            <div>
+              <p>The first section body goes into detail about the approach taken.</p>
+              <p>The second section body, with results, caveats, and next steps.</p>
+            </div>
+        </div></body>"#;
+        let result = convert_html_with(
+            html,
+            HtmlOptions {
+                readability: true,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("introduction"));
+        assert!(result.markdown.contains("first section"));
+        assert!(result.markdown.contains("second section"));
+    }
+
+    #[test]
+    fn test_html_readability_without_paragraphs_converts_everything() {
+        let result = convert_html_with(
+            "<ul><li>Apple</li><li>Banana</li></ul>",
+            HtmlOptions {
+                readability: true,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("- Apple"));
+        assert!(result.markdown.contains("- Banana"));
+    }
+
+    const RELATIVE_LINKS: &str = r#"<p><a href="../docs/guide.html">Guide</a>, <a href="/about">About</a>,
+        <a href="https://other.example/x">Other</a> and <a href="../docs/guide.html">again</a>.</p>
+        <p><img src="img/logo.png" alt="Logo"></p>"#;
+
+    #[test]
+    fn test_html_links_resolved_against_base_url() {
+        let result = convert_html_with(
+            RELATIVE_LINKS,
+            HtmlOptions {
+                base_url: Some("https://example.com/blog/post/index.html".to_string()),
+                links: LinkPolicy::Absolute,
+                ..Default::default()
+            },
+        );
+        assert!(
+            result
+                .markdown
+                .contains("[Guide](https://example.com/blog/docs/guide.html)")
+        );
+        assert!(
+            result
+                .markdown
+                .contains("[About](https://example.com/about)")
+        );
+        assert!(result.markdown.contains("[Other](https://other.example/x)"));
+        assert!(
+            result
+                .markdown
+                .contains("![Logo](https://example.com/blog/post/img/logo.png)")
+        );
+    }
+
+    #[test]
+    fn test_html_links_relative_without_base_unchanged() {
+        let result = convert_html(RELATIVE_LINKS);
+        assert!(result.markdown.contains("[Guide](../docs/guide.html)"));
+        assert!(result.markdown.contains("![Logo](img/logo.png)"));
+    }
+
+    #[test]
+    fn test_html_base_href_honoured() {
+        let html = r#"<html><head><base href="https://cdn.example.org/site/"></head>
+            <body><a href="page.html">Page</a></body></html>"#;
+        let result = convert_html(html);
+        assert!(
+            result
+                .markdown
+                .contains("[Page](https://cdn.example.org/site/page.html)")
+        );
+    }
+
+    #[test]
+    fn test_html_relative_base_href_resolved_against_base_url() {
+        let html = r#"<head><base href="/v2/"></head><a href="start">Start</a>"#;
+        let result = convert_html_with(
+            html,
+            HtmlOptions {
+                base_url: Some("https://example.com/a/b".to_string()),
+                links: LinkPolicy::Absolute,
+                ..Default::default()
+            },
+        );
+        assert!(
+            result
+                .markdown
+                .contains("[Start](https://example.com/v2/start)")
+        );
+    }
+
+    #[test]
+    fn test_html_link_policy_keep() {
+        let result = convert_html_with(
+            RELATIVE_LINKS,
+            HtmlOptions {
+                base_url: Some("https://example.com/blog/post/".to_string()),
+                links: LinkPolicy::Keep,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("[Guide](../docs/guide.html)"));
+        assert!(result.markdown.contains("![Logo](img/logo.png)"));
+    }
+
+    #[test]
+    fn test_html_link_policy_text() {
+        let result = convert_html_with(
+            RELATIVE_LINKS,
+            HtmlOptions {
+                base_url: None,
+                links: LinkPolicy::Text,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("Guide, About,"));
+        assert!(!result.markdown.contains("[Guide]"));
+        assert!(!result.markdown.contains("/about"));
+        // Images are not links and keep their source
+        assert!(result.markdown.contains("![Logo](img/logo.png)"));
+    }
+
+    #[test]
+    fn test_html_link_policy_reference() {
+        let result = convert_html_with(
+            RELATIVE_LINKS,
+            HtmlOptions {
+                base_url: Some("https://example.com/blog/post/".to_string()),
+                links: LinkPolicy::Reference,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("[Guide][1], [About][2],"));
+        assert!(result.markdown.contains("[Other][3] and [again][1]."));
+        assert!(result.markdown.ends_with(
+            "[1]: https://example.com/blog/docs/guide.html\n\
+             [2]: https://example.com/about\n\
+             [3]: https://other.example/x\n"
+        ));
+        assert!(!result.plain_text.contains("[1]"));
+    }
+
+    #[test]
+    fn test_html_invalid_base_url_warns() {
+        let result = convert_html_with(
+            RELATIVE_LINKS,
+            HtmlOptions {
+                base_url: Some("not a url".to_string()),
+                links: LinkPolicy::Absolute,
+                ..Default::default()
+            },
+        );
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].message.contains("base_url"));
+        assert!(result.markdown.contains("[Guide](../docs/guide.html)"));
+    }
+
+    const PNG_BYTES: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 1, 2, 3];
+
+    fn png_data_uri() -> String {
+        format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(PNG_BYTES)
+        )
+    }
+
+    struct MockDescriber;
+
+    impl crate::converter::ImageDescriber for MockDescriber {
+        fn describe(
+            &self,
+            image_bytes: &[u8],
+            mime_type: &str,
+            _prompt: &str,
+        ) -> Result<String, ConvertError> {
+            Ok(format!("{} bytes of {mime_type}", image_bytes.len()))
+        }
+    }
+
+    #[test]
+    fn test_html_data_uri_image_extracted() {
+        let html = format!(r#"<p><img src="{}" alt="Dot"></p>"#, png_data_uri());
+        let options = ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        };
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert!(result.markdown.contains("![Dot](image_1.png)"));
+        assert!(!result.markdown.contains("base64"));
+        assert_eq!(result.plain_text.trim(), "Dot");
+        assert_eq!(
+            result.images,
+            vec![("image_1.png".to_string(), PNG_BYTES.to_vec())]
+        );
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_html_data_uri_image_described() {
+        let html = format!(
+            r#"<img src="{}" alt="Dot"><img src="https://example.com/x.png" alt="Remote">"#,
+            png_data_uri()
+        );
+        let options = ConversionOptions {
+            image_describer: Some(std::sync::Arc::new(MockDescriber)),
+            ..Default::default()
+        };
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("![11 bytes of image/png](image_1.png)")
+        );
+        assert!(
+            result
+                .markdown
+                .contains("![Remote](https://example.com/x.png)")
+        );
+        assert!(result.images.is_empty());
+    }
+
+    #[test]
+    fn test_html_data_uri_not_decoded_without_consumer() {
+        let html = format!(r#"<img src="{}" alt="Dot">"#, png_data_uri());
+        let result = convert_html(&html);
+        assert!(result.markdown.contains("![Dot](image_1.png)"));
+        assert!(result.images.is_empty());
+    }
+
+    #[test]
+    fn test_html_invalid_data_uri_warns() {
+        let html = r#"<img src="data:image/png;base64,***" alt="Broken">"#;
+        let options = ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        };
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert!(result.markdown.contains("![Broken](image_1.png)"));
+        assert!(result.images.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::MalformedSegment);
+    }
+
+    #[test]
+    fn test_html_data_uri_image_budget() {
+        let html = format!(
+            r#"<img src="{0}" alt="A"><img src="{0}" alt="B">"#,
+            png_data_uri()
+        );
+        let options = ConversionOptions {
+            extract_images: true,
+            max_total_image_bytes: PNG_BYTES.len() + 1,
+            ..Default::default()
+        };
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert_eq!(result.images.len(), 1);
+        assert!(result.markdown.contains("![A](image_1.png)"));
+        assert!(result.markdown.contains("![B](image_2.png)"));
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code == WarningCode::ResourceLimitReached)
+        );
+    }
+
+    #[test]
+    fn test_html_over_budget_image_does_not_stop_later_images() {
+        let large = format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(PNG_BYTES.repeat(2))
+        );
+        let html = format!(
+            r#"<img src="{large}" alt="Large"><img src="{}" alt="Small">"#,
+            png_data_uri()
+        );
+        let options = ConversionOptions {
+            extract_images: true,
+            max_total_image_bytes: PNG_BYTES.len() + 1,
+            ..Default::default()
+        };
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert_eq!(
+            result.images,
+            vec![("image_2.png".to_string(), PNG_BYTES.to_vec())]
+        );
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::ResourceLimitReached);
+    }
+
+    #[test]
+    fn test_decode_data_uri_variants() {
+        assert_eq!(
+            decode_data_uri("data:image/svg+xml,%3Csvg%2F%3E").as_deref(),
+            Some(b"<svg/>".as_slice())
+        );
+        assert_eq!(
+            decode_data_uri("data:image/gif;base64,R0lG\n ODlh").as_deref(),
+            Some(b"GIF89a".as_slice())
+        );
+        assert_eq!(data_uri_image_mime("data:text/plain,hi"), None);
+        assert_eq!(
+            data_uri_image_mime("DATA:Image/JPEG;base64,xx").as_deref(),
+            Some("image/jpeg")
+        );
+    }
+
+    #[test]
+    fn test_local_image_path_rejects_escapes() {
+        assert_eq!(
+            local_image_path("img/a%20b.png?v=2"),
+            Some(PathBuf::from("img/a b.png"))
+        );
+        assert_eq!(local_image_path("./a.png"), Some(PathBuf::from("./a.png")));
+        assert_eq!(local_image_path("../secret.png"), None);
+        assert_eq!(local_image_path("img/../../secret.png"), None);
+        assert_eq!(local_image_path("/etc/passwd"), None);
+        assert_eq!(local_image_path("//cdn.example.com/a.png"), None);
+        assert_eq!(local_image_path("https://example.com/a.png"), None);
+        assert_eq!(local_image_path("C:/Windows/a.png"), None);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_html_local_images_read_from_image_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("img")).unwrap();
+        std::fs::write(dir.path().join("img").join("chart.png"), PNG_BYTES).unwrap();
+        std::fs::write(dir.path().join("outside.png"), PNG_BYTES).unwrap();
+
+        let html = r#"<img src="img/chart.png" alt="Chart">
+            <img src="img/missing.png" alt="Missing">
+            <img src="img/../../outside.png" alt="Escape">"#;
+        let options = ConversionOptions {
+            extract_images: true,
+            html: HtmlOptions {
+                image_dir: Some(dir.path().join("img").join("..")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert!(result.markdown.contains("![Chart](img/chart.png)"));
+        assert!(result.markdown.contains("![Missing](img/missing.png)"));
+        assert!(result.markdown.contains("![Escape](img/../../outside.png)"));
+        assert_eq!(
+            result.images,
+            vec![("img/chart.png".to_string(), PNG_BYTES.to_vec())]
+        );
+        assert!(result.warnings.is_empty());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_html_local_images_named_like_their_links() {
+        let dir = tempfile::tempdir().unwrap();
+        for sub in ["a", "b"] {
+            std::fs::create_dir(dir.path().join(sub)).unwrap();
+            std::fs::write(dir.path().join(sub).join("logo.png"), PNG_BYTES).unwrap();
+        }
+        let html = r#"<img src="a/logo.png" alt="A"><img src="b/logo.png" alt="B">
+            <img src="a/logo.png" alt="A again">"#;
+        let options = ConversionOptions {
+            extract_images: true,
+            html: HtmlOptions {
+                image_dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        let names: Vec<&str> = result.images.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["a/logo.png", "b/logo.png"]);
+        assert!(result.markdown.contains("![B](b/logo.png)"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_html_local_image_over_budget_not_read() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.png"), PNG_BYTES).unwrap();
+        std::fs::write(dir.path().join("b.png"), PNG_BYTES).unwrap();
+        let options = ConversionOptions {
+            extract_images: true,
+            max_total_image_bytes: PNG_BYTES.len() + 1,
+            html: HtmlOptions {
+                image_dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let html = r#"<img src="a.png" alt="A"><img src="b.png" alt="B">"#;
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert_eq!(result.images.len(), 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::ResourceLimitReached);
+        assert_eq!(result.warnings[0].location.as_deref(), Some("b.png"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_html_local_image_symlink_out_of_image_dir_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.png"), PNG_BYTES).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.png"),
+            dir.path().join("link.png"),
+        )
+        .unwrap();
+        let options = ConversionOptions {
+            extract_images: true,
+            html: HtmlOptions {
+                image_dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let html = r#"<img src="link.png" alt="Link">"#;
+        let result = HtmlConverter.convert(html.as_bytes(), &options).unwrap();
+        assert!(result.images.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::SkippedElement);
+    }
+
+    const META_PAGE: &str = r#"<html><head>
+        <title>Release notes</title>
+        <meta name="description" content="What changed in 2.0">
+        <meta name="Author" content="Jane &quot;JD&quot; Doe">
+        <meta name="keywords" content="release, changelog, ,2.0">
+        <meta property="og:title" content="Release 2.0">
+        <meta property="og:image" content="https://example.com/cover.png">
+        <meta property="article:published_time" content="2024-05-01T09:00:00Z">
+        <link rel="canonical" href="/blog/release-2">
+        <script type="application/ld+json">
+          { "@type": "Article", "headline": "Release 2.0" }
+        </script>
+        </head><body><h1>Release notes</h1><p>Body text.</p></body></html>"#;
+
+    const META_BASE_URL: &str = "https://example.com/news/";
+
+    #[test]
+    fn test_html_metadata_collected() {
+        let result = convert_html_with(
+            META_PAGE,
+            HtmlOptions {
+                base_url: Some(META_BASE_URL.to_string()),
+                front_matter: false,
+                ..Default::default()
+            },
+        );
+        let meta = &result.metadata;
+        assert_eq!(meta.description.as_deref(), Some("What changed in 2.0"));
+        assert_eq!(meta.author.as_deref(), Some("Jane \"JD\" Doe"));
+        assert_eq!(meta.keywords, vec!["release", "changelog", "2.0"]);
+        assert_eq!(meta.published.as_deref(), Some("2024-05-01T09:00:00Z"));
+        assert_eq!(
+            meta.canonical_url.as_deref(),
+            Some("https://example.com/blog/release-2")
+        );
+        assert_eq!(meta.open_graph["og:title"], "Release 2.0");
+        assert_eq!(meta.open_graph.len(), 2);
+        assert_eq!(
+            meta.json_ld,
+            vec![r#"{"@type":"Article","headline":"Release 2.0"}"#]
+        );
+        assert!(result.markdown.starts_with("# Release notes"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_html_metadata_empty_without_head_tags() {
+        let result = convert_html_with(
+            "<p>Hello</p>",
+            HtmlOptions {
+                base_url: Some(META_BASE_URL.to_string()),
+                front_matter: false,
+                ..Default::default()
+            },
+        );
+        assert!(result.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_html_front_matter() {
+        let result = convert_html_with(
+            META_PAGE,
+            HtmlOptions {
+                base_url: Some(META_BASE_URL.to_string()),
+                front_matter: true,
+                ..Default::default()
+            },
+        );
+        let expected = r#"---
+title: "Release notes"
+description: "What changed in 2.0"
+author: "Jane \"JD\" Doe"
+published: "2024-05-01T09:00:00Z"
+canonical_url: "https://example.com/blog/release-2"
+keywords:
+  - "release"
+  - "changelog"
+  - "2.0"
+open_graph:
+  "og:image": "https://example.com/cover.png"
+  "og:title": "Release 2.0"
+json_ld:
+  - {"@type":"Article","headline":"Release 2.0"}
+---
+
+# Release notes"#;
+        assert!(
+            result.markdown.starts_with(expected),
+            "got: {}",
+            result.markdown
+        );
+        assert!(result.plain_text.starts_with("Release notes"));
+    }
+
+    #[test]
+    fn test_html_front_matter_omitted_when_nothing_to_write() {
+        let result = convert_html_with(
+            "<p>Hello</p>",
+            HtmlOptions {
+                base_url: Some(META_BASE_URL.to_string()),
+                front_matter: true,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.starts_with("Hello"));
+    }
+
+    #[test]
+    fn test_html_invalid_json_ld_warns() {
+        let html = r#"<script type="application/ld+json">{ "broken": </script><p>Text</p>"#;
+        let result = convert_html_with(
+            html,
+            HtmlOptions {
+                base_url: Some(META_BASE_URL.to_string()),
+                front_matter: false,
+                ..Default::default()
+            },
+        );
+        assert!(result.metadata.json_ld.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::MalformedSegment);
+        assert!(result.markdown.contains("Text"));
+        assert!(!result.markdown.contains("broken"));
+    }
 }
@@ -21,7 +21,7 @@ pub struct ImageConverter;
 ///
 /// Returns the extension without a dot (e.g., `"png"`, `"jpg"`).
 /// Falls back to an empty string if the MIME type is unrecognized.
-fn ext_from_mime(mime: &str) -> &'static str {
+pub(crate) fn ext_from_mime(mime: &str) -> &'static str {
     match mime {
         "image/png" => "png",
         "image/jpeg" => "jpg",
@@ -0,0 +1,591 @@
+//! MHTML (saved web page) to Markdown converter.
+//!
+//! An MHTML file (`.mht`, `.mhtml`) is a MIME `multipart/related` message whose
+//! root part is the page's HTML and whose other parts hold its resources.
+//! Parts are decoded (base64, quoted-printable), the root HTML is converted by
+//! [`HtmlConverter`], and embedded images are matched to `<img src>` values by
+//! `Content-Location` or `Content-ID` and handled as described in
+//! [`html`](super::html).
+
+use crate::converter::html::{EmbeddedResource, HtmlConverter, decode_html};
+use crate::converter::ooxml_utils::{PendingImageResolution, resolve_image_placeholders};
+use crate::converter::{
+    ConversionOptions, ConversionResult, ConversionWarning, Converter, WarningCode,
+};
+use crate::error::ConvertError;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use base64::Engine;
+use url::Url;
+
+/// Converts MHTML web archives to Markdown.
+pub struct MhtmlConverter;
+
+/// Maximum nesting depth of multipart bodies.
+const MAX_MULTIPART_DEPTH: usize = 8;
+
+// ---- Internal conversion (parse + image extraction, no resolution) ----
+
+impl MhtmlConverter {
+    /// Convert an MHTML archive to Markdown with image placeholders, collecting
+    /// the bytes of embedded images without resolving descriptions.
+    ///
+    /// Returns the conversion result (with unresolved placeholders in markdown)
+    /// and pending image data for later resolution (sync or async).
+    pub(crate) fn convert_inner(
+        &self,
+        data: &[u8],
+        options: &ConversionOptions,
+    ) -> Result<(ConversionResult, PendingImageResolution), ConvertError> {
+        let mut warnings = Vec::new();
+        let (headers, body) = split_headers(data);
+        let start = content_type_param(&headers, "start");
+        let mut parts = Vec::new();
+        collect_parts(headers, body, 0, &mut parts, &mut warnings);
+
+        let root_index =
+            find_root(start.as_deref(), &parts).ok_or_else(|| ConvertError::MalformedDocument {
+                reason: "MHTML archive has no text/html part".to_string(),
+            })?;
+        let root = parts.remove(root_index);
+
+        let mut resources: HashMap<String, EmbeddedResource> = HashMap::new();
+        for part in parts {
+            let content_type = part.content_type();
+            if !content_type.starts_with("image/") {
+                continue;
+            }
+            let keys: Vec<String> = [part.location(), part.content_id()]
+                .into_iter()
+                .flatten()
+                .collect();
+            let data = Rc::new(part.body);
+            for key in keys {
+                resources.entry(key).or_insert_with(|| EmbeddedResource {
+                    content_type: content_type.clone(),
+                    data: Rc::clone(&data),
+                });
+            }
+        }
+
+        let transport = part_charset(&root)
+            .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()));
+        let (text, encoding_warning) = decode_html(&root.body, transport);
+        warnings.extend(encoding_warning);
+
+        // Relative links and image sources resolve against the saved page's URL
+        let options = match root.location() {
+            Some(location) if options.html.base_url.is_none() && Url::parse(&location).is_ok() => {
+                let mut options = options.clone();
+                options.html.base_url = Some(location);
+                Cow::Owned(options)
+            }
+            _ => Cow::Borrowed(options),
+        };
+
+        HtmlConverter.convert_document(&text, warnings, &options, &resources)
+    }
+}
+
+// ---- Converter trait impl ----
+
+impl Converter for MhtmlConverter {
+    fn supported_extensions(&self) -> &[&str] {
+        &["mhtml", "mht"]
+    }
+
+    fn convert(
+        &self,
+        data: &[u8],
+        options: &ConversionOptions,
+    ) -> Result<ConversionResult, ConvertError> {
+        let (mut result, pending) = self.convert_inner(data, options)?;
+        resolve_image_placeholders(
+            &mut result.markdown,
+            &mut result.plain_text,
+            &pending.infos,
+            &pending.bytes,
+            options.image_describer.as_deref(),
+            &mut result.warnings,
+        );
+        Ok(result)
+    }
+}
+
+// ---- MIME parsing ----
+
+/// A leaf part of the archive with its transfer encoding already removed.
+struct MimePart {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl MimePart {
+    fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+
+    /// Lowercased media type, `text/plain` if none is declared.
+    fn content_type(&self) -> String {
+        self.header("Content-Type")
+            .map(|value| parse_content_type(value).0)
+            .unwrap_or_else(|| "text/plain".to_string())
+    }
+
+    /// `Content-Location`, normalized like the URLs it is compared against.
+    fn location(&self) -> Option<String> {
+        let location = self.header("Content-Location")?.trim();
+        if location.is_empty() {
+            return None;
+        }
+        Some(Url::parse(location).map_or_else(|_| location.to_string(), String::from))
+    }
+
+    /// `Content-ID` as the `cid:` URL that refers to it.
+    fn content_id(&self) -> Option<String> {
+        let id = self.header("Content-ID")?.trim();
+        let id = id.trim_start_matches('<').trim_end_matches('>').trim();
+        (!id.is_empty()).then(|| format!("cid:{id}"))
+    }
+}
+
+/// Value of the first header called `name` (case-insensitive).
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Split a MIME entity into its unfolded headers and its body.
+fn split_headers(data: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let end = data[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(data.len(), |i| pos + i);
+        let line = data[pos..end]
+            .strip_suffix(b"\r")
+            .unwrap_or(&data[pos..end]);
+        pos = (end + 1).min(data.len());
+        if line.is_empty() {
+            break;
+        }
+        let line = String::from_utf8_lossy(line);
+        if line.starts_with([' ', '\t']) {
+            // Folded continuation of the previous header
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, &data[pos..])
+}
+
+/// Lowercased media type and parameters of a `Content-Type` value.
+fn parse_content_type(value: &str) -> (String, Vec<(String, String)>) {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in value.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    let mut fields = fields.into_iter();
+    let mime = fields
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let params = fields
+        .filter_map(|field| {
+            let (key, value) = field.split_once('=')?;
+            Some((key.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect();
+    (mime, params)
+}
+
+fn content_type_param(headers: &[(String, String)], name: &str) -> Option<String> {
+    let (_, params) = parse_content_type(header(headers, "Content-Type")?);
+    params
+        .into_iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value)
+}
+
+fn part_charset(part: &MimePart) -> Option<String> {
+    content_type_param(&part.headers, "charset")
+}
+
+/// Flatten the leaf parts of a MIME entity into `parts`, decoding their
+/// transfer encoding. Nested multipart bodies are descended into.
+fn collect_parts(
+    headers: Vec<(String, String)>,
+    body: &[u8],
+    depth: usize,
+    parts: &mut Vec<MimePart>,
+    warnings: &mut Vec<ConversionWarning>,
+) {
+    let is_multipart = header(&headers, "Content-Type")
+        .is_some_and(|value| parse_content_type(value).0.starts_with("multipart/"));
+    if is_multipart
+        && depth < MAX_MULTIPART_DEPTH
+        && let Some(boundary) = content_type_param(&headers, "boundary")
+    {
+        let (bodies, closed) = split_multipart(body, &boundary);
+        if !closed {
+            warnings.push(ConversionWarning {
+                code: WarningCode::MalformedSegment,
+                message: "MHTML archive is truncated (missing closing boundary)".to_string(),
+                location: None,
+            });
+        }
+        for part in bodies {
+            let (part_headers, part_body) = split_headers(part);
+            collect_parts(part_headers, part_body, depth + 1, parts, warnings);
+        }
+        return;
+    }
+
+    let encoding = header(&headers, "Content-Transfer-Encoding")
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let body = match encoding.as_str() {
+        "base64" => {
+            let compact: Vec<u8> = body
+                .iter()
+                .copied()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            match base64::engine::general_purpose::STANDARD
+                .decode(&compact)
+                .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(&compact))
+            {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warnings.push(ConversionWarning {
+                        code: WarningCode::MalformedSegment,
+                        message: format!("skipping MHTML part with invalid base64: {e}"),
+                        location: header(&headers, "Content-Location").map(str::to_string),
+                    });
+                    return;
+                }
+            }
+        }
+        "quoted-printable" => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    };
+    parts.push(MimePart { headers, body });
+}
+
+/// Split a multipart body at its boundary lines. Returns the part bodies and
+/// whether the closing boundary was found.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> (Vec<&'a [u8]>, bool) {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut pos = 0;
+    while pos < body.len() {
+        let end = body[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(body.len(), |i| pos + i);
+        let line = body[pos..end]
+            .strip_suffix(b"\r")
+            .unwrap_or(&body[pos..end]);
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if let Some(s) = start {
+                // The line break before a boundary belongs to the boundary
+                let part = &body[s..pos];
+                let part = part.strip_suffix(b"\n").unwrap_or(part);
+                parts.push(part.strip_suffix(b"\r").unwrap_or(part));
+            }
+            if rest.starts_with(b"--") {
+                return (parts, true);
+            }
+            start = Some((end + 1).min(body.len()));
+        }
+        pos = end + 1;
+    }
+    if let Some(s) = start
+        && s < body.len()
+    {
+        parts.push(&body[s..]);
+    }
+    (parts, false)
+}
+
+/// Decode a quoted-printable body. Soft line breaks (`=` at the end of a
+/// line) are removed; invalid escapes are kept literally.
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'=' {
+            let rest = &input[i + 1..];
+            if rest.starts_with(b"\r\n") {
+                i += 3;
+                continue;
+            }
+            if rest.starts_with(b"\n") {
+                i += 2;
+                continue;
+            }
+            if let Some(hex) = rest.get(..2)
+                && hex.iter().all(u8::is_ascii_hexdigit)
+                && let Ok(b) = u8::from_str_radix(&String::from_utf8_lossy(hex), 16)
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(input[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Index of the root HTML part: the part named by the `start` parameter of
+/// the top-level `Content-Type`, or else the first `text/html` part.
+fn find_root(start: Option<&str>, parts: &[MimePart]) -> Option<usize> {
+    let start = start.map(|id| {
+        format!(
+            "cid:{}",
+            id.trim().trim_start_matches('<').trim_end_matches('>')
+        )
+    });
+    if let Some(start) = start
+        && let Some(index) = parts
+            .iter()
+            .position(|part| part.content_id().as_deref() == Some(start.as_str()))
+    {
+        return Some(index);
+    }
+    parts
+        .iter()
+        .position(|part| part.content_type() == "text/html")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_BYTES: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 1, 2, 3];
+
+    /// Build a Blink-style archive: a quoted-printable HTML root followed by
+    /// a base64 PNG part.
+    fn archive(html: &str, image_headers: &str) -> Vec<u8> {
+        let png = base64::engine::general_purpose::STANDARD.encode(PNG_BYTES);
+        format!(
+            "From: <Saved by Blink>\r\n\
+             Subject: Saved page\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: multipart/related;\r\n\
+             \ttype=\"text/html\";\r\n\
+             \tboundary=\"----MultipartBoundary--abc\"\r\n\
+             \r\n\
+             ------MultipartBoundary--abc\r\n\
+             Content-Type: text/html\r\n\
+             Content-ID: <frame-1@mhtml.blink>\r\n\
+             Content-Transfer-Encoding: quoted-printable\r\n\
+             Content-Location: https://example.com/blog/post.html\r\n\
+             \r\n\
+             {html}\r\n\
+             ------MultipartBoundary--abc\r\n\
+             Content-Type: image/png\r\n\
+             Content-Transfer-Encoding: base64\r\n\
+             {image_headers}\r\n\
+             \r\n\
+             {png}\r\n\
+             ------MultipartBoundary--abc--\r\n"
+        )
+        .into_bytes()
+    }
+
+    const PAGE: &str = "<html><head><title>Saved</title></head><body><h1>Post</h1>=\r\n\
+        <p>Caf=C3=A9 <a href=3D\"../about\">about</a></p>\r\n\
+        <img src=3D\"img/chart.png\" alt=3D\"Chart\"></body></html>";
+
+    fn extract_options() -> ConversionOptions {
+        ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mhtml_root_html_converted() {
+        let data = archive(
+            PAGE,
+            "Content-Location: https://example.com/blog/img/chart.png",
+        );
+        let result = MhtmlConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert_eq!(result.title.as_deref(), Some("Saved"));
+        assert!(result.markdown.contains("# Post"));
+        assert!(result.markdown.contains("Café"));
+        assert!(
+            result
+                .markdown
+                .contains("[about](https://example.com/about)")
+        );
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    }
+
+    #[test]
+    fn test_mhtml_image_by_content_location() {
+        let data = archive(
+            PAGE,
+            "Content-Location: https://example.com/blog/img/chart.png",
+        );
+        let result = MhtmlConverter.convert(&data, &extract_options()).unwrap();
+        assert!(result.markdown.contains("![Chart](chart.png)"));
+        assert_eq!(
+            result.images,
+            vec![("chart.png".to_string(), PNG_BYTES.to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_mhtml_image_over_budget_not_extracted() {
+        let data = archive(
+            PAGE,
+            "Content-Location: https://example.com/blog/img/chart.png",
+        );
+        let options = ConversionOptions {
+            max_total_image_bytes: PNG_BYTES.len() - 1,
+            ..extract_options()
+        };
+        let result = MhtmlConverter.convert(&data, &options).unwrap();
+        assert!(result.images.is_empty());
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code == WarningCode::ResourceLimitReached)
+        );
+    }
+
+    #[test]
+    fn test_mhtml_image_by_content_id() {
+        let page = "<p>Logo</p><img src=3D\"cid:logo@example\" alt=3D\"Logo\">";
+        let data = archive(page, "Content-ID: <logo@example>");
+        let result = MhtmlConverter.convert(&data, &extract_options()).unwrap();
+        assert!(result.markdown.contains("![Logo](image_1.png)"));
+        assert_eq!(
+            result.images,
+            vec![("image_1.png".to_string(), PNG_BYTES.to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_mhtml_unmatched_image_not_extracted() {
+        let data = archive(PAGE, "Content-Location: https://example.com/other.png");
+        let result = MhtmlConverter.convert(&data, &extract_options()).unwrap();
+        assert!(result.images.is_empty());
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_mhtml_start_parameter_selects_root() {
+        let data = b"MIME-Version: 1.0\r\n\
+            Content-Type: multipart/related; boundary=b; start=\"<main>\"\r\n\
+            \r\n\
+            --b\r\n\
+            Content-Type: text/html\r\n\
+            \r\n\
+            <p>Frame</p>\r\n\
+            --b\r\n\
+            Content-Type: text/html; charset=windows-1252\r\n\
+            Content-ID: <main>\r\n\
+            \r\n\
+            <p>Main \xe9t\xe9</p>\r\n\
+            --b--\r\n";
+        let result = MhtmlConverter
+            .convert(data, &ConversionOptions::default())
+            .unwrap();
+        assert_eq!(result.markdown.trim(), "Main été");
+    }
+
+    #[test]
+    fn test_mhtml_single_part_html() {
+        let data = b"Content-Type: text/html; charset=utf-8\r\n\
+            Content-Transfer-Encoding: base64\r\n\
+            \r\n\
+            PGgxPkhlbGxvPC9oMT4=\r\n";
+        let result = MhtmlConverter
+            .convert(data, &ConversionOptions::default())
+            .unwrap();
+        assert_eq!(result.markdown.trim(), "# Hello");
+    }
+
+    #[test]
+    fn test_mhtml_without_html_part_errors() {
+        let data = b"Content-Type: multipart/related; boundary=b\r\n\r\n\
+            --b\r\nContent-Type: image/png\r\n\r\nxyz\r\n--b--\r\n";
+        let err = MhtmlConverter
+            .convert(data, &ConversionOptions::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("no text/html part"), "{err}");
+    }
+
+    #[test]
+    fn test_mhtml_truncated_and_invalid_parts_warn() {
+        let data = b"Content-Type: multipart/related; boundary=b\r\n\r\n\
+            --b\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64\r\n\r\n\
+            !!not base64!!\r\n\
+            --b\r\nContent-Type: text/html\r\n\r\n<p>Partial";
+        let result = MhtmlConverter
+            .convert(data, &ConversionOptions::default())
+            .unwrap();
+        assert_eq!(result.markdown.trim(), "Partial");
+        assert_eq!(result.warnings.len(), 2);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .all(|w| w.code == WarningCode::MalformedSegment)
+        );
+    }
+
+    #[test]
+    fn test_decode_quoted_printable() {
+        assert_eq!(decode_quoted_printable(b"a=3Db"), b"a=b");
+        assert_eq!(decode_quoted_printable(b"soft=\r\nbreak"), b"softbreak");
+        assert_eq!(decode_quoted_printable(b"soft=\nbreak"), b"softbreak");
+        assert_eq!(decode_quoted_printable(b"caf=C3=A9"), "café".as_bytes());
+        assert_eq!(decode_quoted_printable(b"bad=ZZ="), b"bad=ZZ=");
+        assert_eq!(decode_quoted_printable(b"sign=+1"), b"sign=+1");
+    }
+
+    #[test]
+    fn test_parse_content_type_params() {
+        let (mime, params) =
+            parse_content_type("Multipart/Related; boundary=\"a;b\"; type=text/html");
+        assert_eq!(mime, "multipart/related");
+        assert_eq!(
+            params,
+            vec![
+                ("boundary".to_string(), "a;b".to_string()),
+                ("type".to_string(), "text/html".to_string()),
+            ]
+        );
+    }
+}
@@ -12,6 +12,7 @@ pub mod html;
 pub mod image;
 pub mod ipynb;
 pub mod json;
+pub mod mhtml;
 pub(crate) mod ooxml_utils;
 pub mod plain_text;
 pub mod pptx;
@@ -92,6 +93,211 @@ pub struct ConversionOptions {
     pub max_uncompressed_zip_bytes: usize,
     /// Optional image describer for LLM-based alt text generation.
     pub image_describer: Option<Arc<dyn ImageDescriber>>,
+    /// PPTX-specific options (slide selection, hidden slides, sections).
+    pub pptx: PptxOptions,
+    /// XLSX-specific options (merged cells, hidden content, number formats, formulas,
+    /// region detection, comments, row/column caps).
+    pub xlsx: XlsxOptions,
+    /// HTML-specific options (main content extraction, complex tables, link
+    /// resolution, local images, front matter).
+    pub html: HtmlOptions,
+}
+
+/// How slides marked hidden in PowerPoint (`<p:sld show="0">`) are converted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HiddenSlides {
+    /// Convert hidden slides like visible ones.
+    #[default]
+    Include,
+    /// Leave hidden slides out of the output and report each as a
+    /// `SkippedElement` warning.
+    Skip,
+    /// Convert hidden slides and append `(hidden)` to their heading.
+    Mark,
+}
+
+/// Options that only affect PPTX conversion.
+#[derive(Debug, Clone, Default)]
+pub struct PptxOptions {
+    /// How hidden slides are handled.
+    pub hidden_slides: HiddenSlides,
+    /// 1-based slide numbers to convert, e.g. `Some((3..=7).collect())`.
+    /// `None` converts every slide. Slides keep their original numbers.
+    pub slides: Option<Vec<usize>>,
+    /// Emit section names (`p14:sectionLst`) as `#` headings above the
+    /// `## Slide N` headings of each section.
+    pub section_headings: bool,
+    /// Append review comments (legacy `ppt/comments/commentN.xml` and modern
+    /// threaded `modernComment_*.xml`) with their authors under each slide.
+    pub comments: bool,
+    /// Convert embedded Office packages (xlsx, docx, pptx OLE objects) with the
+    /// matching converter and inline the result under the slide. Nested
+    /// packages are not converted further.
+    pub convert_embedded: bool,
+    /// Emit shapes in visual reading order (top-to-bottom, left-to-right, with
+    /// column detection) based on their `a:xfrm` offsets instead of XML z-order.
+    /// Shapes without an explicit position keep their XML order and come first.
+    pub reading_order: bool,
+}
+
+/// How merged cell ranges (`<mergeCells>`) in spreadsheets are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergedCells {
+    /// Show the value only in the top-left cell; spanned cells stay blank.
+    #[default]
+    TopLeft,
+    /// Repeat the top-left value in every cell of the merged area.
+    Fill,
+    /// Mark spanned cells with `←` (same row as the value) or `↑` (rows below).
+    Mark,
+}
+
+/// How hidden spreadsheet content (sheets with `state="hidden"`/`"veryHidden"`,
+/// hidden rows and hidden columns) is converted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HiddenContent {
+    /// Convert hidden content like visible content and report it as an
+    /// `UnsupportedFeature` warning (an error in strict mode).
+    #[default]
+    Include,
+    /// Leave hidden content out and report it as a `SkippedElement` warning.
+    Skip,
+    /// Convert hidden content and label it: `(hidden)` after sheet headings and
+    /// column headers, and before the first cell of hidden rows. The hidden
+    /// content is also reported as an `UnsupportedFeature` warning.
+    Mark,
+}
+
+/// How numeric cell values are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CellValues {
+    /// Apply the cell's number format from `styles.xml` (percentages, currency,
+    /// thousands separators, fixed decimals, custom date formats), as Excel
+    /// displays it. Built-in locale-dependent date formats stay ISO 8601.
+    #[default]
+    Formatted,
+    /// Render the stored value (e.g. `0.125` instead of `12.5%`).
+    Raw,
+}
+
+/// Whether and how cell formulas are shown next to their cached values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Formulas {
+    /// Emit cached values only.
+    #[default]
+    Off,
+    /// Annotate each formula cell in the table: `1234 (=SUM(B2:B9))`.
+    Inline,
+    /// Keep the table unchanged and list formulas in a `### Formulas` section
+    /// after each sheet: ``- `B10`: `=SUM(B2:B9)` ``.
+    Appendix,
+}
+
+/// Which rows are kept when a sheet exceeds [`XlsxOptions::max_rows`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RowSampling {
+    /// Keep the first rows.
+    #[default]
+    Head,
+    /// Keep the last rows.
+    Tail,
+    /// Keep rows spread evenly across the sheet, including the first one.
+    Evenly,
+}
+
+/// Options that only affect spreadsheet (XLSX/XLS) conversion.
+#[derive(Debug, Clone, Default)]
+pub struct XlsxOptions {
+    /// How merged cell ranges are rendered.
+    pub merged_cells: MergedCells,
+    /// How hidden sheets, rows, and columns are handled. Defaults to
+    /// [`HiddenContent::Include`]. Hidden rows and columns are read from XLSX
+    /// files only; sheet visibility is honoured for XLS as well.
+    pub hidden: HiddenContent,
+    /// Whether number formats are applied to cell values (XLSX only).
+    pub cell_values: CellValues,
+    /// Whether cell formulas are exposed.
+    pub formulas: Formulas,
+    /// Split each sheet into separate regions at fully blank rows and columns.
+    /// Each region gets its own header row (column letters when its first row
+    /// does not look like one) and lone single cells, such as titles, are
+    /// rendered as text instead of a table.
+    pub detect_regions: bool,
+    /// Append cell notes (`xl/comments*.xml`) and threaded comments
+    /// (`xl/threadedComments/`) with their cell references and authors under
+    /// each sheet (XLSX only).
+    pub comments: bool,
+    /// Maximum number of data rows rendered per sheet, shared by the sheet's
+    /// tables in order (header rows are not counted). Omitted rows are
+    /// summarised below each table and reported as a
+    /// [`WarningCode::ResourceLimitReached`] warning. `None` means unlimited.
+    ///
+    /// This caps the output only: the whole sheet is still read into memory,
+    /// so use [`ConversionOptions::max_input_bytes`] and
+    /// [`ConversionOptions::max_uncompressed_zip_bytes`] to bound memory use.
+    pub max_rows: Option<usize>,
+    /// Maximum number of columns rendered per table. Omitted column headers are
+    /// listed in the summary line. `None` means unlimited. Like `max_rows`,
+    /// this does not bound memory use.
+    pub max_columns: Option<usize>,
+    /// Which rows are kept when `max_rows` truncates a table.
+    pub row_sampling: RowSampling,
+}
+
+/// How HTML tables that GFM cannot represent (nested tables, multi-row
+/// headers) are rendered. Plain text output always uses the key-value layout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ComplexTables {
+    /// Emit the table as raw HTML, keeping spans and nested tables.
+    #[default]
+    Html,
+    /// Emit one list item per row: `- Name: Alice; City: Seoul`. Nested
+    /// tables are flattened into the cell value.
+    KeyValue,
+}
+
+/// How links (`<a href>`) in HTML documents are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinkPolicy {
+    /// Resolve relative URLs against the base URL (`<base href>` or
+    /// [`HtmlOptions::base_url`]) when one is known, otherwise keep them as-is.
+    #[default]
+    Absolute,
+    /// Copy `href`/`src` values verbatim.
+    Keep,
+    /// Replace links with their text.
+    Text,
+    /// Write `[text][n]` and list resolved targets as `[n]: url` definitions
+    /// at the end of the document. Repeated targets share one number.
+    Reference,
+}
+
+/// Options that only affect HTML conversion.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlOptions {
+    /// Convert only the main content of the page. DOM subtrees are scored by
+    /// text density, link density and semantic tags (`<main>`, `<article>`,
+    /// `role="main"`), and navigation, sidebars, footers and banners inside
+    /// the chosen subtree are dropped. The title is still taken from
+    /// `<title>` (or the first `<h1>`) of the whole document.
+    pub readability: bool,
+    /// How tables with nested tables or multi-row headers are rendered.
+    /// `colspan`/`rowspan` alone are expanded into a regular GFM table.
+    pub complex_tables: ComplexTables,
+    /// URL of the page, used to resolve relative links and image sources.
+    /// A `<base href>` in the document is resolved against it.
+    pub base_url: Option<String>,
+    /// How links are written.
+    pub links: LinkPolicy,
+    /// Directory that relative `<img src>` paths are read from, so local
+    /// images can be extracted and described. [`convert_file`](crate::convert_file)
+    /// sets it to the HTML file's directory when `None`. Absolute paths and
+    /// paths leaving the directory, directly or through a symlink, are never
+    /// read. Extracted images are named by their `src`, as in the Markdown.
+    pub image_dir: Option<std::path::PathBuf>,
+    /// Prepend a YAML front matter block with the title and
+    /// [`ConversionResult::metadata`] to the Markdown. Plain text is unchanged.
+    pub front_matter: bool,
 }
 
 impl std::fmt::Debug for ConversionOptions {
@@ -109,6 +315,9 @@ impl std::fmt::Debug for ConversionOptions {
                 "image_describer",
                 &self.image_describer.as_ref().map(|_| ".."),
             )
+            .field("pptx", &self.pptx)
+            .field("xlsx", &self.xlsx)
+            .field("html", &self.html)
             .finish()
     }
 }
@@ -122,12 +331,20 @@ impl Default for ConversionOptions {
             max_input_bytes: 8_usize.saturating_mul(1024 * 1024 * 1024), // 8 GiB (usize::MAX on 32-bit)
             max_uncompressed_zip_bytes: 16_usize.saturating_mul(1024 * 1024 * 1024), // 16 GiB (usize::MAX on 32-bit)
             image_describer: None,
+            pptx: PptxOptions::default(),
+            xlsx: XlsxOptions::default(),
+            html: HtmlOptions::default(),
         }
     }
 }
 
 /// The result of converting a document to Markdown.
+///
+/// Marked `#[non_exhaustive]` so fields can be added without a breaking
+/// change. Converters outside this crate start from
+/// `ConversionResult::default()` and assign the fields they fill.
 #[derive(Debug, Clone, Default)]
+#[non_exhaustive]
 pub struct ConversionResult {
     /// Converted Markdown content.
     pub markdown: String,
@@ -141,6 +358,37 @@ pub struct ConversionResult {
     pub images: Vec<(String, Vec<u8>)>,
     /// Recoverable issues encountered during conversion.
     pub warnings: Vec<ConversionWarning>,
+    /// Document metadata beyond the title (currently filled by the HTML converter).
+    pub metadata: DocumentMetadata,
+}
+
+/// Descriptive metadata declared by a document, such as HTML `<meta>` tags.
+///
+/// Fields the source does not declare are left empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DocumentMetadata {
+    /// Short summary (`<meta name="description">`).
+    pub description: Option<String>,
+    /// Author (`<meta name="author">`).
+    pub author: Option<String>,
+    /// Keywords (`<meta name="keywords">`, split at commas).
+    pub keywords: Vec<String>,
+    /// Publication time as written in the source (`article:published_time`).
+    pub published: Option<String>,
+    /// Canonical URL (`<link rel="canonical">`).
+    pub canonical_url: Option<String>,
+    /// Open Graph properties keyed by their full name, e.g. `og:title`.
+    pub open_graph: std::collections::BTreeMap<String, String>,
+    /// JSON-LD blocks (`<script type="application/ld+json">`) as compact JSON.
+    pub json_ld: Vec<String>,
+}
+
+impl DocumentMetadata {
+    /// Whether no metadata was found.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 /// Decode raw bytes to a UTF-8 string, handling BOM detection and encoding fallback.
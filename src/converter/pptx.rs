@@ -1,12 +1,16 @@
 //! PPTX (Office Open XML Presentation) to Markdown converter.
 //!
 //! Parses PPTX files directly from their OOXML ZIP structure. Extracts slide titles,
-//! body text, tables, speaker notes, embedded images, and content from group shapes
-//! (`<p:grpSp>`). Each slide becomes a `## Slide N: Title` section separated by
-//! horizontal rules.
+//! body text, tables, speaker notes, embedded images, SmartArt diagram text, and content
+//! from group shapes (`<p:grpSp>`). Video, audio, and OLE objects become placeholders
+//! such as `[Video: media1.mp4 (1:05)]`. Each slide becomes a `## Slide N: Title` section
+//! separated by horizontal rules. Slide selection, hidden slides, and section headings
+//! are controlled through [`PptxOptions`](crate::PptxOptions), which can also append
+//! each slide's review comments, convert embedded Office packages inline, and
+//! switch from XML z-order to visual reading order.
 
-use std::collections::HashMap;
-use std::io::Cursor;
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 
 use quick_xml::Reader;
 use quick_xml::events::Event;
@@ -17,10 +21,10 @@ use crate::converter::ooxml_utils::{
     resolve_image_placeholders, resolve_relative_to_file,
 };
 use crate::converter::{
-    ConversionOptions, ConversionResult, ConversionWarning, Converter, WarningCode,
+    ConversionOptions, ConversionResult, ConversionWarning, Converter, HiddenSlides, WarningCode,
 };
 use crate::error::ConvertError;
-use crate::markdown::{build_table, build_table_plain};
+use crate::markdown::{build_table, build_table_plain, format_list_item, format_list_item_plain};
 use crate::zip_utils::{read_zip_bytes, read_zip_text};
 
 /// Converts PPTX files to Markdown.
@@ -33,6 +37,88 @@ pub struct PptxConverter;
 struct SlideInfo {
     number: usize,
     path: String,
+    /// Name of the section (`p14:section`) containing this slide, if any.
+    section: Option<String>,
+}
+
+/// A raw `<a:tc>` table cell with its merge attributes.
+#[derive(Debug, Clone)]
+struct TableCell {
+    text: String,
+    /// Number of grid columns the cell spans (`gridSpan`, default 1).
+    grid_span: usize,
+    /// Number of rows the cell spans (`rowSpan`, default 1).
+    row_span: usize,
+    /// Continuation of a horizontal merge (`hMerge="1"`).
+    h_merge: bool,
+    /// Continuation of a vertical merge (`vMerge="1"`).
+    v_merge: bool,
+}
+
+impl Default for TableCell {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            grid_span: 1,
+            row_span: 1,
+            h_merge: false,
+            v_merge: false,
+        }
+    }
+}
+
+/// Content collected for a single slide, ready for rendering.
+#[derive(Debug, Clone)]
+struct SlideContent {
+    number: usize,
+    hidden: bool,
+    shapes: Vec<ShapeContent>,
+    notes: Option<String>,
+    comments: Vec<SlideComment>,
+}
+
+/// Position and size of a shape on the slide, in EMUs, with group transforms applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ShapeBounds {
+    x: f64,
+    y: f64,
+    cx: f64,
+    cy: f64,
+}
+
+/// The `<a:xfrm>` of a `<p:grpSp>`: maps child coordinates (`chOff`/`chExt`)
+/// onto the group's own offset and extent.
+#[derive(Debug, Clone, Copy, Default)]
+struct GroupTransform {
+    off: (f64, f64),
+    ext: (f64, f64),
+    ch_off: (f64, f64),
+    ch_ext: (f64, f64),
+}
+
+impl GroupTransform {
+    fn apply(&self, b: ShapeBounds) -> ShapeBounds {
+        let scale = |ext: f64, ch_ext: f64| if ch_ext > 0.0 { ext / ch_ext } else { 1.0 };
+        let (sx, sy) = (
+            scale(self.ext.0, self.ch_ext.0),
+            scale(self.ext.1, self.ch_ext.1),
+        );
+        ShapeBounds {
+            x: self.off.0 + (b.x - self.ch_off.0) * sx,
+            y: self.off.1 + (b.y - self.ch_off.1) * sy,
+            cx: b.cx * sx,
+            cy: b.cy * sy,
+        }
+    }
+}
+
+/// A review comment (or threaded reply) attached to a slide.
+#[derive(Debug, Clone, PartialEq)]
+struct SlideComment {
+    author: String,
+    text: String,
+    /// `true` for replies in a modern comment thread.
+    is_reply: bool,
 }
 
 /// The type of placeholder in a shape.
@@ -45,6 +131,13 @@ enum PlaceholderType {
     Other,
 }
 
+/// The kind of media referenced by a `<p:pic>` media shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MediaKind {
+    Video,
+    Audio,
+}
+
 /// Content extracted from a single shape on a slide.
 #[derive(Debug, Clone)]
 enum ShapeContent {
@@ -58,33 +151,86 @@ enum ShapeContent {
         rel_id: String,
         alt_text: Option<String>,
     },
+    /// A SmartArt graphic frame. `rel_id` points at the diagram data part;
+    /// `items` holds `(level, text)` list entries once the data is resolved.
+    Diagram {
+        rel_id: String,
+        items: Vec<(u8, String)>,
+    },
+    /// Embedded or linked video/audio. `file_name` and `duration_secs` are
+    /// filled in once the media relationship is resolved.
+    Media {
+        kind: MediaKind,
+        rel_id: String,
+        file_name: String,
+        duration_secs: Option<f64>,
+    },
+    /// An OLE object (`<p:oleObj>`). `file_name` and the optional converted
+    /// Markdown/plain text are filled in once the relationship is resolved.
+    Embedded {
+        rel_id: String,
+        prog_id: Option<String>,
+        file_name: String,
+        converted: Option<(String, String)>,
+    },
 }
 
 // ---- Slide order resolution ----
 
 /// Parse presentation.xml and its rels to determine slide order.
 ///
-/// Returns slides in presentation order (as defined by `<p:sldIdLst>`).
+/// Returns slides in presentation order (as defined by `<p:sldIdLst>`), each
+/// tagged with its section name from `<p14:sectionLst>` when sections are defined.
 fn resolve_slide_order(
     pres_xml: &str,
     pres_rels: &HashMap<String, Relationship>,
 ) -> Vec<SlideInfo> {
     let mut reader = Reader::from_str(pres_xml);
-    let mut rel_ids: Vec<String> = Vec::new();
+    // (slide id, relationship id) pairs from <p:sldIdLst>
+    let mut entries: Vec<(String, String)> = Vec::new();
+    // slide id -> section name, from <p14:section><p14:sldIdLst><p14:sldId id="..."/>
+    let mut sections: HashMap<String, String> = HashMap::new();
+    let mut current_section: Option<String> = None;
 
     loop {
         match reader.read_event() {
+            Ok(Event::Start(ref e)) if e.local_name().as_ref() == b"section" => {
+                let mut name = String::new();
+                for attr in e.attributes().flatten() {
+                    if attr.key.as_ref() == b"name" {
+                        name = attr
+                            .decode_and_unescape_value(reader.decoder())
+                            .map(|v| v.into_owned())
+                            .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).to_string());
+                    }
+                }
+                current_section = Some(name);
+            }
+            Ok(Event::End(ref e)) if e.local_name().as_ref() == b"section" => {
+                current_section = None;
+            }
             Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
                 let local = e.local_name();
                 let local_str = std::str::from_utf8(local.as_ref()).unwrap_or("");
 
                 if local_str == "sldId" {
+                    let mut slide_id = String::new();
+                    let mut rel_id = None;
                     for attr in e.attributes().flatten() {
                         let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                        if key == "r:id" || key.ends_with(":id") {
-                            let val = String::from_utf8_lossy(&attr.value).to_string();
-                            rel_ids.push(val);
+                        let val = String::from_utf8_lossy(&attr.value).to_string();
+                        if key == "id" {
+                            slide_id = val;
+                        } else if key == "r:id" || key.ends_with(":id") {
+                            rel_id = Some(val);
+                        }
+                    }
+                    match (&current_section, rel_id) {
+                        (Some(section), _) => {
+                            sections.insert(slide_id, section.clone());
                         }
+                        (None, Some(rel_id)) => entries.push((slide_id, rel_id)),
+                        (None, None) => {}
                     }
                 }
             }
@@ -95,7 +241,7 @@ fn resolve_slide_order(
     }
 
     let mut slides = Vec::new();
-    for (i, rid) in rel_ids.iter().enumerate() {
+    for (i, (slide_id, rid)) in entries.iter().enumerate() {
         if let Some(rel) = pres_rels.get(rid) {
             // Target is relative to ppt/ directory, e.g., "slides/slide1.xml"
             let path = if rel.target.starts_with("ppt/") {
@@ -106,6 +252,7 @@ fn resolve_slide_order(
             slides.push(SlideInfo {
                 number: i + 1,
                 path,
+                section: sections.get(slide_id).cloned(),
             });
         }
     }
@@ -113,14 +260,38 @@ fn resolve_slide_order(
     slides
 }
 
+/// Check whether a slide is hidden (`<p:sld show="0">`).
+fn is_slide_hidden(xml: &str) -> bool {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                return e.attributes().flatten().any(|attr| {
+                    attr.key.as_ref() == b"show" && matches!(attr.value.as_ref(), b"0" | b"false")
+                });
+            }
+            Ok(Event::Eof) | Err(_) => return false,
+            _ => {}
+        }
+    }
+}
+
 // ---- Slide content parsing ----
 
 /// Parse a slide XML and extract shape contents in document order.
 ///
-/// Returns (shapes, warnings).
-fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
+/// Returns the shapes, their bounds (parallel to the shapes; `None` when a shape
+/// inherits its position from the layout), and any warnings.
+fn parse_slide(
+    xml: &str,
+) -> (
+    Vec<ShapeContent>,
+    Vec<Option<ShapeBounds>>,
+    Vec<ConversionWarning>,
+) {
     let mut reader = Reader::from_str(xml);
     let mut shapes: Vec<ShapeContent> = Vec::new();
+    let mut bounds: Vec<Option<ShapeBounds>> = Vec::new();
     let mut warnings: Vec<ConversionWarning> = Vec::new();
 
     // Shape-level state
@@ -141,9 +312,13 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
     let mut in_table = false;
     let mut in_table_row = false;
     let mut in_table_cell = false;
-    let mut table_rows: Vec<Vec<String>> = Vec::new();
-    let mut current_row: Vec<String> = Vec::new();
+    let mut table_rows: Vec<Vec<TableCell>> = Vec::new();
+    let mut current_row: Vec<TableCell> = Vec::new();
     let mut current_cell = String::new();
+    let mut current_cell_attrs: Option<TableCell> = None;
+    // <a:tblPr firstRow="..."> — None when the table has no tblPr at all
+    let mut table_first_row: Option<bool> = None;
+    let mut table_grid_cols: usize = 0;
     // Track text state within table cells
     let mut in_cell_paragraph = false;
     let mut in_cell_run = false;
@@ -153,6 +328,15 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
     let mut current_blip_rel_id: Option<String> = None;
     let mut current_image_alt: Option<String> = None;
 
+    // SmartArt state: data model relationship ID from <dgm:relIds r:dm="...">
+    let mut current_diagram_rel_id: Option<String> = None;
+
+    // Media state (video/audio <p:pic>) and OLE object state (<p:oleObj>)
+    let mut current_media: Option<(MediaKind, String)> = None;
+    let mut current_ole: Option<(String, Option<String>)> = None;
+    // OLE objects appear twice inside <mc:AlternateContent> (Choice + Fallback)
+    let mut seen_ole_rel_ids: HashSet<String> = HashSet::new();
+
     // Track depth for nested elements
     let mut shape_depth: u32 = 0;
     let mut graphic_frame_depth: u32 = 0;
@@ -163,31 +347,54 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
     // for proper End-tag matching but does not gate any logic.
     let mut group_depth: u32 = 0;
 
+    // Position state: the first <a:xfrm>/<p:xfrm> of the current shape, and
+    // the transforms of enclosing groups (innermost last)
+    let mut in_xfrm = false;
+    let mut current_off: Option<(f64, f64)> = None;
+    let mut current_ext: Option<(f64, f64)> = None;
+    let mut group_transforms: Vec<GroupTransform> = Vec::new();
+
     loop {
         match reader.read_event() {
             Ok(Event::Start(ref e)) => {
                 let local = e.local_name();
                 let local_str = std::str::from_utf8(local.as_ref()).unwrap_or("");
 
+                let in_any_shape = in_shape || in_graphic_frame || in_picture;
+                if local_str == "xfrm" {
+                    // Only the shape's own (first) transform, or a group's transform
+                    in_xfrm = !in_any_shape || current_off.is_none();
+                }
+
                 match local_str {
-                    "grpSp" if !in_shape && !in_graphic_frame && !in_picture => {
+                    "grpSp" if !in_any_shape => {
                         group_depth += 1;
+                        group_transforms.push(GroupTransform::default());
                     }
-                    "sp" if !in_shape && !in_graphic_frame && !in_picture => {
+                    "sp" if !in_any_shape => {
+                        current_off = None;
+                        current_ext = None;
                         in_shape = true;
                         shape_depth = 1;
                         placeholder_type = None;
                         shape_paragraphs.clear();
                     }
-                    "graphicFrame" if !in_shape && !in_graphic_frame && !in_picture => {
+                    "graphicFrame" if !in_any_shape => {
+                        current_off = None;
+                        current_ext = None;
                         in_graphic_frame = true;
                         graphic_frame_depth = 1;
+                        current_diagram_rel_id = None;
+                        current_ole = None;
                     }
-                    "pic" if !in_shape && !in_graphic_frame && !in_picture => {
+                    "pic" if !in_any_shape => {
+                        current_off = None;
+                        current_ext = None;
                         in_picture = true;
                         picture_depth = 1;
                         current_blip_rel_id = None;
                         current_image_alt = None;
+                        current_media = None;
                     }
                     _ if in_shape => {
                         shape_depth += 1;
@@ -204,6 +411,17 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                     }
                     _ if in_graphic_frame => {
                         graphic_frame_depth += 1;
+                        match local_str {
+                            "relIds" => current_diagram_rel_id = diagram_data_rel_id(e),
+                            "oleObj" => current_ole = ole_object_ref(e),
+                            "tbl" => {
+                                table_first_row = None;
+                                table_grid_cols = 0;
+                            }
+                            "tblPr" if in_table => table_first_row = Some(table_first_row_flag(e)),
+                            "tc" if in_table_row => current_cell_attrs = Some(table_cell_attrs(e)),
+                            _ => {}
+                        }
                         handle_graphic_frame_start(
                             local_str,
                             &mut in_table,
@@ -224,6 +442,7 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                             e,
                             &mut current_blip_rel_id,
                             &mut current_image_alt,
+                            &mut current_media,
                         );
                     }
                     _ => {}
@@ -233,6 +452,26 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                 let local = e.local_name();
                 let local_str = std::str::from_utf8(local.as_ref()).unwrap_or("");
 
+                if in_xfrm {
+                    let point = xfrm_point(e, local_str);
+                    if in_shape || in_graphic_frame || in_picture {
+                        match local_str {
+                            "off" => current_off = point,
+                            "ext" => current_ext = point,
+                            _ => {}
+                        }
+                    } else if let (Some(group), Some(point)) = (group_transforms.last_mut(), point)
+                    {
+                        match local_str {
+                            "off" => group.off = point,
+                            "ext" => group.ext = point,
+                            "chOff" => group.ch_off = point,
+                            "chExt" => group.ch_ext = point,
+                            _ => {}
+                        }
+                    }
+                }
+
                 if in_shape {
                     handle_shape_empty(
                         local_str,
@@ -242,6 +481,15 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                         &mut current_paragraph,
                     );
                 } else if in_graphic_frame {
+                    match local_str {
+                        "relIds" => current_diagram_rel_id = diagram_data_rel_id(e),
+                        "oleObj" => current_ole = ole_object_ref(e),
+                        "tblPr" if in_table => table_first_row = Some(table_first_row_flag(e)),
+                        "gridCol" if in_table => table_grid_cols += 1,
+                        // Self-closing cell: an empty merge continuation
+                        "tc" if in_table_row => current_row.push(table_cell_attrs(e)),
+                        _ => {}
+                    }
                     handle_graphic_frame_empty(local_str, in_cell_run, &mut current_cell);
                 } else if in_picture {
                     handle_picture_start(
@@ -249,6 +497,7 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                         e,
                         &mut current_blip_rel_id,
                         &mut current_image_alt,
+                        &mut current_media,
                     );
                 }
             }
@@ -265,6 +514,10 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                 let local = e.local_name();
                 let local_str = std::str::from_utf8(local.as_ref()).unwrap_or("");
 
+                if local_str == "xfrm" {
+                    in_xfrm = false;
+                }
+
                 if in_shape {
                     shape_depth -= 1;
 
@@ -292,6 +545,9 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                         if let Some(c) = content {
                             shapes.push(c);
                         }
+                        let shape_bounds =
+                            shape_bounds(current_off, current_ext, &group_transforms);
+                        bounds.resize(shapes.len(), shape_bounds);
                         in_shape = false;
                         placeholder_type = None;
                         shape_paragraphs.clear();
@@ -313,7 +569,9 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                             in_cell_paragraph = false;
                         }
                         "tc" if in_table_cell => {
-                            current_row.push(current_cell.trim().to_string());
+                            let mut cell = current_cell_attrs.take().unwrap_or_default();
+                            cell.text = current_cell.trim().to_string();
+                            current_row.push(cell);
                             current_cell.clear();
                             in_table_cell = false;
                             in_cell_paragraph = false;
@@ -328,15 +586,25 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                         "tbl" if in_table => {
                             // Finalize table
                             if !table_rows.is_empty() {
-                                let headers = table_rows[0].clone();
-                                let data_rows = if table_rows.len() > 1 {
-                                    table_rows[1..].to_vec()
+                                let mut grid = layout_table_grid(&table_rows);
+                                let col_count = grid
+                                    .iter()
+                                    .map(|row| row.len())
+                                    .max()
+                                    .unwrap_or(0)
+                                    .max(table_grid_cols);
+                                for row in &mut grid {
+                                    row.resize(col_count, String::new());
+                                }
+                                // Without <a:tblPr>, keep treating the first row as the header
+                                let headers = if table_first_row.unwrap_or(true) {
+                                    grid.remove(0)
                                 } else {
-                                    Vec::new()
+                                    vec![String::new(); col_count]
                                 };
                                 shapes.push(ShapeContent::Table {
                                     headers,
-                                    rows: data_rows,
+                                    rows: grid,
                                 });
                             }
                             table_rows.clear();
@@ -346,6 +614,25 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                     }
 
                     if graphic_frame_depth == 0 {
+                        if let Some(rel_id) = current_diagram_rel_id.take() {
+                            shapes.push(ShapeContent::Diagram {
+                                rel_id,
+                                items: Vec::new(),
+                            });
+                        }
+                        if let Some((rel_id, prog_id)) = current_ole.take()
+                            && seen_ole_rel_ids.insert(rel_id.clone())
+                        {
+                            shapes.push(ShapeContent::Embedded {
+                                rel_id,
+                                prog_id,
+                                file_name: String::new(),
+                                converted: None,
+                            });
+                        }
+                        let shape_bounds =
+                            shape_bounds(current_off, current_ext, &group_transforms);
+                        bounds.resize(shapes.len(), shape_bounds);
                         in_graphic_frame = false;
                         in_table = false;
                         in_table_row = false;
@@ -358,17 +645,30 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
                     picture_depth -= 1;
 
                     if picture_depth == 0 {
-                        if let Some(rel_id) = current_blip_rel_id.take() {
+                        // A media shape's blip is only the poster frame
+                        if let Some((kind, rel_id)) = current_media.take() {
+                            shapes.push(ShapeContent::Media {
+                                kind,
+                                rel_id,
+                                file_name: String::new(),
+                                duration_secs: None,
+                            });
+                            current_blip_rel_id = None;
+                        } else if let Some(rel_id) = current_blip_rel_id.take() {
                             shapes.push(ShapeContent::Image {
                                 rel_id,
                                 alt_text: current_image_alt.take(),
                             });
                         }
+                        let shape_bounds =
+                            shape_bounds(current_off, current_ext, &group_transforms);
+                        bounds.resize(shapes.len(), shape_bounds);
                         in_picture = false;
                         current_image_alt = None;
                     }
                 } else if local_str == "grpSp" && group_depth > 0 {
                     group_depth -= 1;
+                    group_transforms.pop();
                 }
             }
             Ok(Event::Eof) => break,
@@ -384,7 +684,47 @@ fn parse_slide(xml: &str) -> (Vec<ShapeContent>, Vec<ConversionWarning>) {
         }
     }
 
-    (shapes, warnings)
+    (shapes, bounds, warnings)
+}
+
+/// Read the `x`/`y` (for `off`/`chOff`) or `cx`/`cy` (for `ext`/`chExt`)
+/// attributes of an `<a:xfrm>` child element.
+fn xfrm_point(e: &quick_xml::events::BytesStart, local_str: &str) -> Option<(f64, f64)> {
+    let (kx, ky): (&[u8], &[u8]) = match local_str {
+        "off" | "chOff" => (b"x", b"y"),
+        "ext" | "chExt" => (b"cx", b"cy"),
+        _ => return None,
+    };
+    let mut x = None;
+    let mut y = None;
+    for attr in e.attributes().flatten() {
+        let value = std::str::from_utf8(&attr.value)
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok());
+        if attr.key.as_ref() == kx {
+            x = value;
+        } else if attr.key.as_ref() == ky {
+            y = value;
+        }
+    }
+    Some((x?, y?))
+}
+
+/// Build a shape's slide-space bounds from its transform and enclosing groups.
+fn shape_bounds(
+    off: Option<(f64, f64)>,
+    ext: Option<(f64, f64)>,
+    group_transforms: &[GroupTransform],
+) -> Option<ShapeBounds> {
+    let (x, y) = off?;
+    let (cx, cy) = ext.unwrap_or((0.0, 0.0));
+    let local = ShapeBounds { x, y, cx, cy };
+    Some(
+        group_transforms
+            .iter()
+            .rev()
+            .fold(local, |b, group| group.apply(b)),
+    )
 }
 
 /// Handle a Start event inside a <p:sp> shape.
@@ -481,8 +821,8 @@ fn handle_graphic_frame_start(
     in_cell_run: &mut bool,
     in_cell_text: &mut bool,
     current_cell: &mut String,
-    current_row: &mut Vec<String>,
-    table_rows: &mut Vec<Vec<String>>,
+    current_row: &mut Vec<TableCell>,
+    table_rows: &mut Vec<Vec<TableCell>>,
 ) {
     match local_str {
         "tbl" => {
@@ -521,14 +861,174 @@ fn handle_graphic_frame_empty(local_str: &str, in_cell_run: bool, current_cell:
     }
 }
 
+/// Read the relationship ID (`r:id`) and `progId` of a `<p:oleObj>` element.
+fn ole_object_ref(e: &quick_xml::events::BytesStart) -> Option<(String, Option<String>)> {
+    let mut rel_id = None;
+    let mut prog_id = None;
+    for attr in e.attributes().flatten() {
+        let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
+        let val = String::from_utf8_lossy(&attr.value).to_string();
+        if key == "progId" {
+            prog_id = Some(val).filter(|v| !v.is_empty());
+        } else if key == "r:id" || key.ends_with(":id") {
+            rel_id = Some(val);
+        }
+    }
+    rel_id.map(|id| (id, prog_id))
+}
+
+/// Read the `firstRow` flag of an `<a:tblPr>` element (default `false`).
+fn table_first_row_flag(e: &quick_xml::events::BytesStart) -> bool {
+    e.attributes().flatten().any(|attr| {
+        attr.key.local_name().as_ref() == b"firstRow"
+            && matches!(attr.value.as_ref(), b"1" | b"true")
+    })
+}
+
+/// Upper bounds for `gridSpan`/`rowSpan`, far above what PowerPoint allows.
+const MAX_GRID_SPAN: usize = 1000;
+const MAX_ROW_SPAN: usize = 65534;
+
+/// Widest a table grid is laid out, as a multiple of the most cells in any row,
+/// so a stray `gridSpan="1000"` does not pad every row with empty cells.
+const MAX_WIDTH_PER_CELL: usize = 2;
+
+/// Parse a `gridSpan`/`rowSpan` value. Missing, invalid or zero values mean 1.
+fn span_attr(value: &str, max: usize) -> usize {
+    value.trim().parse::<usize>().map_or(1, |n| n.clamp(1, max))
+}
+
+/// Read the merge attributes of an `<a:tc>` element. The text is filled in later.
+fn table_cell_attrs(e: &quick_xml::events::BytesStart) -> TableCell {
+    let mut cell = TableCell::default();
+    for attr in e.attributes().flatten() {
+        let local_name = attr.key.local_name();
+        let val = String::from_utf8_lossy(&attr.value);
+        let flag = matches!(val.as_ref(), "1" | "true");
+        match local_name.as_ref() {
+            b"gridSpan" => cell.grid_span = span_attr(&val, MAX_GRID_SPAN),
+            b"rowSpan" => cell.row_span = span_attr(&val, MAX_ROW_SPAN),
+            b"hMerge" => cell.h_merge = flag,
+            b"vMerge" => cell.v_merge = flag,
+            _ => {}
+        }
+    }
+    cell
+}
+
+/// Expand merged table cells into a rectangular grid of cell texts.
+///
+/// The origin cell of a merge keeps its text and every other position it covers
+/// becomes an empty cell, so columns stay aligned. `hMerge`/`vMerge`
+/// continuation cells fill covered positions; when a writer omits them, the
+/// covered positions are padded from the `gridSpan`/`rowSpan` of the origin.
+/// Column spans are cut short at [`MAX_WIDTH_PER_CELL`] times the most cells in
+/// a row (every cell still gets at least one column).
+fn layout_table_grid(rows: &[Vec<TableCell>]) -> Vec<Vec<String>> {
+    let width_cap = rows.iter().map(Vec::len).max().unwrap_or(0) * MAX_WIDTH_PER_CELL;
+    // Last row index covered by a rowSpan origin, per grid column
+    let mut span_until: Vec<Option<usize>> = Vec::new();
+    let mut grid: Vec<Vec<String>> = Vec::with_capacity(rows.len());
+
+    for (r, row) in rows.iter().enumerate() {
+        let covered = |span_until: &[Option<usize>], col: usize| {
+            span_until
+                .get(col)
+                .copied()
+                .flatten()
+                .is_some_and(|last| last >= r)
+        };
+        let mut out: Vec<String> = Vec::new();
+        // hMerge continuations already accounted for by the previous gridSpan
+        let mut pending_h_merges: usize = 0;
+
+        for cell in row {
+            if cell.h_merge && pending_h_merges > 0 {
+                pending_h_merges -= 1;
+                continue;
+            }
+            if !cell.v_merge {
+                while covered(&span_until, out.len()) {
+                    out.push(String::new());
+                }
+            }
+
+            let col = out.len();
+            let text = if cell.h_merge || cell.v_merge {
+                String::new()
+            } else {
+                cell.text.clone()
+            };
+            out.push(text);
+            let grid_span = cell.grid_span.min(width_cap.saturating_sub(col)).max(1);
+            out.resize(col + grid_span, String::new());
+            pending_h_merges = cell.grid_span - 1;
+
+            if cell.row_span > 1 && !cell.v_merge {
+                if span_until.len() < col + grid_span {
+                    span_until.resize(col + grid_span, None);
+                }
+                for slot in &mut span_until[col..col + grid_span] {
+                    *slot = Some(r.saturating_add(cell.row_span - 1));
+                }
+            }
+        }
+
+        // Trailing positions covered from above whose continuation cells were omitted
+        while covered(&span_until, out.len()) {
+            out.push(String::new());
+        }
+        grid.push(out);
+    }
+
+    grid
+}
+
+/// Extract the data model relationship ID (`r:dm`) from a `<dgm:relIds>` element.
+fn diagram_data_rel_id(e: &quick_xml::events::BytesStart) -> Option<String> {
+    for attr in e.attributes().flatten() {
+        let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
+        if key == "r:dm" || key.ends_with(":dm") {
+            return Some(String::from_utf8_lossy(&attr.value).to_string());
+        }
+    }
+    None
+}
+
 /// Handle a Start/Empty event inside a <p:pic>.
 fn handle_picture_start(
     local_str: &str,
     e: &quick_xml::events::BytesStart,
     current_blip_rel_id: &mut Option<String>,
     current_image_alt: &mut Option<String>,
+    current_media: &mut Option<(MediaKind, String)>,
 ) {
     match local_str {
+        "videoFile" | "quickTimeFile" | "audioFile" | "wavAudioFile" => {
+            let kind = match local_str {
+                "audioFile" | "wavAudioFile" => MediaKind::Audio,
+                _ => MediaKind::Video,
+            };
+            for attr in e.attributes().flatten() {
+                let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
+                if key.ends_with(":link") || key.ends_with(":embed") {
+                    let val = String::from_utf8_lossy(&attr.value).to_string();
+                    current_media.get_or_insert((kind, val));
+                }
+            }
+        }
+        // <p14:media r:embed="..."> references the embedded media part and
+        // takes precedence over the (possibly external) link above
+        "media" => {
+            for attr in e.attributes().flatten() {
+                let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
+                if key.ends_with(":embed") {
+                    let val = String::from_utf8_lossy(&attr.value).to_string();
+                    let kind = current_media.as_ref().map_or(MediaKind::Video, |m| m.0);
+                    *current_media = Some((kind, val));
+                }
+            }
+        }
         "blip" => {
             for attr in e.attributes().flatten() {
                 let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
@@ -723,1338 +1223,3527 @@ fn resolve_notes_path(slide_rels: &HashMap<String, Relationship>) -> Option<Stri
     None
 }
 
-// ---- Markdown rendering ----
+// ---- Comments parsing ----
 
-/// Render a single slide's content as Markdown.
+/// Parse comment author lists into an author ID -> name map.
 ///
-/// Images are emitted with unique placeholder alt text `__img_N__`.
-/// `image_counter` is incremented for each image to ensure uniqueness.
-/// Returns (markdown, image_infos).
-/// Render a slide into both markdown and plain text.
-///
-/// Returns `(markdown, plain_text, image_infos)`.
-fn render_slide(
-    number: usize,
-    shapes: &[ShapeContent],
-    notes: &Option<String>,
-    image_filenames: &HashMap<String, String>,
-    slide_key: &str,
-    image_counter: &mut usize,
-) -> (String, String, Vec<ImageInfo>) {
-    let mut out = String::new();
-    let mut plain = String::new();
-    let mut image_infos: Vec<ImageInfo> = Vec::new();
-
-    // Find the title
-    let title = shapes.iter().find_map(|s| {
-        if let ShapeContent::Title(t) = s {
-            Some(t.as_str())
-        } else {
-            None
+/// Handles both legacy `ppt/commentAuthors.xml` (`<p:cmAuthor id="0" name="...">`)
+/// and modern `ppt/authors.xml` (`<p188:author id="{GUID}" name="...">`).
+fn parse_comment_authors(xml: &str, authors: &mut HashMap<String, String>) {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if matches!(e.local_name().as_ref(), b"cmAuthor" | b"author") =>
+            {
+                let mut id = None;
+                let mut name = None;
+                for attr in e.attributes().flatten() {
+                    let val = attr
+                        .decode_and_unescape_value(reader.decoder())
+                        .map(|v| v.into_owned())
+                        .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).to_string());
+                    match attr.key.as_ref() {
+                        b"id" => id = Some(val),
+                        b"name" => name = Some(val),
+                        _ => {}
+                    }
+                }
+                if let (Some(id), Some(name)) = (id, name) {
+                    authors.insert(id, name);
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
         }
-    });
-
-    // Slide heading
-    if let Some(title_text) = title {
-        out.push_str(&format!("## Slide {number}: {title_text}\n\n"));
-        plain.push_str(&format!("{title_text}\n\n"));
-    } else {
-        out.push_str(&format!("## Slide {number}\n\n"));
-        plain.push('\n');
     }
+}
 
-    // Body content, tables, and images (skip title since it's already in heading)
-    for shape in shapes {
-        match shape {
-            ShapeContent::Title(_) => {} // Already rendered as heading
-            ShapeContent::Body(text) => {
-                out.push_str(text);
-                out.push_str("\n\n");
-                plain.push_str(text);
-                plain.push_str("\n\n");
-            }
-            ShapeContent::Table { headers, rows } => {
-                let header_refs: Vec<&str> = headers.iter().map(|s| s.as_str()).collect();
-                let row_refs: Vec<Vec<&str>> = rows
-                    .iter()
-                    .map(|r| r.iter().map(|s| s.as_str()).collect())
-                    .collect();
-                out.push_str(&build_table(&header_refs, &row_refs));
-                out.push('\n');
-                plain.push_str(&build_table_plain(&header_refs, &row_refs));
-                plain.push('\n');
-            }
-            ShapeContent::Image { rel_id, alt_text } => {
-                if let Some(filename) = image_filenames.get(rel_id) {
-                    let original_alt = alt_text.as_deref().unwrap_or("").to_string();
-                    let placeholder = format!("__img_{n}__", n = *image_counter);
-                    *image_counter += 1;
-                    image_infos.push(ImageInfo {
-                        placeholder: placeholder.clone(),
-                        original_alt: original_alt.clone(),
-                        filename: filename.clone(),
-                        bytes_key: format!("{slide_key}::{rel_id}"),
+/// Parse a slide comments part into comments in document order.
+///
+/// Legacy comments (`<p:cm><p:text>`) and modern threaded comments
+/// (`<p188:cm><p188:txBody>` with `<p188:replyLst>`) share the `cmLst`/`cm`
+/// structure, so one parser handles both. Replies follow their parent comment.
+fn parse_comments(xml: &str, authors: &HashMap<String, String>) -> Vec<SlideComment> {
+    let mut reader = Reader::from_str(xml);
+    let mut comments: Vec<SlideComment> = Vec::new();
+    let mut comment_idx: Option<usize> = None;
+    let mut reply_idx: Option<usize> = None;
+    let mut in_text = false;
+
+    let author_name = |e: &quick_xml::events::BytesStart| {
+        e.attributes()
+            .flatten()
+            .find(|attr| attr.key.as_ref() == b"authorId")
+            .and_then(|attr| authors.get(String::from_utf8_lossy(&attr.value).as_ref()))
+            .cloned()
+            .unwrap_or_else(|| "Unknown".to_string())
+    };
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"cm" => {
+                    comments.push(SlideComment {
+                        author: author_name(e),
+                        text: String::new(),
+                        is_reply: false,
                     });
-                    out.push_str(&format!("![{placeholder}]({filename})\n\n"));
-                    // Plain text: image description placeholder (resolved later)
-                    plain.push_str(&format!("{placeholder}\n\n"));
+                    comment_idx = Some(comments.len() - 1);
+                }
+                b"reply" if comment_idx.is_some() => {
+                    comments.push(SlideComment {
+                        author: author_name(e),
+                        text: String::new(),
+                        is_reply: true,
+                    });
+                    reply_idx = Some(comments.len() - 1);
+                }
+                b"text" | b"t" if comment_idx.is_some() => in_text = true,
+                _ => {}
+            },
+            Ok(Event::Text(ref e)) if in_text => {
+                if let Some(idx) = reply_idx.or(comment_idx) {
+                    let text = e.unescape().unwrap_or_default();
+                    comments[idx].text.push_str(&text);
                 }
             }
+            Ok(Event::End(ref e)) => match e.local_name().as_ref() {
+                b"text" | b"t" => in_text = false,
+                b"p" => {
+                    // Separate paragraphs of a multi-paragraph comment
+                    if let Some(idx) = reply_idx.or(comment_idx) {
+                        comments[idx].text.push(' ');
+                    }
+                }
+                b"reply" => reply_idx = None,
+                b"cm" => {
+                    comment_idx = None;
+                    reply_idx = None;
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
         }
     }
 
-    // Notes
-    if let Some(notes_text) = notes {
-        let lines: Vec<&str> = notes_text.lines().collect();
-        if !lines.is_empty() {
-            out.push_str(&format!("> Note: {}", lines[0]));
-            for line in &lines[1..] {
-                out.push_str(&format!("\n> {line}"));
+    comments
+        .into_iter()
+        .filter_map(|mut c| {
+            c.text = c.text.split_whitespace().collect::<Vec<_>>().join(" ");
+            (!c.text.is_empty()).then_some(c)
+        })
+        .collect()
+}
+
+/// Find a slide's comment parts from its relationships.
+///
+/// Modern threaded comments supersede legacy ones when both are present,
+/// since PowerPoint may keep a legacy copy for older readers.
+fn resolve_comment_paths(slide_rels: &HashMap<String, Relationship>) -> Vec<String> {
+    let mut modern: Vec<&String> = Vec::new();
+    let mut legacy: Vec<&String> = Vec::new();
+    for rel in slide_rels.values() {
+        if rel.rel_type.ends_with("/comments") {
+            if rel.rel_type.contains("schemas.microsoft.com/office/2018") {
+                modern.push(&rel.target);
+            } else {
+                legacy.push(&rel.target);
             }
-            out.push_str("\n\n");
-            // Plain text: notes without blockquote prefix
-            plain.push_str(notes_text);
-            plain.push_str("\n\n");
         }
     }
-
-    // Trim trailing whitespace
-    (
-        out.trim_end().to_string(),
-        plain.trim_end().to_string(),
-        image_infos,
-    )
+    let mut targets = if modern.is_empty() { legacy } else { modern };
+    targets.sort();
+    targets.into_iter().cloned().collect()
 }
 
-// ---- Converter trait impl ----
+// ---- SmartArt diagram parsing ----
 
-// ---- Internal conversion (parse + image extraction, no resolution) ----
+/// A point (`<dgm:pt>`) in a SmartArt data model.
+#[derive(Debug, Clone)]
+struct DiagramPoint {
+    /// Point type: `doc`, `node`, `asst`, `parTrans`, `sibTrans`, or `pres`.
+    kind: String,
+    text: String,
+}
 
-impl PptxConverter {
-    /// Parse the presentation and extract images without resolving placeholders.
-    ///
-    /// Returns the conversion result (with unresolved placeholders in markdown)
-    /// and pending image data for later resolution (sync or async).
-    pub(crate) fn convert_inner(
-        &self,
-        data: &[u8],
-        options: &ConversionOptions,
-    ) -> Result<(ConversionResult, PendingImageResolution), ConvertError> {
-        let cursor = Cursor::new(data);
-        let mut archive = ZipArchive::new(cursor)?;
-
-        crate::zip_utils::validate_zip_budget(&mut archive, options.max_uncompressed_zip_bytes)?;
-
-        let mut warnings: Vec<ConversionWarning> = Vec::new();
-        let mut images: Vec<(String, Vec<u8>)> = Vec::new();
-
-        // 1. Parse presentation.xml.rels (optional but needed for slide resolution)
-        let pres_rels = match read_zip_text(&mut archive, "ppt/_rels/presentation.xml.rels")? {
-            Some(xml) => parse_relationships(&xml),
-            None => HashMap::new(),
-        };
-
-        // 2. Parse presentation.xml (required)
-        let pres_xml = read_zip_text(&mut archive, "ppt/presentation.xml")?.ok_or_else(|| {
-            ConvertError::MalformedDocument {
-                reason: "missing ppt/presentation.xml".to_string(),
-            }
-        })?;
-
-        // 3. Resolve slide order
-        let slides = resolve_slide_order(&pres_xml, &pres_rels);
-
-        if slides.is_empty() {
-            return Ok((
-                ConversionResult {
-                    markdown: String::new(),
-                    ..Default::default()
-                },
-                PendingImageResolution::default(),
-            ));
-        }
+/// Parse a SmartArt data model (`ppt/diagrams/dataN.xml`) into nested list items.
+///
+/// Node text is emitted as `(level, text)` pairs following the `parOf` connection
+/// hierarchy, with siblings ordered by `srcOrd`. Also returns the relationship ID
+/// of the pre-rendered drawing part (`<dsp:dataModelExt relId="...">`), if any.
+fn parse_diagram_data(xml: &str) -> (Vec<(u8, String)>, Option<String>) {
+    let mut reader = Reader::from_str(xml);
+    let mut points: HashMap<String, DiagramPoint> = HashMap::new();
+    let mut point_order: Vec<String> = Vec::new();
+    let mut children: HashMap<String, Vec<(u32, String)>> = HashMap::new();
+    let mut has_parent: HashSet<String> = HashSet::new();
+    let mut drawing_rel_id: Option<String> = None;
+
+    let mut current_point: Option<(String, String)> = None; // (modelId, type)
+    let mut point_depth: u32 = 0;
+    let mut in_text_body = false;
+    let mut in_run = false;
+    let mut in_text = false;
+    let mut current_paragraph = String::new();
+    let mut paragraphs: Vec<String> = Vec::new();
 
-        // 4. Process each slide — collect all image infos and bytes across slides
-        let mut slide_markdowns: Vec<String> = Vec::new();
-        let mut slide_plains: Vec<String> = Vec::new();
-        let mut document_title: Option<String> = None;
-        let mut total_image_bytes: usize = 0;
-        let mut image_counter: usize = 0;
-        let mut all_image_infos: Vec<ImageInfo> = Vec::new();
-        let mut all_image_bytes: HashMap<String, Vec<u8>> = HashMap::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => {
+                let local = e.local_name();
+                let local_str = std::str::from_utf8(local.as_ref()).unwrap_or("");
 
-        for slide_info in &slides {
-            // Read slide XML
-            let slide_xml = match read_zip_text(&mut archive, &slide_info.path)? {
-                Some(xml) => xml,
-                None => {
-                    warnings.push(ConversionWarning {
-                        code: WarningCode::SkippedElement,
-                        message: format!("slide file not found: {}", slide_info.path),
-                        location: Some(slide_info.path.clone()),
-                    });
-                    continue;
+                if current_point.is_some() {
+                    point_depth += 1;
+                    match local_str {
+                        "t" if in_run => in_text = true,
+                        "t" => in_text_body = true,
+                        "p" if in_text_body => current_paragraph.clear(),
+                        "r" | "fld" if in_text_body => in_run = true,
+                        _ => {}
+                    }
+                } else if local_str == "pt" {
+                    current_point = Some(diagram_point_identity(e));
+                    point_depth = 1;
+                    paragraphs.clear();
+                } else if local_str == "cxn" {
+                    record_diagram_connection(e, &mut children, &mut has_parent);
+                } else if local_str == "dataModelExt" {
+                    drawing_rel_id = drawing_rel_id.or_else(|| diagram_drawing_rel_id(e));
                 }
-            };
-
-            // Parse slide content
-            let (shapes, mut slide_warnings) = parse_slide(&slide_xml);
-            warnings.append(&mut slide_warnings);
-
-            // Read slide rels for notes and images
-            let slide_rels_path = derive_rels_path(&slide_info.path);
-            let slide_rels = match read_zip_text(&mut archive, &slide_rels_path)? {
-                Some(xml) => parse_relationships(&xml),
-                None => HashMap::new(),
-            };
+            }
+            Ok(Event::Empty(ref e)) => {
+                let local = e.local_name();
+                let local_str = std::str::from_utf8(local.as_ref()).unwrap_or("");
 
-            // Parse notes
-            let notes = if let Some(notes_target) = resolve_notes_path(&slide_rels) {
-                let notes_path = resolve_relative_to_file(&slide_info.path, &notes_target);
-                match read_zip_text(&mut archive, &notes_path)? {
-                    Some(xml) => parse_notes(&xml),
-                    None => None,
+                if current_point.is_some() {
+                    if local_str == "br" && in_text_body {
+                        current_paragraph.push(' ');
+                    }
+                } else if local_str == "pt" {
+                    let (model_id, kind) = diagram_point_identity(e);
+                    point_order.push(model_id.clone());
+                    points.insert(
+                        model_id,
+                        DiagramPoint {
+                            kind,
+                            text: String::new(),
+                        },
+                    );
+                } else if local_str == "cxn" {
+                    record_diagram_connection(e, &mut children, &mut has_parent);
+                } else if local_str == "dataModelExt" {
+                    drawing_rel_id = drawing_rel_id.or_else(|| diagram_drawing_rel_id(e));
                 }
-            } else {
-                None
-            };
-
-            // Resolve image filenames and optionally extract image data
-            let need_image_bytes = options.extract_images || options.image_describer.is_some();
-            let mut image_filenames: HashMap<String, String> = HashMap::new();
-            for shape in &shapes {
-                if let ShapeContent::Image { rel_id, .. } = shape
-                    && let Some(rel) = slide_rels.get(rel_id)
-                {
-                    let image_path = resolve_relative_to_file(&slide_info.path, &rel.target);
-                    let filename = image_path.rsplit('/').next().unwrap_or(&image_path);
-                    image_filenames.insert(rel_id.clone(), filename.to_string());
+            }
+            Ok(Event::Text(ref e)) if in_text => {
+                let text = e.unescape().unwrap_or_default().to_string();
+                current_paragraph.push_str(&text);
+            }
+            Ok(Event::End(ref e)) if current_point.is_some() => {
+                let local = e.local_name();
+                let local_str = std::str::from_utf8(local.as_ref()).unwrap_or("");
+                point_depth -= 1;
 
-                    if need_image_bytes
-                        && total_image_bytes < options.max_total_image_bytes
-                        && let Ok(Some(img_data)) = read_zip_bytes(&mut archive, &image_path)
-                    {
-                        total_image_bytes += img_data.len();
-                        if total_image_bytes <= options.max_total_image_bytes {
-                            if options.extract_images {
-                                images.push((filename.to_string(), img_data.clone()));
-                            }
-                            let bytes_key = format!("{}::{}", slide_info.path, rel_id);
-                            all_image_bytes.insert(bytes_key, img_data);
-                        } else {
-                            warnings.push(ConversionWarning {
-                                code: WarningCode::ResourceLimitReached,
-                                message: format!(
-                                    "total image bytes exceeded limit ({})",
-                                    options.max_total_image_bytes
-                                ),
-                                location: Some(image_path),
-                            });
+                match local_str {
+                    "t" if in_text => in_text = false,
+                    "t" => in_text_body = false,
+                    "r" | "fld" => {
+                        in_run = false;
+                        in_text = false;
+                    }
+                    "p" if in_text_body => {
+                        let para = current_paragraph.trim();
+                        if !para.is_empty() {
+                            paragraphs.push(para.to_string());
                         }
+                        current_paragraph.clear();
                     }
+                    _ => {}
                 }
-            }
 
-            // Set document title from first slide's title
-            if document_title.is_none() {
-                document_title = shapes.iter().find_map(|s| {
-                    if let ShapeContent::Title(t) = s {
-                        Some(t.clone())
-                    } else {
-                        None
+                if point_depth == 0 {
+                    if let Some((model_id, kind)) = current_point.take() {
+                        point_order.push(model_id.clone());
+                        points.insert(
+                            model_id,
+                            DiagramPoint {
+                                kind,
+                                text: paragraphs.join(" "),
+                            },
+                        );
                     }
-                });
+                    paragraphs.clear();
+                    in_text_body = false;
+                    in_run = false;
+                    in_text = false;
+                }
             }
-
-            let (slide_md, slide_plain, slide_image_infos) = render_slide(
-                slide_info.number,
-                &shapes,
-                &notes,
-                &image_filenames,
-                &slide_info.path,
-                &mut image_counter,
-            );
-
-            all_image_infos.extend(slide_image_infos);
-            slide_markdowns.push(slide_md);
-            slide_plains.push(slide_plain);
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
         }
+    }
 
-        // Join slides with horizontal rule separator (markdown) or blank line (plain text)
-        let markdown = slide_markdowns.join("\n\n---\n\n");
-        let markdown = if markdown.is_empty() {
-            markdown
-        } else {
-            format!("{markdown}\n")
-        };
-
-        let plain_text = slide_plains.join("\n\n");
-        let plain_text = if plain_text.is_empty() {
-            plain_text
-        } else {
-            format!("{plain_text}\n")
-        };
+    // Start from the document root(s); without one, treat parentless nodes as roots.
+    let mut roots: Vec<&String> = point_order
+        .iter()
+        .filter(|id| points.get(*id).is_some_and(|p| p.kind == "doc"))
+        .collect();
+    if roots.is_empty() {
+        roots = point_order
+            .iter()
+            .filter(|id| !has_parent.contains(*id))
+            .collect();
+    }
 
-        let result = ConversionResult {
-            markdown,
-            plain_text,
-            title: document_title,
-            images,
-            warnings,
-        };
+    let mut items: Vec<(u8, String)> = Vec::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    for root in roots {
+        visit_diagram_point(root, &points, &children, &mut visited, &mut items);
+    }
 
-        let pending = PendingImageResolution {
-            infos: all_image_infos,
-            bytes: all_image_bytes,
-        };
+    (items, drawing_rel_id)
+}
 
-        Ok((result, pending))
+/// Read the `modelId` and `type` (default `node`) attributes of a `<dgm:pt>`.
+fn diagram_point_identity(e: &quick_xml::events::BytesStart) -> (String, String) {
+    let mut model_id = String::new();
+    let mut kind = "node".to_string();
+    for attr in e.attributes().flatten() {
+        let local_name = attr.key.local_name();
+        let key = std::str::from_utf8(local_name.as_ref()).unwrap_or("");
+        match key {
+            "modelId" => model_id = String::from_utf8_lossy(&attr.value).to_string(),
+            "type" => kind = String::from_utf8_lossy(&attr.value).to_string(),
+            _ => {}
+        }
     }
+    (model_id, kind)
 }
 
-// ---- Converter trait impl ----
-
-impl Converter for PptxConverter {
-    fn supported_extensions(&self) -> &[&str] {
-        &["pptx"]
+/// Record a `<dgm:cxn>` parent/child connection. Only `parOf` (the default type)
+/// connections contribute to the node hierarchy.
+fn record_diagram_connection(
+    e: &quick_xml::events::BytesStart,
+    children: &mut HashMap<String, Vec<(u32, String)>>,
+    has_parent: &mut HashSet<String>,
+) {
+    let mut src = None;
+    let mut dest = None;
+    let mut order: u32 = 0;
+    let mut is_parent_of = true;
+    for attr in e.attributes().flatten() {
+        let local_name = attr.key.local_name();
+        let key = std::str::from_utf8(local_name.as_ref()).unwrap_or("");
+        let val = String::from_utf8_lossy(&attr.value);
+        match key {
+            "srcId" => src = Some(val.to_string()),
+            "destId" => dest = Some(val.to_string()),
+            "srcOrd" => order = val.parse().unwrap_or(0),
+            "type" => is_parent_of = val == "parOf",
+            _ => {}
+        }
     }
-
-    fn convert(
-        &self,
-        data: &[u8],
-        options: &ConversionOptions,
-    ) -> Result<ConversionResult, ConvertError> {
-        let (mut result, pending) = self.convert_inner(data, options)?;
-        resolve_image_placeholders(
-            &mut result.markdown,
-            &mut result.plain_text,
-            &pending.infos,
-            &pending.bytes,
-            options.image_describer.as_deref(),
-            &mut result.warnings,
-        );
-        Ok(result)
+    if is_parent_of && let (Some(src), Some(dest)) = (src, dest) {
+        has_parent.insert(dest.clone());
+        children.entry(src).or_default().push((order, dest));
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    // ---- Helper: build minimal PPTX ZIP in memory ----
-
-    struct TestSlide<'a> {
-        title: Option<&'a str>,
-        body_texts: Vec<&'a str>,
-        notes: Option<&'a str>,
-        table: Option<TestTable<'a>>,
-        images: Vec<&'a str>,                  // rel IDs for image references
-        image_alt_texts: Vec<Option<&'a str>>, // alt text per image (parallel to images)
+/// Read the `relId` attribute of a `<dsp:dataModelExt>` element.
+fn diagram_drawing_rel_id(e: &quick_xml::events::BytesStart) -> Option<String> {
+    for attr in e.attributes().flatten() {
+        let local_name = attr.key.local_name();
+        if local_name.as_ref() == b"relId" {
+            let val = String::from_utf8_lossy(&attr.value).to_string();
+            if !val.is_empty() {
+                return Some(val);
+            }
+        }
     }
+    None
+}
 
-    struct TestTable<'a> {
-        headers: Vec<&'a str>,
-        rows: Vec<Vec<&'a str>>,
+/// Depth-first walk of the SmartArt hierarchy, emitting text-bearing nodes.
+///
+/// Nodes without text are transparent: their children stay at the same level.
+/// Transition and presentation points are skipped entirely. The walk uses an
+/// explicit stack, so deep hierarchies cannot overflow the call stack.
+fn visit_diagram_point(
+    root: &str,
+    points: &HashMap<String, DiagramPoint>,
+    children: &HashMap<String, Vec<(u32, String)>>,
+    visited: &mut HashSet<String>,
+    items: &mut Vec<(u8, String)>,
+) {
+    let mut stack: Vec<(&str, u8)> = vec![(root, 0)];
+    while let Some((id, level)) = stack.pop() {
+        if !visited.insert(id.to_string()) {
+            continue;
+        }
+        let Some(point) = points.get(id) else {
+            continue;
+        };
+        let child_level = match point.kind.as_str() {
+            "node" | "asst" if !point.text.is_empty() => {
+                items.push((level, point.text.clone()));
+                level.saturating_add(1)
+            }
+            "doc" | "node" | "asst" => level,
+            _ => continue,
+        };
+        if let Some(kids) = children.get(id) {
+            let mut kids: Vec<&(u32, String)> = kids.iter().collect();
+            kids.sort_by_key(|(order, _)| *order);
+            // Pushed in reverse so the first child is visited first
+            stack.extend(
+                kids.into_iter()
+                    .rev()
+                    .map(|(_, kid)| (kid.as_str(), child_level)),
+            );
+        }
     }
+}
 
-    /// Build a minimal PPTX ZIP in memory.
-    fn build_test_pptx(slides: &[TestSlide]) -> Vec<u8> {
-        use std::io::Write;
-        use zip::ZipWriter;
-        use zip::write::SimpleFileOptions;
+/// Parse a pre-rendered SmartArt drawing (`ppt/diagrams/drawingN.xml`).
+///
+/// The drawing carries no hierarchy, so each shape's text becomes a top-level item.
+fn parse_diagram_drawing(xml: &str) -> Vec<(u8, String)> {
+    let mut reader = Reader::from_str(xml);
+    let mut items: Vec<(u8, String)> = Vec::new();
 
-        let buf = Vec::new();
-        let mut zip = ZipWriter::new(Cursor::new(buf));
-        let opts = SimpleFileOptions::default();
+    let mut in_shape = false;
+    let mut in_text_body = false;
+    let mut in_run = false;
+    let mut in_text = false;
+    let mut current_paragraph = String::new();
+    let mut paragraphs: Vec<String> = Vec::new();
 
-        // [Content_Types].xml
-        let mut ct = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
-        ct.push_str(
-            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
-        );
-        ct.push_str(
-            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
-        );
-        ct.push_str(r#"<Default Extension="xml" ContentType="application/xml"/>"#);
-        ct.push_str("</Types>");
-        zip.start_file("[Content_Types].xml", opts).unwrap();
-        zip.write_all(ct.as_bytes()).unwrap();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => {
+                let local = e.local_name();
+                match local.as_ref() {
+                    b"sp" => {
+                        in_shape = true;
+                        paragraphs.clear();
+                    }
+                    b"txBody" if in_shape => in_text_body = true,
+                    b"p" if in_text_body => current_paragraph.clear(),
+                    b"r" | b"fld" if in_text_body => in_run = true,
+                    b"t" if in_run => in_text = true,
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(ref e)) if in_text_body && e.local_name().as_ref() == b"br" => {
+                current_paragraph.push(' ');
+            }
+            Ok(Event::Text(ref e)) if in_text => {
+                let text = e.unescape().unwrap_or_default().to_string();
+                current_paragraph.push_str(&text);
+            }
+            Ok(Event::End(ref e)) => {
+                let local = e.local_name();
+                match local.as_ref() {
+                    b"t" => in_text = false,
+                    b"r" | b"fld" => {
+                        in_run = false;
+                        in_text = false;
+                    }
+                    b"p" if in_text_body => {
+                        let para = current_paragraph.trim();
+                        if !para.is_empty() {
+                            paragraphs.push(para.to_string());
+                        }
+                        current_paragraph.clear();
+                    }
+                    b"txBody" => in_text_body = false,
+                    b"sp" if in_shape => {
+                        if !paragraphs.is_empty() {
+                            items.push((0, paragraphs.join(" ")));
+                        }
+                        in_shape = false;
+                        paragraphs.clear();
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+    }
 
-        // Build presentation.xml with slide references
-        let mut pres_xml = String::from(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst>"#,
-        );
-        let mut pres_rels_xml = String::from(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
-        );
+    items
+}
 
-        for (i, slide) in slides.iter().enumerate() {
-            let slide_num = i + 1;
-            let rid = format!("rId{slide_num}");
-            let slide_id = 256 + i;
+/// Load the list items for a SmartArt graphic frame.
+///
+/// Reads the data model referenced by `rel_id`; if it is missing or yields no
+/// text, falls back to the pre-rendered drawing part. Returns `None` when
+/// neither part can be found in the package.
+fn load_diagram_items(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    slide_path: &str,
+    slide_rels: &HashMap<String, Relationship>,
+    rel_id: &str,
+) -> Result<Option<Vec<(u8, String)>>, ConvertError> {
+    let data_path = slide_rels
+        .get(rel_id)
+        .map(|rel| resolve_relative_to_file(slide_path, &rel.target));
+
+    let mut found = false;
+    let mut drawing_path: Option<String> = None;
+    if let Some(ref path) = data_path
+        && let Some(xml) = read_zip_text(archive, path)?
+    {
+        found = true;
+        let (items, drawing_rel_id) = parse_diagram_data(&xml);
+        if !items.is_empty() {
+            return Ok(Some(items));
+        }
+        drawing_path = drawing_rel_id
+            .and_then(|id| slide_rels.get(&id))
+            .map(|rel| resolve_relative_to_file(slide_path, &rel.target));
+    }
 
-            pres_xml.push_str(&format!(r#"<p:sldId id="{slide_id}" r:id="{rid}"/>"#));
-            pres_rels_xml.push_str(&format!(
-                r#"<Relationship Id="{rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{slide_num}.xml"/>"#
-            ));
+    // PowerPoint names the parts in pairs: diagrams/dataN.xml ↔ diagrams/drawingN.xml
+    let drawing_path = drawing_path.or_else(|| {
+        let path = data_path?;
+        let (dir, file) = path.rsplit_once('/').unwrap_or(("", &path));
+        let suffix = file.strip_prefix("data")?;
+        Some(format!("{dir}/drawing{suffix}"))
+    });
 
-            // Build slide XML
-            let slide_xml = build_slide_xml(slide);
-            zip.start_file(format!("ppt/slides/slide{slide_num}.xml"), opts)
-                .unwrap();
-            zip.write_all(slide_xml.as_bytes()).unwrap();
+    if let Some(path) = drawing_path
+        && let Some(xml) = read_zip_text(archive, &path)?
+    {
+        return Ok(Some(parse_diagram_drawing(&xml)));
+    }
 
-            // Build slide rels if notes or images exist
-            if slide.notes.is_some() || !slide.images.is_empty() {
-                let mut slide_rels = String::from(
-                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
-                );
+    Ok(if found { Some(Vec::new()) } else { None })
+}
 
-                if slide.notes.is_some() {
-                    slide_rels.push_str(&format!(
-                        r#"<Relationship Id="rIdNotes" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{slide_num}.xml"/>"#
-                    ));
-                }
+// ---- Reading order ----
 
-                for (img_idx, _) in slide.images.iter().enumerate() {
-                    let img_rid = format!("rIdImg{}", img_idx + 1);
-                    slide_rels.push_str(&format!(
-                        r#"<Relationship Id="{img_rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}.png"/>"#,
-                        img_idx + 1
-                    ));
-                }
+/// Overlaps smaller than this (0.05 inch) still count as a gap between shapes.
+const READING_ORDER_TOLERANCE_EMU: f64 = 45_720.0;
 
-                slide_rels.push_str("</Relationships>");
-                zip.start_file(format!("ppt/slides/_rels/slide{slide_num}.xml.rels"), opts)
-                    .unwrap();
-                zip.write_all(slide_rels.as_bytes()).unwrap();
-            }
+/// Compute a visual reading order for shapes with the given bounds.
+///
+/// Returns shape indices. Shapes without a position (layout placeholders) come
+/// first in XML order. Positioned shapes are ordered by recursive XY-cut: split
+/// into horizontal bands wherever no shape spans a vertical gap, then split each
+/// band into columns at horizontal gaps, and so on. Shapes that cannot be
+/// separated (overlapping) fall back to top-to-bottom, left-to-right.
+fn reading_order(bounds: &[Option<ShapeBounds>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..bounds.len()).filter(|&i| bounds[i].is_none()).collect();
+    let positioned: Vec<(usize, ShapeBounds)> = bounds
+        .iter()
+        .enumerate()
+        .filter_map(|(i, b)| b.map(|b| (i, b)))
+        .collect();
+    xy_cut(positioned, &mut order);
+    order
+}
 
-            // Build notes slide if present
-            if let Some(notes_text) = slide.notes {
-                let notes_xml = build_notes_xml(notes_text);
-                zip.start_file(format!("ppt/notesSlides/notesSlide{slide_num}.xml"), opts)
-                    .unwrap();
-                zip.write_all(notes_xml.as_bytes()).unwrap();
-            }
+fn xy_cut(mut items: Vec<(usize, ShapeBounds)>, order: &mut Vec<usize>) {
+    if items.len() > 1 {
+        let bands = split_at_gaps(&items, |b| (b.y, b.y + b.cy));
+        if bands.len() > 1 {
+            bands.into_iter().for_each(|band| xy_cut(band, order));
+            return;
         }
+        let columns = split_at_gaps(&items, |b| (b.x, b.x + b.cx));
+        if columns.len() > 1 {
+            columns.into_iter().for_each(|column| xy_cut(column, order));
+            return;
+        }
+        items.sort_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)));
+    }
+    order.extend(items.iter().map(|(i, _)| *i));
+}
 
-        pres_xml.push_str("</p:sldIdLst></p:presentation>");
-        pres_rels_xml.push_str("</Relationships>");
+/// Partition items into groups separated by gaps along one axis, in axis order.
+fn split_at_gaps(
+    items: &[(usize, ShapeBounds)],
+    span: impl Fn(&ShapeBounds) -> (f64, f64),
+) -> Vec<Vec<(usize, ShapeBounds)>> {
+    let mut sorted = items.to_vec();
+    sorted.sort_by(|a, b| span(&a.1).0.total_cmp(&span(&b.1).0));
+
+    let mut groups: Vec<Vec<(usize, ShapeBounds)>> = Vec::new();
+    let mut group_end = f64::NEG_INFINITY;
+    for item in sorted {
+        let (start, end) = span(&item.1);
+        match groups.last_mut() {
+            Some(group) if start < group_end - READING_ORDER_TOLERANCE_EMU => group.push(item),
+            _ => groups.push(vec![item]),
+        }
+        group_end = group_end.max(end);
+    }
+    groups
+}
 
-        zip.start_file("ppt/presentation.xml", opts).unwrap();
-        zip.write_all(pres_xml.as_bytes()).unwrap();
+// ---- Media and embedded objects ----
 
-        zip.start_file("ppt/_rels/presentation.xml.rels", opts)
-            .unwrap();
-        zip.write_all(pres_rels_xml.as_bytes()).unwrap();
+/// Last path segment of a relationship target, for both package paths and
+/// external links (`file:///C:\\clips\\intro.mp4`, `https://...`).
+fn target_file_name(target: &str) -> String {
+    target
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(target)
+        .to_string()
+}
 
-        let cursor = zip.finish().unwrap();
-        cursor.into_inner()
+const REL_TYPE_VIDEO: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/video";
+const REL_TYPE_AUDIO: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/audio";
+
+/// Media kind declared by a relationship type. The generic `p14:media`
+/// relationship says nothing about the kind, so the part's content type
+/// (`video/*` or `audio/*`) decides.
+fn media_kind(rel_type: &str, content_type: Option<&str>) -> Option<MediaKind> {
+    match rel_type {
+        REL_TYPE_VIDEO => Some(MediaKind::Video),
+        REL_TYPE_AUDIO => Some(MediaKind::Audio),
+        _ => match content_type?.split('/').next()? {
+            "video" => Some(MediaKind::Video),
+            "audio" => Some(MediaKind::Audio),
+            _ => None,
+        },
     }
+}
 
-    /// Build the XML for a single slide.
-    fn build_slide_xml(slide: &TestSlide) -> String {
-        let mut xml = String::from(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree>"#,
-        );
+/// Content types declared in `[Content_Types].xml`.
+#[derive(Debug, Default)]
+struct ContentTypes {
+    /// Lowercased extension -> content type (`<Default>`).
+    defaults: HashMap<String, String>,
+    /// Part path without leading slash -> content type (`<Override>`).
+    overrides: HashMap<String, String>,
+}
 
-        // Title shape
-        if let Some(title) = slide.title {
-            xml.push_str(&format!(
-                r#"<p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>{title}</a:t></a:r></a:p></p:txBody></p:sp>"#
-            ));
+impl ContentTypes {
+    /// Content type of the part at `path` (a ZIP entry name).
+    fn content_type(&self, path: &str) -> Option<&str> {
+        if let Some(ct) = self.overrides.get(path) {
+            return Some(ct);
         }
+        let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        self.defaults.get(&ext).map(String::as_str)
+    }
+}
 
-        // Body text shapes
-        for text in &slide.body_texts {
-            xml.push_str(&format!(
-                r#"<p:sp><p:nvSpPr><p:cNvPr id="2" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p></p:txBody></p:sp>"#
-            ));
+fn parse_content_types(xml: &str) -> ContentTypes {
+    let mut types = ContentTypes::default();
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                let local = e.local_name();
+                let mut key = None;
+                let mut content_type = None;
+                for attr in e.attributes().flatten() {
+                    let val = String::from_utf8_lossy(&attr.value).to_string();
+                    match attr.key.as_ref() {
+                        b"Extension" => key = Some(val.to_ascii_lowercase()),
+                        b"PartName" => key = Some(val.trim_start_matches('/').to_string()),
+                        b"ContentType" => content_type = Some(val.to_ascii_lowercase()),
+                        _ => {}
+                    }
+                }
+                if let (Some(key), Some(content_type)) = (key, content_type) {
+                    match local.as_ref() {
+                        b"Default" => types.defaults.insert(key, content_type),
+                        b"Override" => types.overrides.insert(key, content_type),
+                        _ => None,
+                    };
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
         }
+    }
+    types
+}
 
-        // Table
-        if let Some(table) = &slide.table {
-            xml.push_str(r#"<p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="3" name="Table"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><a:graphic><a:graphicData><a:tbl>"#);
+/// Skip exactly `n` bytes of a reader without buffering them.
+fn skip_bytes(reader: &mut impl Read, n: u64) -> Option<()> {
+    let skipped = std::io::copy(&mut reader.take(n), &mut std::io::sink()).ok()?;
+    (skipped == n).then_some(())
+}
 
-            // Header row
-            xml.push_str("<a:tr>");
-            for header in &table.headers {
-                xml.push_str(&format!(
-                    r#"<a:tc><a:txBody><a:p><a:r><a:t>{header}</a:t></a:r></a:p></a:txBody></a:tc>"#
-                ));
-            }
-            xml.push_str("</a:tr>");
+fn read_array<const N: usize>(reader: &mut impl Read) -> Option<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf).ok()?;
+    Some(buf)
+}
 
-            // Data rows
-            for row in &table.rows {
-                xml.push_str("<a:tr>");
-                for cell in row {
-                    xml.push_str(&format!(
-                        r#"<a:tc><a:txBody><a:p><a:r><a:t>{cell}</a:t></a:r></a:p></a:txBody></a:tc>"#
-                    ));
-                }
-                xml.push_str("</a:tr>");
-            }
+/// How many bytes of a compressed media entry are scanned for its duration.
+/// Stored (uncompressed) entries are seeked instead and have no limit.
+const MEDIA_SCAN_LIMIT: u64 = 16 * 1024 * 1024;
 
-            xml.push_str("</a:tbl></a:graphicData></a:graphic></p:graphicFrame>");
-        }
+/// Read the playback duration of an embedded media part, in seconds.
+///
+/// Supports MP4/MOV/M4A (`mvhd` box) and WAV (`fmt `/`data` chunks). Stored
+/// entries are seeked through, so a `moov` box after a large `mdat` costs
+/// nothing; compressed entries are streamed up to [`MEDIA_SCAN_LIMIT`] bytes.
+/// Returns `None` for other containers, truncated data, or a header beyond
+/// the scan limit.
+fn media_duration_secs(archive: &mut ZipArchive<Cursor<&[u8]>>, path: &str) -> Option<f64> {
+    if let Ok(mut file) = archive.by_name_seek(path) {
+        return read_media_duration(&mut file, seek_bytes);
+    }
+    let mut file = archive.by_name(path).ok()?.take(MEDIA_SCAN_LIMIT);
+    read_media_duration(&mut file, skip_bytes)
+}
 
-        // Image shapes
-        for (idx, rel_id) in slide.images.iter().enumerate() {
-            let descr_attr = slide
-                .image_alt_texts
-                .get(idx)
-                .and_then(|a| *a)
-                .map(|alt| format!(r#" descr="{alt}""#))
-                .unwrap_or_default();
-            xml.push_str(&format!(
-                r#"<p:pic><p:nvPicPr><p:cNvPr id="{}"{descr_attr} name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="{rel_id}"/></p:blipFill></p:pic>"#,
-                10 + idx
-            ));
-        }
+/// Skip `n` bytes of a seekable reader.
+fn seek_bytes<R: Read + Seek>(reader: &mut R, n: u64) -> Option<()> {
+    reader
+        .seek(SeekFrom::Current(i64::try_from(n).ok()?))
+        .ok()
+        .map(|_| ())
+}
 
-        xml.push_str("</p:spTree></p:cSld></p:sld>");
-        xml
+/// Parse a media duration from `file`, using `skip` to jump over payloads.
+fn read_media_duration<R: Read>(file: &mut R, skip: fn(&mut R, u64) -> Option<()>) -> Option<f64> {
+    let header: [u8; 12] = read_array(file)?;
+
+    if &header[0..4] == b"RIFF" && &header[8..12] == b"WAVE" {
+        let mut byte_rate: u32 = 0;
+        loop {
+            let chunk: [u8; 8] = read_array(file)?;
+            let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+            match &chunk[0..4] {
+                b"fmt " if size >= 12 => {
+                    let fmt: [u8; 12] = read_array(file)?;
+                    byte_rate = u32::from_le_bytes([fmt[8], fmt[9], fmt[10], fmt[11]]);
+                    skip(file, u64::from(size - 12 + (size & 1)))?;
+                }
+                b"data" if byte_rate > 0 => return Some(f64::from(size) / f64::from(byte_rate)),
+                _ => skip(file, u64::from(size) + u64::from(size & 1))?,
+            }
+        }
     }
 
-    /// Build the XML for a notes slide.
-    fn build_notes_xml(text: &str) -> String {
-        // Split text by newlines to create separate paragraphs
-        let paragraphs: Vec<&str> = text.lines().collect();
-        let mut para_xml = String::new();
-        for p in &paragraphs {
-            para_xml.push_str(&format!(r#"<a:p><a:r><a:t>{p}</a:t></a:r></a:p>"#));
+    if &header[4..8] == b"ftyp" {
+        let ftyp_size = u64::from(u32::from_be_bytes([
+            header[0], header[1], header[2], header[3],
+        ]));
+        skip(file, ftyp_size.checked_sub(12)?)?;
+        // Walk top-level boxes to `moov`, then its children to `mvhd`
+        let mut in_moov = false;
+        loop {
+            let (box_type, payload) = read_mp4_box_header(file)?;
+            match &box_type {
+                b"moov" if !in_moov => in_moov = true,
+                b"mvhd" if in_moov => {
+                    let version: [u8; 4] = read_array(file)?;
+                    let (timescale, duration) = if version[0] == 1 {
+                        skip(file, 16)?;
+                        let timescale = u32::from_be_bytes(read_array(file)?);
+                        (timescale, u64::from_be_bytes(read_array(file)?))
+                    } else {
+                        skip(file, 8)?;
+                        let timescale = u32::from_be_bytes(read_array(file)?);
+                        (timescale, u64::from(u32::from_be_bytes(read_array(file)?)))
+                    };
+                    return (timescale > 0).then(|| duration as f64 / f64::from(timescale));
+                }
+                _ => skip(file, payload)?,
+            }
         }
-
-        format!(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:notes xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Slide Number"/><p:cNvSpPr/><p:nvPr><p:ph type="sldNum"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>1</a:t></a:r></a:p></p:txBody></p:sp><p:sp><p:nvSpPr><p:cNvPr id="2" name="Notes"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody>{para_xml}</p:txBody></p:sp></p:spTree></p:cSld></p:notes>"#
-        )
     }
 
-    // ---- Tests ----
+    None
+}
 
-    #[test]
-    fn test_pptx_supported_extensions() {
-        let converter = PptxConverter;
-        assert_eq!(converter.supported_extensions(), &["pptx"]);
-    }
+/// Read an MP4 box header, returning the box type and payload size.
+fn read_mp4_box_header(reader: &mut impl Read) -> Option<([u8; 4], u64)> {
+    let header: [u8; 8] = read_array(reader)?;
+    let size = u64::from(u32::from_be_bytes([
+        header[0], header[1], header[2], header[3],
+    ]));
+    let box_type = [header[4], header[5], header[6], header[7]];
+    let payload = match size {
+        1 => u64::from_be_bytes(read_array(reader)?).checked_sub(16)?,
+        // Size 0 means "extends to end of file"; the caller stops at EOF anyway
+        0 => u64::MAX,
+        _ => size.checked_sub(8)?,
+    };
+    Some((box_type, payload))
+}
 
-    #[test]
-    fn test_pptx_can_convert() {
-        let converter = PptxConverter;
-        assert!(converter.can_convert("pptx", &[]));
-        assert!(!converter.can_convert("docx", &[]));
-        assert!(!converter.can_convert("xlsx", &[]));
-        assert!(!converter.can_convert("pdf", &[]));
+/// Format a duration as `m:ss` (or `h:mm:ss` for an hour or more).
+fn format_duration(secs: f64) -> String {
+    let total = secs.round() as u64;
+    let (h, m, s) = (total / 3600, (total % 3600) / 60, total % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
     }
+}
 
-    #[test]
-    fn test_pptx_invalid_data_returns_error() {
-        let converter = PptxConverter;
-        let result = converter.convert(b"not a valid pptx file", &ConversionOptions::default());
-        assert!(result.is_err());
+/// Shift ATX headings down by `levels` (capped at `######`), leaving fenced code alone.
+///
+/// Used to nest converted embedded documents below the `## Slide N` heading.
+fn demote_headings(markdown: &str, levels: usize) -> String {
+    let mut out = String::with_capacity(markdown.len() + 16);
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let hashes = line.len() - line.trim_start_matches('#').len();
+        if !in_fence
+            && (1..=6).contains(&hashes)
+            && (line.len() == hashes || line[hashes..].starts_with(' '))
+        {
+            out.push_str(&"#".repeat((hashes + levels).min(6)));
+            out.push_str(&line[hashes..]);
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
     }
+    out
+}
 
-    #[test]
-    fn test_pptx_empty_presentation() {
+/// Convert an embedded Office package with the matching converter.
+///
+/// Returns `(markdown, plain_text)`, or `None` when the file type is not a
+/// convertible package. Conversion failures are reported as warnings. The
+/// nested conversion gets what is left of the image byte budget, and the bytes
+/// of its images are added to `total_image_bytes`.
+fn convert_embedded_package(
+    bytes: &[u8],
+    file_name: &str,
+    options: &ConversionOptions,
+    images: &mut Vec<(String, Vec<u8>)>,
+    total_image_bytes: &mut usize,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Option<(String, String)> {
+    // Macro-enabled variants share the package layout of their base format
+    let extension = match file_name.rsplit('.').next()?.to_ascii_lowercase().as_str() {
+        "xlsx" | "xlsm" => "xlsx",
+        "docx" | "docm" => "docx",
+        "pptx" | "pptm" => "pptx",
+        _ => return None,
+    };
+
+    // Embedded packages are converted one level deep only
+    let mut nested_options = options.clone();
+    nested_options.pptx.convert_embedded = false;
+    nested_options.max_total_image_bytes = options
+        .max_total_image_bytes
+        .saturating_sub(*total_image_bytes);
+
+    match crate::convert_bytes(bytes, extension, &nested_options) {
+        Ok(result) => {
+            warnings.extend(result.warnings.into_iter().map(|mut w| {
+                w.location = Some(match w.location {
+                    Some(loc) => format!("{file_name}: {loc}"),
+                    None => file_name.to_string(),
+                });
+                w
+            }));
+            *total_image_bytes += result
+                .images
+                .iter()
+                .map(|(_, data)| data.len())
+                .sum::<usize>();
+            images.extend(result.images);
+            Some((
+                demote_headings(result.markdown.trim_end(), 2),
+                result.plain_text,
+            ))
+        }
+        Err(e) => {
+            warnings.push(ConversionWarning {
+                code: WarningCode::SkippedElement,
+                message: format!("failed to convert embedded object: {e}"),
+                location: Some(file_name.to_string()),
+            });
+            None
+        }
+    }
+}
+
+// ---- Markdown rendering ----
+
+/// Render a single slide's content as Markdown.
+///
+/// Images are emitted with unique placeholder alt text `__img_N__`.
+/// `image_counter` is incremented for each image to ensure uniqueness.
+/// Returns (markdown, image_infos).
+/// Render a slide into both markdown and plain text.
+///
+/// Returns `(markdown, plain_text, image_infos)`.
+fn render_slide(
+    slide: &SlideContent,
+    image_filenames: &HashMap<String, String>,
+    slide_key: &str,
+    image_counter: &mut usize,
+) -> (String, String, Vec<ImageInfo>) {
+    let mut out = String::new();
+    let mut plain = String::new();
+    let mut image_infos: Vec<ImageInfo> = Vec::new();
+    let number = slide.number;
+    let shapes = &slide.shapes;
+    let hidden_suffix = if slide.hidden { " (hidden)" } else { "" };
+
+    // Find the title
+    let title = shapes.iter().find_map(|s| {
+        if let ShapeContent::Title(t) = s {
+            Some(t.as_str())
+        } else {
+            None
+        }
+    });
+
+    // Slide heading
+    if let Some(title_text) = title {
+        out.push_str(&format!(
+            "## Slide {number}: {title_text}{hidden_suffix}\n\n"
+        ));
+        plain.push_str(&format!("{title_text}{hidden_suffix}\n\n"));
+    } else {
+        out.push_str(&format!("## Slide {number}{hidden_suffix}\n\n"));
+        plain.push_str(hidden_suffix.trim_start());
+        plain.push('\n');
+    }
+
+    // Body content, tables, and images (skip title since it's already in heading)
+    for shape in shapes {
+        match shape {
+            ShapeContent::Title(_) => {} // Already rendered as heading
+            ShapeContent::Body(text) => {
+                out.push_str(text);
+                out.push_str("\n\n");
+                plain.push_str(text);
+                plain.push_str("\n\n");
+            }
+            ShapeContent::Table { headers, rows } => {
+                let header_refs: Vec<&str> = headers.iter().map(|s| s.as_str()).collect();
+                let row_refs: Vec<Vec<&str>> = rows
+                    .iter()
+                    .map(|r| r.iter().map(|s| s.as_str()).collect())
+                    .collect();
+                out.push_str(&build_table(&header_refs, &row_refs));
+                out.push('\n');
+                let plain_table = build_table_plain(&header_refs, &row_refs);
+                // A table without a header row has an all-empty header: omit it
+                if headers.iter().all(|h| h.is_empty()) {
+                    plain.push_str(plain_table.split_once('\n').map_or("", |(_, rest)| rest));
+                } else {
+                    plain.push_str(&plain_table);
+                }
+                plain.push('\n');
+            }
+            ShapeContent::Image { rel_id, alt_text } => {
+                if let Some(filename) = image_filenames.get(rel_id) {
+                    let original_alt = alt_text.as_deref().unwrap_or("").to_string();
+                    let placeholder = format!("__img_{n}__", n = *image_counter);
+                    *image_counter += 1;
+                    image_infos.push(ImageInfo {
+                        placeholder: placeholder.clone(),
+                        original_alt: original_alt.clone(),
+                        filename: filename.clone(),
+                        bytes_key: format!("{slide_key}::{rel_id}"),
+                    });
+                    out.push_str(&format!("![{placeholder}]({filename})\n\n"));
+                    // Plain text: image description placeholder (resolved later)
+                    plain.push_str(&format!("{placeholder}\n\n"));
+                }
+            }
+            ShapeContent::Media {
+                kind,
+                file_name,
+                duration_secs,
+                ..
+            } => {
+                let label = match kind {
+                    MediaKind::Video => "Video",
+                    MediaKind::Audio => "Audio",
+                };
+                let mut placeholder = format!("[{label}");
+                if !file_name.is_empty() {
+                    placeholder.push_str(&format!(": {file_name}"));
+                }
+                if let Some(secs) = duration_secs {
+                    placeholder.push_str(&format!(" ({})", format_duration(*secs)));
+                }
+                placeholder.push(']');
+                out.push_str(&placeholder);
+                out.push_str("\n\n");
+                plain.push_str(&placeholder);
+                plain.push_str("\n\n");
+            }
+            ShapeContent::Embedded {
+                prog_id,
+                file_name,
+                converted,
+                ..
+            } => {
+                let mut placeholder = "[Embedded object".to_string();
+                if !file_name.is_empty() {
+                    placeholder.push_str(&format!(": {file_name}"));
+                }
+                if let Some(prog_id) = prog_id {
+                    placeholder.push_str(&format!(" ({prog_id})"));
+                }
+                placeholder.push(']');
+                out.push_str(&placeholder);
+                out.push_str("\n\n");
+                plain.push_str(&placeholder);
+                plain.push_str("\n\n");
+                if let Some((md, text)) = converted {
+                    out.push_str(md.trim_end());
+                    out.push_str("\n\n");
+                    plain.push_str(text.trim_end());
+                    plain.push_str("\n\n");
+                }
+            }
+            ShapeContent::Diagram { items, .. } => {
+                if !items.is_empty() {
+                    for (level, text) in items {
+                        out.push_str(&format_list_item(*level, false, 0, text));
+                        out.push('\n');
+                        plain.push_str(&format_list_item_plain(*level, text));
+                        plain.push('\n');
+                    }
+                    out.push('\n');
+                    plain.push('\n');
+                }
+            }
+        }
+    }
+
+    // Notes
+    if let Some(notes_text) = &slide.notes {
+        let lines: Vec<&str> = notes_text.lines().collect();
+        if !lines.is_empty() {
+            out.push_str(&format!("> Note: {}", lines[0]));
+            for line in &lines[1..] {
+                out.push_str(&format!("\n> {line}"));
+            }
+            out.push_str("\n\n");
+            // Plain text: notes without blockquote prefix
+            plain.push_str(notes_text);
+            plain.push_str("\n\n");
+        }
+    }
+
+    // Comments
+    if !slide.comments.is_empty() {
+        out.push_str("### Comments\n\n");
+        plain.push_str("Comments\n");
+        for comment in &slide.comments {
+            let level = u8::from(comment.is_reply);
+            let entry = format!("{}: {}", comment.author, comment.text);
+            out.push_str(&format_list_item(level, false, 0, &entry));
+            out.push('\n');
+            plain.push_str(&format_list_item_plain(level, &entry));
+            plain.push('\n');
+        }
+    }
+
+    // Trim trailing whitespace
+    (
+        out.trim_end().to_string(),
+        plain.trim_end().to_string(),
+        image_infos,
+    )
+}
+
+// ---- Converter trait impl ----
+
+// ---- Internal conversion (parse + image extraction, no resolution) ----
+
+impl PptxConverter {
+    /// Parse the presentation and extract images without resolving placeholders.
+    ///
+    /// Returns the conversion result (with unresolved placeholders in markdown)
+    /// and pending image data for later resolution (sync or async).
+    pub(crate) fn convert_inner(
+        &self,
+        data: &[u8],
+        options: &ConversionOptions,
+    ) -> Result<(ConversionResult, PendingImageResolution), ConvertError> {
+        let cursor = Cursor::new(data);
+        let mut archive = ZipArchive::new(cursor)?;
+
+        crate::zip_utils::validate_zip_budget(&mut archive, options.max_uncompressed_zip_bytes)?;
+
+        let mut warnings: Vec<ConversionWarning> = Vec::new();
+        let mut images: Vec<(String, Vec<u8>)> = Vec::new();
+
+        // 1. Parse presentation.xml.rels (optional but needed for slide resolution)
+        let pres_rels = match read_zip_text(&mut archive, "ppt/_rels/presentation.xml.rels")? {
+            Some(xml) => parse_relationships(&xml),
+            None => HashMap::new(),
+        };
+
+        // 2. Parse presentation.xml (required)
+        let pres_xml = read_zip_text(&mut archive, "ppt/presentation.xml")?.ok_or_else(|| {
+            ConvertError::MalformedDocument {
+                reason: "missing ppt/presentation.xml".to_string(),
+            }
+        })?;
+
+        // 3. Resolve slide order, then apply the caller's slide selection
+        let mut slides = resolve_slide_order(&pres_xml, &pres_rels);
+        if let Some(ref selection) = options.pptx.slides {
+            slides.retain(|slide| selection.contains(&slide.number));
+        }
+
+        if slides.is_empty() {
+            return Ok((
+                ConversionResult {
+                    markdown: String::new(),
+                    ..Default::default()
+                },
+                PendingImageResolution::default(),
+            ));
+        }
+
+        // 4. Process each slide — collect all image infos and bytes across slides
+        let mut slide_markdowns: Vec<String> = Vec::new();
+        let mut slide_plains: Vec<String> = Vec::new();
+        let mut document_title: Option<String> = None;
+        let mut total_image_bytes: usize = 0;
+        let mut image_counter: usize = 0;
+        let mut all_image_infos: Vec<ImageInfo> = Vec::new();
+        let mut all_image_bytes: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut previous_section: Option<&str> = None;
+        // Read on first use to tell audio from video behind generic media relationships
+        let mut content_types: Option<ContentTypes> = None;
+
+        // Comment authors are shared by all slides; only needed when comments are requested
+        let mut comment_authors: HashMap<String, String> = HashMap::new();
+        if options.pptx.comments {
+            for path in ["ppt/commentAuthors.xml", "ppt/authors.xml"] {
+                if let Some(xml) = read_zip_text(&mut archive, path)? {
+                    parse_comment_authors(&xml, &mut comment_authors);
+                }
+            }
+        }
+
+        for slide_info in &slides {
+            // Read slide XML
+            let slide_xml = match read_zip_text(&mut archive, &slide_info.path)? {
+                Some(xml) => xml,
+                None => {
+                    warnings.push(ConversionWarning {
+                        code: WarningCode::SkippedElement,
+                        message: format!("slide file not found: {}", slide_info.path),
+                        location: Some(slide_info.path.clone()),
+                    });
+                    continue;
+                }
+            };
+
+            let hidden = is_slide_hidden(&slide_xml);
+            if hidden && options.pptx.hidden_slides == HiddenSlides::Skip {
+                warnings.push(ConversionWarning {
+                    code: WarningCode::SkippedElement,
+                    message: format!("skipped hidden slide {}", slide_info.number),
+                    location: Some(slide_info.path.clone()),
+                });
+                continue;
+            }
+
+            // Parse slide content
+            let (mut shapes, bounds, mut slide_warnings) = parse_slide(&slide_xml);
+            warnings.append(&mut slide_warnings);
+
+            if options.pptx.reading_order {
+                let mut rank = vec![0; shapes.len()];
+                for (position, index) in reading_order(&bounds).into_iter().enumerate() {
+                    rank[index] = position;
+                }
+                let mut ranked: Vec<(usize, ShapeContent)> =
+                    shapes.into_iter().enumerate().collect();
+                ranked.sort_by_key(|(index, _)| rank[*index]);
+                shapes = ranked.into_iter().map(|(_, shape)| shape).collect();
+            }
+
+            // Read slide rels for notes, images, and diagrams
+            let slide_rels_path = derive_rels_path(&slide_info.path);
+            let slide_rels = match read_zip_text(&mut archive, &slide_rels_path)? {
+                Some(xml) => parse_relationships(&xml),
+                None => HashMap::new(),
+            };
+
+            // Resolve SmartArt diagram text from the diagram data parts
+            for shape in &mut shapes {
+                if let ShapeContent::Diagram { rel_id, items } = shape {
+                    match load_diagram_items(&mut archive, &slide_info.path, &slide_rels, rel_id)? {
+                        Some(loaded) => *items = loaded,
+                        None => warnings.push(ConversionWarning {
+                            code: WarningCode::SkippedElement,
+                            message: format!("SmartArt diagram data not found for '{rel_id}'"),
+                            location: Some(slide_info.path.clone()),
+                        }),
+                    }
+                }
+            }
+
+            // Parse notes
+            let notes = if let Some(notes_target) = resolve_notes_path(&slide_rels) {
+                let notes_path = resolve_relative_to_file(&slide_info.path, &notes_target);
+                match read_zip_text(&mut archive, &notes_path)? {
+                    Some(xml) => parse_notes(&xml),
+                    None => None,
+                }
+            } else {
+                None
+            };
+
+            // Media and OLE objects: resolve file names, durations, and embedded packages
+            for shape in &mut shapes {
+                match shape {
+                    ShapeContent::Media {
+                        kind,
+                        rel_id,
+                        file_name,
+                        duration_secs,
+                    } => {
+                        if let Some(rel) = slide_rels.get(rel_id.as_str()) {
+                            *file_name = target_file_name(&rel.target);
+                            let media_path =
+                                resolve_relative_to_file(&slide_info.path, &rel.target);
+                            let content_types = content_types.get_or_insert_with(|| {
+                                read_zip_text(&mut archive, "[Content_Types].xml")
+                                    .ok()
+                                    .flatten()
+                                    .map(|xml| parse_content_types(&xml))
+                                    .unwrap_or_default()
+                            });
+                            if let Some(declared) =
+                                media_kind(&rel.rel_type, content_types.content_type(&media_path))
+                            {
+                                *kind = declared;
+                            }
+                            *duration_secs = media_duration_secs(&mut archive, &media_path);
+                        }
+                    }
+                    ShapeContent::Embedded {
+                        rel_id,
+                        file_name,
+                        converted,
+                        ..
+                    } => {
+                        if let Some(rel) = slide_rels.get(rel_id.as_str()) {
+                            *file_name = target_file_name(&rel.target);
+                            if options.pptx.convert_embedded {
+                                let object_path =
+                                    resolve_relative_to_file(&slide_info.path, &rel.target);
+                                match read_zip_bytes(&mut archive, &object_path) {
+                                    Ok(Some(bytes)) => {
+                                        *converted = convert_embedded_package(
+                                            &bytes,
+                                            file_name,
+                                            options,
+                                            &mut images,
+                                            &mut total_image_bytes,
+                                            &mut warnings,
+                                        );
+                                    }
+                                    Ok(None) => {}
+                                    Err(e) => warnings.push(ConversionWarning {
+                                        code: WarningCode::SkippedElement,
+                                        message: format!(
+                                            "failed to read embedded object '{object_path}': {e}"
+                                        ),
+                                        location: Some(slide_info.path.clone()),
+                                    }),
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            // Review comments
+            let mut comments: Vec<SlideComment> = Vec::new();
+            if options.pptx.comments {
+                for target in resolve_comment_paths(&slide_rels) {
+                    let comments_path = resolve_relative_to_file(&slide_info.path, &target);
+                    match read_zip_text(&mut archive, &comments_path)? {
+                        Some(xml) => comments.extend(parse_comments(&xml, &comment_authors)),
+                        None => warnings.push(ConversionWarning {
+                            code: WarningCode::SkippedElement,
+                            message: format!("comments file not found: {comments_path}"),
+                            location: Some(slide_info.path.clone()),
+                        }),
+                    }
+                }
+            }
+
+            // Resolve image filenames and optionally extract image data
+            let need_image_bytes = options.extract_images || options.image_describer.is_some();
+            let mut image_filenames: HashMap<String, String> = HashMap::new();
+            for shape in &shapes {
+                if let ShapeContent::Image { rel_id, .. } = shape
+                    && let Some(rel) = slide_rels.get(rel_id)
+                {
+                    let image_path = resolve_relative_to_file(&slide_info.path, &rel.target);
+                    let filename = image_path.rsplit('/').next().unwrap_or(&image_path);
+                    image_filenames.insert(rel_id.clone(), filename.to_string());
+
+                    if need_image_bytes
+                        && total_image_bytes < options.max_total_image_bytes
+                        && let Ok(Some(img_data)) = read_zip_bytes(&mut archive, &image_path)
+                    {
+                        total_image_bytes += img_data.len();
+                        if total_image_bytes <= options.max_total_image_bytes {
+                            if options.extract_images {
+                                images.push((filename.to_string(), img_data.clone()));
+                            }
+                            let bytes_key = format!("{}::{}", slide_info.path, rel_id);
+                            all_image_bytes.insert(bytes_key, img_data);
+                        } else {
+                            warnings.push(ConversionWarning {
+                                code: WarningCode::ResourceLimitReached,
+                                message: format!(
+                                    "total image bytes exceeded limit ({})",
+                                    options.max_total_image_bytes
+                                ),
+                                location: Some(image_path),
+                            });
+                        }
+                    }
+                }
+            }
+
+            // Set document title from first slide's title
+            if document_title.is_none() {
+                document_title = shapes.iter().find_map(|s| {
+                    if let ShapeContent::Title(t) = s {
+                        Some(t.clone())
+                    } else {
+                        None
+                    }
+                });
+            }
+
+            let slide = SlideContent {
+                number: slide_info.number,
+                hidden: hidden && options.pptx.hidden_slides == HiddenSlides::Mark,
+                shapes,
+                notes,
+                comments,
+            };
+            let (mut slide_md, mut slide_plain, slide_image_infos) = render_slide(
+                &slide,
+                &image_filenames,
+                &slide_info.path,
+                &mut image_counter,
+            );
+
+            // Section heading above the first converted slide of each section
+            if options.pptx.section_headings
+                && let Some(ref section) = slide_info.section
+                && previous_section != Some(section.as_str())
+            {
+                slide_md = format!("# {section}\n\n{slide_md}");
+                slide_plain = format!("{section}\n\n{slide_plain}");
+            }
+            previous_section = slide_info.section.as_deref();
+
+            all_image_infos.extend(slide_image_infos);
+            slide_markdowns.push(slide_md);
+            slide_plains.push(slide_plain);
+        }
+
+        // Join slides with horizontal rule separator (markdown) or blank line (plain text)
+        let markdown = slide_markdowns.join("\n\n---\n\n");
+        let markdown = if markdown.is_empty() {
+            markdown
+        } else {
+            format!("{markdown}\n")
+        };
+
+        let plain_text = slide_plains.join("\n\n");
+        let plain_text = if plain_text.is_empty() {
+            plain_text
+        } else {
+            format!("{plain_text}\n")
+        };
+
+        let result = ConversionResult {
+            markdown,
+            plain_text,
+            title: document_title,
+            images,
+            warnings,
+            ..Default::default()
+        };
+
+        let pending = PendingImageResolution {
+            infos: all_image_infos,
+            bytes: all_image_bytes,
+        };
+
+        Ok((result, pending))
+    }
+}
+
+// ---- Converter trait impl ----
+
+impl Converter for PptxConverter {
+    fn supported_extensions(&self) -> &[&str] {
+        &["pptx"]
+    }
+
+    fn convert(
+        &self,
+        data: &[u8],
+        options: &ConversionOptions,
+    ) -> Result<ConversionResult, ConvertError> {
+        let (mut result, pending) = self.convert_inner(data, options)?;
+        resolve_image_placeholders(
+            &mut result.markdown,
+            &mut result.plain_text,
+            &pending.infos,
+            &pending.bytes,
+            options.image_describer.as_deref(),
+            &mut result.warnings,
+        );
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::PptxOptions;
+
+    // ---- Helper: build minimal PPTX ZIP in memory ----
+
+    struct TestSlide<'a> {
+        title: Option<&'a str>,
+        body_texts: Vec<&'a str>,
+        notes: Option<&'a str>,
+        table: Option<TestTable<'a>>,
+        images: Vec<&'a str>,                  // rel IDs for image references
+        image_alt_texts: Vec<Option<&'a str>>, // alt text per image (parallel to images)
+    }
+
+    struct TestTable<'a> {
+        headers: Vec<&'a str>,
+        rows: Vec<Vec<&'a str>>,
+    }
+
+    /// Build a minimal PPTX ZIP in memory.
+    fn build_test_pptx(slides: &[TestSlide]) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let buf = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(buf));
+        let opts = SimpleFileOptions::default();
+
+        // [Content_Types].xml
+        let mut ct = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+        ct.push_str(
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        );
+        ct.push_str(
+            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        );
+        ct.push_str(r#"<Default Extension="xml" ContentType="application/xml"/>"#);
+        ct.push_str("</Types>");
+        zip.start_file("[Content_Types].xml", opts).unwrap();
+        zip.write_all(ct.as_bytes()).unwrap();
+
+        // Build presentation.xml with slide references
+        let mut pres_xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst>"#,
+        );
+        let mut pres_rels_xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+
+        for (i, slide) in slides.iter().enumerate() {
+            let slide_num = i + 1;
+            let rid = format!("rId{slide_num}");
+            let slide_id = 256 + i;
+
+            pres_xml.push_str(&format!(r#"<p:sldId id="{slide_id}" r:id="{rid}"/>"#));
+            pres_rels_xml.push_str(&format!(
+                r#"<Relationship Id="{rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{slide_num}.xml"/>"#
+            ));
+
+            // Build slide XML
+            let slide_xml = build_slide_xml(slide);
+            zip.start_file(format!("ppt/slides/slide{slide_num}.xml"), opts)
+                .unwrap();
+            zip.write_all(slide_xml.as_bytes()).unwrap();
+
+            // Build slide rels if notes or images exist
+            if slide.notes.is_some() || !slide.images.is_empty() {
+                let mut slide_rels = String::from(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                );
+
+                if slide.notes.is_some() {
+                    slide_rels.push_str(&format!(
+                        r#"<Relationship Id="rIdNotes" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{slide_num}.xml"/>"#
+                    ));
+                }
+
+                for (img_idx, _) in slide.images.iter().enumerate() {
+                    let img_rid = format!("rIdImg{}", img_idx + 1);
+                    slide_rels.push_str(&format!(
+                        r#"<Relationship Id="{img_rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}.png"/>"#,
+                        img_idx + 1
+                    ));
+                }
+
+                slide_rels.push_str("</Relationships>");
+                zip.start_file(format!("ppt/slides/_rels/slide{slide_num}.xml.rels"), opts)
+                    .unwrap();
+                zip.write_all(slide_rels.as_bytes()).unwrap();
+            }
+
+            // Build notes slide if present
+            if let Some(notes_text) = slide.notes {
+                let notes_xml = build_notes_xml(notes_text);
+                zip.start_file(format!("ppt/notesSlides/notesSlide{slide_num}.xml"), opts)
+                    .unwrap();
+                zip.write_all(notes_xml.as_bytes()).unwrap();
+            }
+        }
+
+        pres_xml.push_str("</p:sldIdLst></p:presentation>");
+        pres_rels_xml.push_str("</Relationships>");
+
+        zip.start_file("ppt/presentation.xml", opts).unwrap();
+        zip.write_all(pres_xml.as_bytes()).unwrap();
+
+        zip.start_file("ppt/_rels/presentation.xml.rels", opts)
+            .unwrap();
+        zip.write_all(pres_rels_xml.as_bytes()).unwrap();
+
+        let cursor = zip.finish().unwrap();
+        cursor.into_inner()
+    }
+
+    /// Build the XML for a single slide.
+    fn build_slide_xml(slide: &TestSlide) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree>"#,
+        );
+
+        // Title shape
+        if let Some(title) = slide.title {
+            xml.push_str(&format!(
+                r#"<p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>{title}</a:t></a:r></a:p></p:txBody></p:sp>"#
+            ));
+        }
+
+        // Body text shapes
+        for text in &slide.body_texts {
+            xml.push_str(&format!(
+                r#"<p:sp><p:nvSpPr><p:cNvPr id="2" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p></p:txBody></p:sp>"#
+            ));
+        }
+
+        // Table
+        if let Some(table) = &slide.table {
+            xml.push_str(r#"<p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="3" name="Table"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><a:graphic><a:graphicData><a:tbl>"#);
+
+            // Header row
+            xml.push_str("<a:tr>");
+            for header in &table.headers {
+                xml.push_str(&format!(
+                    r#"<a:tc><a:txBody><a:p><a:r><a:t>{header}</a:t></a:r></a:p></a:txBody></a:tc>"#
+                ));
+            }
+            xml.push_str("</a:tr>");
+
+            // Data rows
+            for row in &table.rows {
+                xml.push_str("<a:tr>");
+                for cell in row {
+                    xml.push_str(&format!(
+                        r#"<a:tc><a:txBody><a:p><a:r><a:t>{cell}</a:t></a:r></a:p></a:txBody></a:tc>"#
+                    ));
+                }
+                xml.push_str("</a:tr>");
+            }
+
+            xml.push_str("</a:tbl></a:graphicData></a:graphic></p:graphicFrame>");
+        }
+
+        // Image shapes
+        for (idx, rel_id) in slide.images.iter().enumerate() {
+            let descr_attr = slide
+                .image_alt_texts
+                .get(idx)
+                .and_then(|a| *a)
+                .map(|alt| format!(r#" descr="{alt}""#))
+                .unwrap_or_default();
+            xml.push_str(&format!(
+                r#"<p:pic><p:nvPicPr><p:cNvPr id="{}"{descr_attr} name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="{rel_id}"/></p:blipFill></p:pic>"#,
+                10 + idx
+            ));
+        }
+
+        xml.push_str("</p:spTree></p:cSld></p:sld>");
+        xml
+    }
+
+    /// Build the XML for a notes slide.
+    fn build_notes_xml(text: &str) -> String {
+        // Split text by newlines to create separate paragraphs
+        let paragraphs: Vec<&str> = text.lines().collect();
+        let mut para_xml = String::new();
+        for p in &paragraphs {
+            para_xml.push_str(&format!(r#"<a:p><a:r><a:t>{p}</a:t></a:r></a:p>"#));
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:notes xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Slide Number"/><p:cNvSpPr/><p:nvPr><p:ph type="sldNum"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>1</a:t></a:r></a:p></p:txBody></p:sp><p:sp><p:nvSpPr><p:cNvPr id="2" name="Notes"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody>{para_xml}</p:txBody></p:sp></p:spTree></p:cSld></p:notes>"#
+        )
+    }
+
+    // ---- Tests ----
+
+    #[test]
+    fn test_pptx_supported_extensions() {
+        let converter = PptxConverter;
+        assert_eq!(converter.supported_extensions(), &["pptx"]);
+    }
+
+    #[test]
+    fn test_pptx_can_convert() {
+        let converter = PptxConverter;
+        assert!(converter.can_convert("pptx", &[]));
+        assert!(!converter.can_convert("docx", &[]));
+        assert!(!converter.can_convert("xlsx", &[]));
+        assert!(!converter.can_convert("pdf", &[]));
+    }
+
+    #[test]
+    fn test_pptx_invalid_data_returns_error() {
+        let converter = PptxConverter;
+        let result = converter.convert(b"not a valid pptx file", &ConversionOptions::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_pptx_empty_presentation() {
         let data = build_test_pptx(&[]);
         let converter = PptxConverter;
         let result = converter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
-        assert_eq!(result.markdown, "");
-        assert!(result.title.is_none());
+        assert_eq!(result.markdown, "");
+        assert!(result.title.is_none());
+    }
+
+    #[test]
+    fn test_pptx_single_slide_title_and_body() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("Hello World"),
+            body_texts: vec!["This is the body text."],
+            notes: None,
+            table: None,
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## Slide 1: Hello World"));
+        assert!(result.markdown.contains("This is the body text."));
+    }
+
+    #[test]
+    fn test_pptx_multiple_slides_with_separator() {
+        let data = build_test_pptx(&[
+            TestSlide {
+                title: Some("First"),
+                body_texts: vec!["Body one."],
+                notes: None,
+                table: None,
+                images: vec![],
+                image_alt_texts: vec![],
+            },
+            TestSlide {
+                title: Some("Second"),
+                body_texts: vec!["Body two."],
+                notes: None,
+                table: None,
+                images: vec![],
+                image_alt_texts: vec![],
+            },
+        ]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## Slide 1: First"));
+        assert!(result.markdown.contains("## Slide 2: Second"));
+        assert!(result.markdown.contains("\n\n---\n\n"));
+    }
+
+    #[test]
+    fn test_pptx_slide_without_title() {
+        let data = build_test_pptx(&[TestSlide {
+            title: None,
+            body_texts: vec!["Just body text."],
+            notes: None,
+            table: None,
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## Slide 1\n"));
+        // Should NOT have a colon when no title
+        assert!(!result.markdown.contains("## Slide 1:"));
+    }
+
+    #[test]
+    fn test_pptx_title_center_title() {
+        // Build a PPTX with ctrTitle placeholder type
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="ctrTitle"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Center Title</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Title(text) => assert_eq!(text, "Center Title"),
+            other => panic!("expected Title, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_document_title_from_first_slide() {
+        let data = build_test_pptx(&[
+            TestSlide {
+                title: Some("Presentation Title"),
+                body_texts: vec![],
+                notes: None,
+                table: None,
+                images: vec![],
+                image_alt_texts: vec![],
+            },
+            TestSlide {
+                title: Some("Second Slide"),
+                body_texts: vec![],
+                notes: None,
+                table: None,
+                images: vec![],
+                image_alt_texts: vec![],
+            },
+        ]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert_eq!(result.title, Some("Presentation Title".to_string()));
+    }
+
+    #[test]
+    fn test_pptx_body_text_multiple_paragraphs() {
+        // Build slide XML with multiple paragraphs in body
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>First paragraph</a:t></a:r></a:p><a:p><a:r><a:t>Second paragraph</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Body(text) => {
+                assert!(text.contains("First paragraph"));
+                assert!(text.contains("Second paragraph"));
+                assert!(text.contains('\n'));
+            }
+            other => panic!("expected Body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_body_text_multiple_runs_joined() {
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Hello </a:t></a:r><a:r><a:t>World</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        match &shapes[0] {
+            ShapeContent::Body(text) => assert_eq!(text, "Hello World"),
+            other => panic!("expected Body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_subtitle_treated_as_body() {
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Main Title</a:t></a:r></a:p></p:txBody></p:sp><p:sp><p:nvSpPr><p:cNvPr id="2" name="Subtitle"/><p:cNvSpPr/><p:nvPr><p:ph type="subTitle"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>The subtitle</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        assert_eq!(shapes.len(), 2);
+        match &shapes[0] {
+            ShapeContent::Title(text) => assert_eq!(text, "Main Title"),
+            other => panic!("expected Title, got {:?}", other),
+        }
+        match &shapes[1] {
+            ShapeContent::Body(text) => assert_eq!(text, "The subtitle"),
+            other => panic!("expected Body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_table_basic() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("Data"),
+            body_texts: vec![],
+            notes: None,
+            table: Some(TestTable {
+                headers: vec!["Name", "Value"],
+                rows: vec![vec!["Alpha", "100"], vec!["Beta", "200"]],
+            }),
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("| Name | Value |"));
+        assert!(result.markdown.contains("|---|---|"));
+        assert!(result.markdown.contains("| Alpha | 100 |"));
+        assert!(result.markdown.contains("| Beta | 200 |"));
+    }
+
+    #[test]
+    fn test_pptx_table_empty_cells() {
+        let data = build_test_pptx(&[TestSlide {
+            title: None,
+            body_texts: vec![],
+            notes: None,
+            table: Some(TestTable {
+                headers: vec!["A", "B", "C"],
+                rows: vec![vec!["1", "", "3"]],
+            }),
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("| A | B | C |"));
+        assert!(result.markdown.contains("| 1 |  | 3 |"));
+    }
+
+    #[test]
+    fn test_pptx_notes_basic() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("Slide"),
+            body_texts: vec!["Content."],
+            notes: Some("This is a speaker note."),
+            table: None,
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("> Note: This is a speaker note."));
+    }
+
+    #[test]
+    fn test_pptx_notes_multiline() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("Slide"),
+            body_texts: vec![],
+            notes: Some("First line\nSecond line\nThird line"),
+            table: None,
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("> Note: First line"));
+        assert!(result.markdown.contains("> Second line"));
+        assert!(result.markdown.contains("> Third line"));
+    }
+
+    #[test]
+    fn test_pptx_notes_missing() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("Slide"),
+            body_texts: vec!["Text."],
+            notes: None,
+            table: None,
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(!result.markdown.contains("> Note:"));
+    }
+
+    #[test]
+    fn test_pptx_unicode_cjk() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("다국어"),
+            body_texts: vec!["한국어 테스트", "中文测试", "日本語テスト"],
+            notes: None,
+            table: None,
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("한국어 테스트"));
+        assert!(result.markdown.contains("中文测试"));
+        assert!(result.markdown.contains("日本語テスト"));
+        assert!(result.markdown.contains("다국어"));
+    }
+
+    #[test]
+    fn test_pptx_emoji() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("Emoji Test"),
+            body_texts: vec!["Rocket: 🚀 Stars: ✨ Earth: 🌍"],
+            notes: None,
+            table: None,
+            images: vec![],
+            image_alt_texts: vec![],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("🚀"));
+        assert!(result.markdown.contains("✨"));
+        assert!(result.markdown.contains("🌍"));
+    }
+
+    #[test]
+    fn test_pptx_missing_presentation_xml() {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let buf = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(buf));
+        let opts = SimpleFileOptions::default();
+
+        // Just a content types file, no presentation.xml
+        zip.start_file("[Content_Types].xml", opts).unwrap();
+        zip.write_all(b"<?xml version=\"1.0\"?><Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"></Types>").unwrap();
+
+        let cursor = zip.finish().unwrap();
+        let data = cursor.into_inner();
+
+        let converter = PptxConverter;
+        let result = converter.convert(&data, &ConversionOptions::default());
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(
+            format!("{err}").contains("missing ppt/presentation.xml"),
+            "error was: {err}"
+        );
+    }
+
+    #[test]
+    fn test_pptx_missing_slide_file_graceful() {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let buf = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(buf));
+        let opts = SimpleFileOptions::default();
+
+        zip.start_file("[Content_Types].xml", opts).unwrap();
+        zip.write_all(b"<?xml version=\"1.0\"?><Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"></Types>").unwrap();
+
+        // presentation.xml references a slide that doesn't exist
+        let pres_xml = r#"<?xml version="1.0"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst><p:sldId id="256" r:id="rId1"/></p:sldIdLst></p:presentation>"#;
+        zip.start_file("ppt/presentation.xml", opts).unwrap();
+        zip.write_all(pres_xml.as_bytes()).unwrap();
+
+        let pres_rels = r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/></Relationships>"#;
+        zip.start_file("ppt/_rels/presentation.xml.rels", opts)
+            .unwrap();
+        zip.write_all(pres_rels.as_bytes()).unwrap();
+
+        let cursor = zip.finish().unwrap();
+        let data = cursor.into_inner();
+
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(!result.warnings.is_empty());
+        assert_eq!(result.warnings[0].code, WarningCode::SkippedElement);
+        assert!(result.warnings[0].message.contains("slide file not found"));
+    }
+
+    #[test]
+    fn test_pptx_derive_rels_path() {
+        assert_eq!(
+            derive_rels_path("ppt/slides/slide1.xml"),
+            "ppt/slides/_rels/slide1.xml.rels"
+        );
+        assert_eq!(
+            derive_rels_path("ppt/presentation.xml"),
+            "ppt/_rels/presentation.xml.rels"
+        );
+        assert_eq!(derive_rels_path("file.xml"), "_rels/file.xml.rels");
+    }
+
+    #[test]
+    fn test_pptx_resolve_relative_to_file() {
+        assert_eq!(
+            resolve_relative_to_file("ppt/slides/slide1.xml", "../media/image1.png"),
+            "ppt/media/image1.png"
+        );
+        assert_eq!(
+            resolve_relative_to_file("ppt/slides/slide1.xml", "/ppt/media/image1.png"),
+            "ppt/media/image1.png"
+        );
+        assert_eq!(
+            resolve_relative_to_file("ppt/slides/slide1.xml", "../notesSlides/notesSlide1.xml"),
+            "ppt/notesSlides/notesSlide1.xml"
+        );
+        assert_eq!(
+            resolve_relative_to_file("ppt/slides/slide1.xml", "chart1.xml"),
+            "ppt/slides/chart1.xml"
+        );
+    }
+
+    #[test]
+    fn test_pptx_image_reference_detected() {
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:pic><p:nvPicPr><p:cNvPr id="1" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId2"/></p:blipFill></p:pic></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Image { rel_id, .. } => assert_eq!(rel_id, "rId2"),
+            other => panic!("expected Image, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_image_alt_text_extracted() {
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:pic><p:nvPicPr><p:cNvPr id="10" descr="A beautiful chart" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId2"/></p:blipFill></p:pic></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Image { rel_id, alt_text } => {
+                assert_eq!(rel_id, "rId2");
+                assert_eq!(alt_text.as_deref(), Some("A beautiful chart"));
+            }
+            other => panic!("expected Image, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_image_alt_text_missing() {
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:pic><p:nvPicPr><p:cNvPr id="10" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId3"/></p:blipFill></p:pic></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Image { rel_id, alt_text } => {
+                assert_eq!(rel_id, "rId3");
+                assert!(alt_text.is_none());
+            }
+            other => panic!("expected Image, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_image_alt_text_in_markdown() {
+        let data = build_test_pptx(&[TestSlide {
+            title: Some("Images"),
+            body_texts: vec![],
+            notes: None,
+            table: None,
+            images: vec!["rIdImg1"],
+            image_alt_texts: vec![Some("A diagram of the architecture")],
+        }]);
+        let converter = PptxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("![A diagram of the architecture](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+    }
+
+    #[test]
+    fn test_pptx_line_break() {
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Line one</a:t><a:br/><a:t>Line two</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, _) = parse_slide(slide_xml);
+        match &shapes[0] {
+            ShapeContent::Body(text) => assert!(text.contains("Line one\nLine two")),
+            other => panic!("expected Body, got {:?}", other),
+        }
+    }
+
+    // ---- Image describer tests ----
+
+    use crate::converter::ImageDescriber;
+    use std::sync::Arc;
+
+    struct MockDescriber {
+        description: String,
+    }
+
+    impl ImageDescriber for MockDescriber {
+        fn describe(
+            &self,
+            _image_bytes: &[u8],
+            _mime_type: &str,
+            _prompt: &str,
+        ) -> Result<String, ConvertError> {
+            Ok(self.description.clone())
+        }
+    }
+
+    struct FailingDescriber;
+
+    impl ImageDescriber for FailingDescriber {
+        fn describe(
+            &self,
+            _image_bytes: &[u8],
+            _mime_type: &str,
+            _prompt: &str,
+        ) -> Result<String, ConvertError> {
+            Err(ConvertError::ImageDescriptionError {
+                reason: "API error".to_string(),
+            })
+        }
+    }
+
+    /// Build a PPTX with actual image data embedded in the ZIP for describer tests.
+    fn build_test_pptx_with_image_data(
+        slides: &[TestSlide],
+        image_data: &[(&str, &[u8])], // (path in zip, data)
+    ) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let buf = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(buf));
+        let opts = SimpleFileOptions::default();
+
+        let mut ct = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+        ct.push_str(
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        );
+        ct.push_str(
+            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        );
+        ct.push_str(r#"<Default Extension="xml" ContentType="application/xml"/>"#);
+        ct.push_str(r#"<Default Extension="png" ContentType="image/png"/>"#);
+        ct.push_str("</Types>");
+        zip.start_file("[Content_Types].xml", opts).unwrap();
+        zip.write_all(ct.as_bytes()).unwrap();
+
+        let mut pres_xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst>"#,
+        );
+        let mut pres_rels_xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+
+        for (i, slide) in slides.iter().enumerate() {
+            let slide_num = i + 1;
+            let rid = format!("rId{slide_num}");
+            let slide_id = 256 + i;
+
+            pres_xml.push_str(&format!(r#"<p:sldId id="{slide_id}" r:id="{rid}"/>"#));
+            pres_rels_xml.push_str(&format!(
+                r#"<Relationship Id="{rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{slide_num}.xml"/>"#
+            ));
+
+            let slide_xml = build_slide_xml(slide);
+            zip.start_file(format!("ppt/slides/slide{slide_num}.xml"), opts)
+                .unwrap();
+            zip.write_all(slide_xml.as_bytes()).unwrap();
+
+            if slide.notes.is_some() || !slide.images.is_empty() {
+                let mut slide_rels = String::from(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                );
+
+                if slide.notes.is_some() {
+                    slide_rels.push_str(&format!(
+                        r#"<Relationship Id="rIdNotes" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{slide_num}.xml"/>"#
+                    ));
+                }
+
+                for (img_idx, _) in slide.images.iter().enumerate() {
+                    let img_rid = format!("rIdImg{}", img_idx + 1);
+                    slide_rels.push_str(&format!(
+                        r#"<Relationship Id="{img_rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}.png"/>"#,
+                        img_idx + 1
+                    ));
+                }
+
+                slide_rels.push_str("</Relationships>");
+                zip.start_file(format!("ppt/slides/_rels/slide{slide_num}.xml.rels"), opts)
+                    .unwrap();
+                zip.write_all(slide_rels.as_bytes()).unwrap();
+            }
+
+            if let Some(notes_text) = slide.notes {
+                let notes_xml = build_notes_xml(notes_text);
+                zip.start_file(format!("ppt/notesSlides/notesSlide{slide_num}.xml"), opts)
+                    .unwrap();
+                zip.write_all(notes_xml.as_bytes()).unwrap();
+            }
+        }
+
+        pres_xml.push_str("</p:sldIdLst></p:presentation>");
+        pres_rels_xml.push_str("</Relationships>");
+
+        zip.start_file("ppt/presentation.xml", opts).unwrap();
+        zip.write_all(pres_xml.as_bytes()).unwrap();
+
+        zip.start_file("ppt/_rels/presentation.xml.rels", opts)
+            .unwrap();
+        zip.write_all(pres_rels_xml.as_bytes()).unwrap();
+
+        // Add image files
+        for (path, data) in image_data {
+            zip.start_file(path.to_string(), opts).unwrap();
+            zip.write_all(data).unwrap();
+        }
+
+        let cursor = zip.finish().unwrap();
+        cursor.into_inner()
     }
 
     #[test]
-    fn test_pptx_single_slide_title_and_body() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("Hello World"),
-            body_texts: vec!["This is the body text."],
-            notes: None,
-            table: None,
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
+    fn test_pptx_multiple_images_on_one_slide() {
+        let data = build_test_pptx_with_image_data(
+            &[TestSlide {
+                title: Some("Gallery"),
+                body_texts: vec![],
+                notes: None,
+                table: None,
+                images: vec!["rIdImg1", "rIdImg2"],
+                image_alt_texts: vec![Some("First image"), Some("Second image")],
+            }],
+            &[
+                ("ppt/media/image1.png", b"fake-png-1"),
+                ("ppt/media/image2.png", b"fake-png-2"),
+            ],
+        );
+
         let converter = PptxConverter;
         let result = converter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
-        assert!(result.markdown.contains("## Slide 1: Hello World"));
-        assert!(result.markdown.contains("This is the body text."));
+        assert!(
+            result.markdown.contains("![First image](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(
+            result.markdown.contains("![Second image](image2.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+        // Verify ordering: image1 appears before image2
+        let pos1 = result.markdown.find("image1.png").unwrap();
+        let pos2 = result.markdown.find("image2.png").unwrap();
+        assert!(pos1 < pos2, "image1 should appear before image2");
     }
 
     #[test]
-    fn test_pptx_multiple_slides_with_separator() {
-        let data = build_test_pptx(&[
-            TestSlide {
-                title: Some("First"),
-                body_texts: vec!["Body one."],
+    fn test_pptx_image_describer_replaces_alt_text() {
+        let data = build_test_pptx_with_image_data(
+            &[TestSlide {
+                title: Some("Slide with Image"),
+                body_texts: vec![],
                 notes: None,
                 table: None,
-                images: vec![],
-                image_alt_texts: vec![],
-            },
-            TestSlide {
-                title: Some("Second"),
-                body_texts: vec!["Body two."],
+                images: vec!["rIdImg1"],
+                image_alt_texts: vec![None],
+            }],
+            &[("ppt/media/image1.png", b"fake-png-data")],
+        );
+
+        let converter = PptxConverter;
+        let options = ConversionOptions {
+            image_describer: Some(Arc::new(MockDescriber {
+                description: "A diagram showing data flow".to_string(),
+            })),
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("![A diagram showing data flow](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.images.is_empty());
+    }
+
+    #[test]
+    fn test_pptx_image_describer_error_keeps_original_alt() {
+        let data = build_test_pptx_with_image_data(
+            &[TestSlide {
+                title: Some("Slide"),
+                body_texts: vec![],
                 notes: None,
                 table: None,
-                images: vec![],
-                image_alt_texts: vec![],
-            },
-        ]);
+                images: vec!["rIdImg1"],
+                image_alt_texts: vec![Some("Original description")],
+            }],
+            &[("ppt/media/image1.png", b"fake-png-data")],
+        );
+
         let converter = PptxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("## Slide 1: First"));
-        assert!(result.markdown.contains("## Slide 2: Second"));
-        assert!(result.markdown.contains("\n\n---\n\n"));
+        let options = ConversionOptions {
+            image_describer: Some(Arc::new(FailingDescriber)),
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("![Original description](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code == WarningCode::SkippedElement
+                    && w.message.contains("image description failed")),
+        );
     }
 
+    // ---- Group shape tests ----
+
     #[test]
-    fn test_pptx_slide_without_title() {
-        let data = build_test_pptx(&[TestSlide {
-            title: None,
-            body_texts: vec!["Just body text."],
-            notes: None,
-            table: None,
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
+    fn test_pptx_group_shape_text_extracted() {
+        // A single <p:sp> inside a <p:grpSp> should have its text extracted
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group 1"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:sp><p:nvSpPr><p:cNvPr id="11" name="TextBox"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>Group text</a:t></a:r></a:p></p:txBody></p:sp></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, warnings) = parse_slide(slide_xml);
+        assert!(warnings.is_empty());
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Body(text) => assert_eq!(text, "Group text"),
+            other => panic!("expected Body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_group_shape_multiple_children() {
+        // Two <p:sp> shapes inside one <p:grpSp>
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:sp><p:nvSpPr><p:cNvPr id="11" name="Shape1"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>First shape</a:t></a:r></a:p></p:txBody></p:sp><p:sp><p:nvSpPr><p:cNvPr id="12" name="Shape2"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>Second shape</a:t></a:r></a:p></p:txBody></p:sp></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, warnings) = parse_slide(slide_xml);
+        assert!(warnings.is_empty());
+        assert_eq!(shapes.len(), 2);
+        match &shapes[0] {
+            ShapeContent::Body(text) => assert_eq!(text, "First shape"),
+            other => panic!("expected Body, got {:?}", other),
+        }
+        match &shapes[1] {
+            ShapeContent::Body(text) => assert_eq!(text, "Second shape"),
+            other => panic!("expected Body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_group_shape_nested() {
+        // <p:grpSp> inside <p:grpSp> with a <p:sp> child
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Outer"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:grpSp><p:nvGrpSpPr><p:cNvPr id="11" name="Inner"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:sp><p:nvSpPr><p:cNvPr id="12" name="Deep"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>Nested group text</a:t></a:r></a:p></p:txBody></p:sp></p:grpSp></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, warnings) = parse_slide(slide_xml);
+        assert!(warnings.is_empty());
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Body(text) => assert_eq!(text, "Nested group text"),
+            other => panic!("expected Body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_group_shape_with_table() {
+        // <p:graphicFrame> (table) inside a <p:grpSp>
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="11" name="Table"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><a:graphic><a:graphicData><a:tbl><a:tr><a:tc><a:txBody><a:p><a:r><a:t>H1</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>H2</a:t></a:r></a:p></a:txBody></a:tc></a:tr><a:tr><a:tc><a:txBody><a:p><a:r><a:t>A</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>B</a:t></a:r></a:p></a:txBody></a:tc></a:tr></a:tbl></a:graphicData></a:graphic></p:graphicFrame></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, warnings) = parse_slide(slide_xml);
+        assert!(warnings.is_empty());
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Table { headers, rows } => {
+                assert_eq!(headers, &["H1", "H2"]);
+                assert_eq!(rows.len(), 1);
+                assert_eq!(rows[0], &["A", "B"]);
+            }
+            other => panic!("expected Table, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_group_shape_with_image() {
+        // <p:pic> inside a <p:grpSp>
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:pic><p:nvPicPr><p:cNvPr id="11" descr="Alt text" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rIdImg1"/></p:blipFill></p:pic></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, warnings) = parse_slide(slide_xml);
+        assert!(warnings.is_empty());
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0] {
+            ShapeContent::Image { rel_id, alt_text } => {
+                assert_eq!(rel_id, "rIdImg1");
+                assert_eq!(alt_text.as_deref(), Some("Alt text"));
+            }
+            other => panic!("expected Image, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pptx_group_shape_empty() {
+        // Empty <p:grpSp> produces no shapes
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Empty Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+
+        let (shapes, _, warnings) = parse_slide(slide_xml);
+        assert!(warnings.is_empty());
+        assert!(shapes.is_empty());
+    }
+
+    // ---- SmartArt diagram tests ----
+
+    /// Build a PPTX from raw slide XML (with optional slide rels), extra XML
+    /// inserted into presentation.xml after `<p:sldIdLst>` (e.g. an `<p:extLst>`),
+    /// and extra package parts. Slide N gets `id="255+N"`.
+    fn build_raw_pptx(
+        slides: &[(&str, Option<&str>)],
+        pres_extra: &str,
+        parts: &[(&str, &[u8])],
+    ) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let opts = SimpleFileOptions::default();
+
+        let mut pres_xml = String::from(
+            r#"<?xml version="1.0"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p14="http://schemas.microsoft.com/office/powerpoint/2010/main"><p:sldIdLst>"#,
+        );
+        let mut pres_rels = String::from(
+            r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for (i, (slide_xml, slide_rels)) in slides.iter().enumerate() {
+            let n = i + 1;
+            pres_xml.push_str(&format!(r#"<p:sldId id="{}" r:id="rId{n}"/>"#, 255 + n));
+            pres_rels.push_str(&format!(
+                r#"<Relationship Id="rId{n}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{n}.xml"/>"#
+            ));
+            zip.start_file(format!("ppt/slides/slide{n}.xml"), opts)
+                .unwrap();
+            zip.write_all(slide_xml.as_bytes()).unwrap();
+            if let Some(rels) = slide_rels {
+                zip.start_file(format!("ppt/slides/_rels/slide{n}.xml.rels"), opts)
+                    .unwrap();
+                zip.write_all(rels.as_bytes()).unwrap();
+            }
+        }
+        pres_xml.push_str("</p:sldIdLst>");
+        pres_xml.push_str(pres_extra);
+        pres_xml.push_str("</p:presentation>");
+        pres_rels.push_str("</Relationships>");
+
+        zip.start_file("ppt/presentation.xml", opts).unwrap();
+        zip.write_all(pres_xml.as_bytes()).unwrap();
+        zip.start_file("ppt/_rels/presentation.xml.rels", opts)
             .unwrap();
-        assert!(result.markdown.contains("## Slide 1\n"));
-        // Should NOT have a colon when no title
-        assert!(!result.markdown.contains("## Slide 1:"));
-    }
+        zip.write_all(pres_rels.as_bytes()).unwrap();
+        for (path, content) in parts {
+            zip.start_file(path.to_string(), opts).unwrap();
+            zip.write_all(content).unwrap();
+        }
 
-    #[test]
-    fn test_pptx_title_center_title() {
-        // Build a PPTX with ctrTitle placeholder type
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="ctrTitle"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Center Title</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+        zip.finish().unwrap().into_inner()
+    }
 
-        let (shapes, _) = parse_slide(slide_xml);
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Title(text) => assert_eq!(text, "Center Title"),
-            other => panic!("expected Title, got {:?}", other),
+    /// Build minimal slide XML with an optional title and body paragraphs.
+    /// `root_attrs` is inserted into the `<p:sld>` start tag (e.g. `show="0"`).
+    fn simple_slide_xml(root_attrs: &str, title: Option<&str>, body: &[&str]) -> String {
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" {root_attrs}><p:cSld><p:spTree>"#
+        );
+        if let Some(title) = title {
+            xml.push_str(&format!(
+                r#"<p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>{title}</a:t></a:r></a:p></p:txBody></p:sp>"#
+            ));
+        }
+        for text in body {
+            xml.push_str(&format!(
+                r#"<p:sp><p:nvSpPr><p:cNvPr id="2" name="Content"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p></p:txBody></p:sp>"#
+            ));
         }
+        xml.push_str("</p:spTree></p:cSld></p:sld>");
+        xml
     }
 
-    #[test]
-    fn test_pptx_document_title_from_first_slide() {
-        let data = build_test_pptx(&[
-            TestSlide {
-                title: Some("Presentation Title"),
-                body_texts: vec![],
-                notes: None,
-                table: None,
-                images: vec![],
-                image_alt_texts: vec![],
-            },
-            TestSlide {
-                title: Some("Second Slide"),
-                body_texts: vec![],
-                notes: None,
-                table: None,
-                images: vec![],
-                image_alt_texts: vec![],
-            },
-        ]);
-        let converter = PptxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert_eq!(result.title, Some("Presentation Title".to_string()));
-    }
+    const DIAGRAM_SLIDE: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="4" name="Diagram 3"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/diagram"><dgm:relIds xmlns:dgm="http://schemas.openxmlformats.org/drawingml/2006/diagram" r:dm="rId2" r:lo="rId3" r:qs="rId4" r:cs="rId5"/></a:graphicData></a:graphic></p:graphicFrame></p:spTree></p:cSld></p:sld>"#;
 
-    #[test]
-    fn test_pptx_body_text_multiple_paragraphs() {
-        // Build slide XML with multiple paragraphs in body
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>First paragraph</a:t></a:r></a:p><a:p><a:r><a:t>Second paragraph</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+    const DIAGRAM_RELS: &str = r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/diagramData" Target="../diagrams/data1.xml"/></Relationships>"#;
 
-        let (shapes, _) = parse_slide(slide_xml);
+    /// Org chart: CEO with two reports (listed out of order), one of which has a report.
+    const ORG_CHART_DATA: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><dgm:dataModel xmlns:dgm="http://schemas.openxmlformats.org/drawingml/2006/diagram" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><dgm:ptLst><dgm:pt modelId="0" type="doc"><dgm:prSet/><dgm:spPr/><dgm:t><a:bodyPr/><a:p><a:endParaRPr/></a:p></dgm:t></dgm:pt><dgm:pt modelId="1"><dgm:prSet/><dgm:spPr/><dgm:t><a:bodyPr/><a:p><a:r><a:t>CEO</a:t></a:r></a:p></dgm:t></dgm:pt><dgm:pt modelId="2"><dgm:t><a:bodyPr/><a:p><a:r><a:t>CTO</a:t></a:r></a:p></dgm:t></dgm:pt><dgm:pt modelId="3"><dgm:t><a:bodyPr/><a:p><a:r><a:t>CFO</a:t></a:r></a:p></dgm:t></dgm:pt><dgm:pt modelId="4"><dgm:t><a:bodyPr/><a:p><a:r><a:t>Platform</a:t></a:r></a:p><a:p><a:r><a:t>Team</a:t></a:r></a:p></dgm:t></dgm:pt><dgm:pt modelId="9" type="sibTrans"><dgm:t><a:bodyPr/><a:p><a:r><a:t>ignored</a:t></a:r></a:p></dgm:t></dgm:pt><dgm:pt modelId="8" type="pres"><dgm:prSet presName="hierRoot"/></dgm:pt></dgm:ptLst><dgm:cxnLst><dgm:cxn modelId="10" srcId="0" destId="1" srcOrd="0" destOrd="0"/><dgm:cxn modelId="11" srcId="1" destId="3" srcOrd="1" destOrd="0"/><dgm:cxn modelId="12" srcId="1" destId="2" srcOrd="0" destOrd="0"/><dgm:cxn modelId="13" srcId="2" destId="4" srcOrd="0" destOrd="0"/><dgm:cxn modelId="14" type="presOf" srcId="1" destId="8" srcOrd="0" destOrd="0"/></dgm:cxnLst></dgm:dataModel>"#;
+
+    #[test]
+    fn test_pptx_parse_slide_detects_diagram_frame() {
+        let (shapes, _, warnings) = parse_slide(DIAGRAM_SLIDE);
+        assert!(warnings.is_empty());
         assert_eq!(shapes.len(), 1);
         match &shapes[0] {
-            ShapeContent::Body(text) => {
-                assert!(text.contains("First paragraph"));
-                assert!(text.contains("Second paragraph"));
-                assert!(text.contains('\n'));
+            ShapeContent::Diagram { rel_id, items } => {
+                assert_eq!(rel_id, "rId2");
+                assert!(items.is_empty());
             }
-            other => panic!("expected Body, got {:?}", other),
+            other => panic!("expected Diagram, got {:?}", other),
         }
     }
 
     #[test]
-    fn test_pptx_body_text_multiple_runs_joined() {
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Hello </a:t></a:r><a:r><a:t>World</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
-
-        let (shapes, _) = parse_slide(slide_xml);
-        match &shapes[0] {
-            ShapeContent::Body(text) => assert_eq!(text, "Hello World"),
-            other => panic!("expected Body, got {:?}", other),
-        }
+    fn test_pptx_parse_diagram_data_hierarchy() {
+        let (items, drawing_rel_id) = parse_diagram_data(ORG_CHART_DATA);
+        assert_eq!(
+            items,
+            vec![
+                (0, "CEO".to_string()),
+                (1, "CTO".to_string()),
+                (2, "Platform Team".to_string()),
+                (1, "CFO".to_string()),
+            ]
+        );
+        assert!(drawing_rel_id.is_none());
     }
 
     #[test]
-    fn test_pptx_subtitle_treated_as_body() {
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Main Title</a:t></a:r></a:p></p:txBody></p:sp><p:sp><p:nvSpPr><p:cNvPr id="2" name="Subtitle"/><p:cNvSpPr/><p:nvPr><p:ph type="subTitle"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>The subtitle</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+    fn test_pptx_parse_diagram_data_without_doc_root() {
+        let xml = r#"<dgm:dataModel xmlns:dgm="d" xmlns:a="a"><dgm:ptLst><dgm:pt modelId="1"><dgm:t><a:p><a:r><a:t>Plan</a:t></a:r></a:p></dgm:t></dgm:pt><dgm:pt modelId="2"><dgm:t><a:p><a:r><a:t>Build</a:t></a:r></a:p></dgm:t></dgm:pt></dgm:ptLst><dgm:cxnLst/></dgm:dataModel>"#;
+        let (items, _) = parse_diagram_data(xml);
+        assert_eq!(
+            items,
+            vec![(0, "Plan".to_string()), (0, "Build".to_string())]
+        );
+    }
 
-        let (shapes, _) = parse_slide(slide_xml);
-        assert_eq!(shapes.len(), 2);
-        match &shapes[0] {
-            ShapeContent::Title(text) => assert_eq!(text, "Main Title"),
-            other => panic!("expected Title, got {:?}", other),
+    #[test]
+    fn test_pptx_parse_diagram_data_deep_chain() {
+        // A linear chain far deeper than the call stack could recurse
+        const DEPTH: usize = 100_000;
+        let mut xml = String::from(r#"<dgm:dataModel xmlns:dgm="d" xmlns:a="a"><dgm:ptLst>"#);
+        for id in 0..DEPTH {
+            xml.push_str(&format!(
+                r#"<dgm:pt modelId="{id}"><dgm:t><a:p><a:r><a:t>n{id}</a:t></a:r></a:p></dgm:t></dgm:pt>"#
+            ));
         }
-        match &shapes[1] {
-            ShapeContent::Body(text) => assert_eq!(text, "The subtitle"),
-            other => panic!("expected Body, got {:?}", other),
+        xml.push_str("</dgm:ptLst><dgm:cxnLst>");
+        for id in 1..DEPTH {
+            let parent = id - 1;
+            xml.push_str(&format!(r#"<dgm:cxn srcId="{parent}" destId="{id}"/>"#));
         }
+        xml.push_str("</dgm:cxnLst></dgm:dataModel>");
+
+        let (items, _) = parse_diagram_data(&xml);
+        assert_eq!(items.len(), DEPTH);
+        assert_eq!(items[2], (2, "n2".to_string()));
+        // Levels saturate at the u8 limit
+        assert_eq!(items[DEPTH - 1].0, u8::MAX);
     }
 
     #[test]
-    fn test_pptx_table_basic() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("Data"),
-            body_texts: vec![],
-            notes: None,
-            table: Some(TestTable {
-                headers: vec!["Name", "Value"],
-                rows: vec![vec!["Alpha", "100"], vec!["Beta", "200"]],
-            }),
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("| Name | Value |"));
-        assert!(result.markdown.contains("|---|---|"));
-        assert!(result.markdown.contains("| Alpha | 100 |"));
-        assert!(result.markdown.contains("| Beta | 200 |"));
+    fn test_pptx_parse_diagram_data_drawing_rel_id() {
+        let xml = r#"<dgm:dataModel xmlns:dgm="d" xmlns:a="a" xmlns:dsp="s"><dgm:ptLst/><dgm:extLst><a:ext uri="http://schemas.microsoft.com/office/drawing/2008/diagram"><dsp:dataModelExt relId="rId6" minVer="http://schemas.openxmlformats.org/drawingml/2006/diagram"/></a:ext></dgm:extLst></dgm:dataModel>"#;
+        let (items, drawing_rel_id) = parse_diagram_data(xml);
+        assert!(items.is_empty());
+        assert_eq!(drawing_rel_id.as_deref(), Some("rId6"));
     }
 
     #[test]
-    fn test_pptx_table_empty_cells() {
-        let data = build_test_pptx(&[TestSlide {
-            title: None,
-            body_texts: vec![],
-            notes: None,
-            table: Some(TestTable {
-                headers: vec!["A", "B", "C"],
-                rows: vec![vec!["1", "", "3"]],
-            }),
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("| A | B | C |"));
-        assert!(result.markdown.contains("| 1 |  | 3 |"));
+    fn test_pptx_parse_diagram_drawing_shapes() {
+        let xml = r#"<dsp:drawing xmlns:dsp="s" xmlns:a="a"><dsp:spTree><dsp:sp modelId="1"><dsp:txBody><a:p><a:r><a:t>Step 1</a:t></a:r></a:p></dsp:txBody></dsp:sp><dsp:sp modelId="2"><dsp:spPr/></dsp:sp><dsp:sp modelId="3"><dsp:txBody><a:p><a:r><a:t>Step 2</a:t></a:r></a:p></dsp:txBody></dsp:sp></dsp:spTree></dsp:drawing>"#;
+        let items = parse_diagram_drawing(xml);
+        assert_eq!(
+            items,
+            vec![(0, "Step 1".to_string()), (0, "Step 2".to_string())]
+        );
     }
 
     #[test]
-    fn test_pptx_notes_basic() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("Slide"),
-            body_texts: vec!["Content."],
-            notes: Some("This is a speaker note."),
-            table: None,
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
+    fn test_pptx_smartart_rendered_as_nested_list() {
+        let data = build_raw_pptx(
+            &[(DIAGRAM_SLIDE, Some(DIAGRAM_RELS))],
+            "",
+            &[("ppt/diagrams/data1.xml", ORG_CHART_DATA.as_bytes())],
+        );
+        let result = PptxConverter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
-        assert!(result.markdown.contains("> Note: This is a speaker note."));
+        assert!(
+            result
+                .markdown
+                .contains("- CEO\n  - CTO\n    - Platform Team\n  - CFO"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.plain_text.contains("CEO\n  CTO\n    Platform Team"));
+        assert!(!result.markdown.contains("ignored"));
+        assert!(result.warnings.is_empty());
     }
 
     #[test]
-    fn test_pptx_notes_multiline() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("Slide"),
-            body_texts: vec![],
-            notes: Some("First line\nSecond line\nThird line"),
-            table: None,
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
+    fn test_pptx_smartart_falls_back_to_drawing() {
+        let drawing = r#"<dsp:drawing xmlns:dsp="s" xmlns:a="a"><dsp:spTree><dsp:sp><dsp:txBody><a:p><a:r><a:t>Fallback node</a:t></a:r></a:p></dsp:txBody></dsp:sp></dsp:spTree></dsp:drawing>"#;
+        let data = build_raw_pptx(
+            &[(DIAGRAM_SLIDE, Some(DIAGRAM_RELS))],
+            "",
+            &[("ppt/diagrams/drawing1.xml", drawing.as_bytes())],
+        );
+        let result = PptxConverter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
-        assert!(result.markdown.contains("> Note: First line"));
-        assert!(result.markdown.contains("> Second line"));
-        assert!(result.markdown.contains("> Third line"));
+        assert!(result.markdown.contains("- Fallback node"));
+        assert!(result.warnings.is_empty());
     }
 
     #[test]
-    fn test_pptx_notes_missing() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("Slide"),
-            body_texts: vec!["Text."],
-            notes: None,
-            table: None,
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
+    fn test_pptx_smartart_missing_data_warns() {
+        let data = build_raw_pptx(&[(DIAGRAM_SLIDE, Some(DIAGRAM_RELS))], "", &[]);
+        let result = PptxConverter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
-        assert!(!result.markdown.contains("> Note:"));
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code == WarningCode::SkippedElement && w.message.contains("SmartArt"))
+        );
+    }
+
+    // ---- Slide selection, hidden slides, and section tests ----
+
+    const SECTIONS_EXT: &str = r#"<p:extLst><p:ext uri="{521415D9-36F7-43E2-AB2F-B90AF26B5E84}"><p14:sectionLst><p14:section name="Intro" id="{A}"><p14:sldIdLst><p14:sldId id="256"/></p14:sldIdLst></p14:section><p14:section name="Results &amp; Data" id="{B}"><p14:sldIdLst><p14:sldId id="257"/><p14:sldId id="258"/></p14:sldIdLst></p14:section></p14:sectionLst></p:ext></p:extLst>"#;
+
+    fn build_three_slide_pptx(second_slide_attrs: &str) -> Vec<u8> {
+        let s1 = simple_slide_xml("", Some("Welcome"), &["Opening"]);
+        let s2 = simple_slide_xml(second_slide_attrs, Some("Backup"), &["Secret"]);
+        let s3 = simple_slide_xml("", Some("Summary"), &["Closing"]);
+        build_raw_pptx(&[(&s1, None), (&s2, None), (&s3, None)], SECTIONS_EXT, &[])
     }
 
     #[test]
-    fn test_pptx_unicode_cjk() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("다국어"),
-            body_texts: vec!["한국어 테스트", "中文测试", "日本語テスト"],
-            notes: None,
-            table: None,
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("한국어 테스트"));
-        assert!(result.markdown.contains("中文测试"));
-        assert!(result.markdown.contains("日本語テスト"));
-        assert!(result.markdown.contains("다국어"));
+    fn test_pptx_resolve_slide_order_with_sections() {
+        let pres_xml = format!(
+            r#"<p:presentation xmlns:p="p" xmlns:r="r" xmlns:p14="p14"><p:sldIdLst><p:sldId id="256" r:id="rId1"/><p:sldId id="257" r:id="rId2"/><p:sldId id="258" r:id="rId3"/></p:sldIdLst>{SECTIONS_EXT}</p:presentation>"#
+        );
+        let rels = parse_relationships(
+            r#"<Relationships><Relationship Id="rId1" Type="slide" Target="slides/slide1.xml"/><Relationship Id="rId2" Type="slide" Target="slides/slide2.xml"/><Relationship Id="rId3" Type="slide" Target="slides/slide3.xml"/></Relationships>"#,
+        );
+        let slides = resolve_slide_order(&pres_xml, &rels);
+        assert_eq!(slides.len(), 3);
+        assert_eq!(slides[0].section.as_deref(), Some("Intro"));
+        assert_eq!(slides[1].section.as_deref(), Some("Results & Data"));
+        assert_eq!(slides[2].section.as_deref(), Some("Results & Data"));
+        assert_eq!(slides[2].number, 3);
     }
 
     #[test]
-    fn test_pptx_emoji() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("Emoji Test"),
-            body_texts: vec!["Rocket: 🚀 Stars: ✨ Earth: 🌍"],
-            notes: None,
-            table: None,
-            images: vec![],
-            image_alt_texts: vec![],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
+    fn test_pptx_is_slide_hidden() {
+        assert!(is_slide_hidden(&simple_slide_xml(r#"show="0""#, None, &[])));
+        assert!(!is_slide_hidden(&simple_slide_xml(
+            r#"show="1""#,
+            None,
+            &[]
+        )));
+        assert!(!is_slide_hidden(&simple_slide_xml("", None, &[])));
+    }
+
+    #[test]
+    fn test_pptx_hidden_slides_included_by_default() {
+        let data = build_three_slide_pptx(r#"show="0""#);
+        let result = PptxConverter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
-        assert!(result.markdown.contains("🚀"));
-        assert!(result.markdown.contains("✨"));
-        assert!(result.markdown.contains("🌍"));
+        assert!(result.markdown.contains("## Slide 2: Backup\n"));
+        assert!(result.markdown.contains("Secret"));
+        assert!(!result.markdown.contains("# Intro"));
     }
 
     #[test]
-    fn test_pptx_missing_presentation_xml() {
-        use std::io::Write;
-        use zip::ZipWriter;
-        use zip::write::SimpleFileOptions;
-
-        let buf = Vec::new();
-        let mut zip = ZipWriter::new(Cursor::new(buf));
-        let opts = SimpleFileOptions::default();
+    fn test_pptx_hidden_slides_skipped() {
+        let data = build_three_slide_pptx(r#"show="0""#);
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                hidden_slides: HiddenSlides::Skip,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
+        assert!(!result.markdown.contains("Secret"));
+        // Remaining slides keep their original numbers
+        assert!(result.markdown.contains("## Slide 1: Welcome"));
+        assert!(result.markdown.contains("## Slide 3: Summary"));
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::SkippedElement);
+        assert!(result.warnings[0].message.contains("hidden slide 2"));
+    }
 
-        // Just a content types file, no presentation.xml
-        zip.start_file("[Content_Types].xml", opts).unwrap();
-        zip.write_all(b"<?xml version=\"1.0\"?><Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"></Types>").unwrap();
+    #[test]
+    fn test_pptx_hidden_slides_marked() {
+        let data = build_three_slide_pptx(r#"show="0""#);
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                hidden_slides: HiddenSlides::Mark,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
+        assert!(result.markdown.contains("## Slide 2: Backup (hidden)"));
+        assert!(result.markdown.contains("## Slide 1: Welcome\n"));
+        assert!(result.plain_text.contains("Backup (hidden)"));
+    }
 
-        let cursor = zip.finish().unwrap();
-        let data = cursor.into_inner();
+    #[test]
+    fn test_pptx_slide_selection() {
+        let data = build_three_slide_pptx("");
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                slides: Some((2..=3).collect()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
+        assert!(!result.markdown.contains("Welcome"));
+        assert!(result.markdown.starts_with("## Slide 2: Backup"));
+        assert!(result.markdown.contains("## Slide 3: Summary"));
+        assert_eq!(result.title.as_deref(), Some("Backup"));
+    }
 
-        let converter = PptxConverter;
-        let result = converter.convert(&data, &ConversionOptions::default());
-        assert!(result.is_err());
-        let err = result.unwrap_err();
+    #[test]
+    fn test_pptx_section_headings() {
+        let data = build_three_slide_pptx("");
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                section_headings: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
         assert!(
-            format!("{err}").contains("missing ppt/presentation.xml"),
-            "error was: {err}"
+            result
+                .markdown
+                .starts_with("# Intro\n\n## Slide 1: Welcome")
+        );
+        assert!(
+            result
+                .markdown
+                .contains("---\n\n# Results & Data\n\n## Slide 2: Backup")
         );
+        // Section heading is emitted once, not repeated for later slides
+        assert_eq!(result.markdown.matches("# Results & Data").count(), 1);
+        assert!(result.plain_text.starts_with("Intro\n\nWelcome"));
     }
 
     #[test]
-    fn test_pptx_missing_slide_file_graceful() {
-        use std::io::Write;
-        use zip::ZipWriter;
-        use zip::write::SimpleFileOptions;
-
-        let buf = Vec::new();
-        let mut zip = ZipWriter::new(Cursor::new(buf));
-        let opts = SimpleFileOptions::default();
-
-        zip.start_file("[Content_Types].xml", opts).unwrap();
-        zip.write_all(b"<?xml version=\"1.0\"?><Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"></Types>").unwrap();
+    fn test_pptx_section_heading_follows_slide_selection() {
+        let data = build_three_slide_pptx("");
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                slides: Some(vec![3]),
+                section_headings: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
+        assert!(
+            result
+                .markdown
+                .starts_with("# Results & Data\n\n## Slide 3: Summary")
+        );
+    }
 
-        // presentation.xml references a slide that doesn't exist
-        let pres_xml = r#"<?xml version="1.0"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst><p:sldId id="256" r:id="rId1"/></p:sldIdLst></p:presentation>"#;
-        zip.start_file("ppt/presentation.xml", opts).unwrap();
-        zip.write_all(pres_xml.as_bytes()).unwrap();
+    // ---- Table merge and header flag tests ----
 
-        let pres_rels = r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/></Relationships>"#;
-        zip.start_file("ppt/_rels/presentation.xml.rels", opts)
-            .unwrap();
-        zip.write_all(pres_rels.as_bytes()).unwrap();
+    /// Wrap `<a:tbl>` inner XML in a slide with a single graphic frame.
+    fn table_slide_xml(tbl_inner: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="3" name="Table"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><a:graphic><a:graphicData><a:tbl>{tbl_inner}</a:tbl></a:graphicData></a:graphic></p:graphicFrame></p:spTree></p:cSld></p:sld>"#
+        )
+    }
 
-        let cursor = zip.finish().unwrap();
-        let data = cursor.into_inner();
+    fn tc(attrs: &str, text: &str) -> String {
+        format!(
+            r#"<a:tc{attrs}><a:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p></a:txBody></a:tc>"#
+        )
+    }
 
-        let converter = PptxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(!result.warnings.is_empty());
-        assert_eq!(result.warnings[0].code, WarningCode::SkippedElement);
-        assert!(result.warnings[0].message.contains("slide file not found"));
+    fn parse_single_table(tbl_inner: &str) -> (Vec<String>, Vec<Vec<String>>) {
+        let (shapes, _, warnings) = parse_slide(&table_slide_xml(tbl_inner));
+        assert!(warnings.is_empty());
+        match shapes.into_iter().next() {
+            Some(ShapeContent::Table { headers, rows }) => (headers, rows),
+            other => panic!("expected Table, got {:?}", other),
+        }
     }
 
     #[test]
-    fn test_pptx_derive_rels_path() {
-        assert_eq!(
-            derive_rels_path("ppt/slides/slide1.xml"),
-            "ppt/slides/_rels/slide1.xml.rels"
-        );
-        assert_eq!(
-            derive_rels_path("ppt/presentation.xml"),
-            "ppt/_rels/presentation.xml.rels"
+    fn test_pptx_table_grid_span_with_hmerge_continuation() {
+        let tbl = format!(
+            r#"<a:tblPr firstRow="1"/><a:tblGrid><a:gridCol w="1"/><a:gridCol w="1"/><a:gridCol w="1"/></a:tblGrid><a:tr>{}{}{}</a:tr><a:tr>{}{}{}</a:tr>"#,
+            tc(r#" gridSpan="2""#, "Q1"),
+            r#"<a:tc hMerge="1"><a:txBody><a:p/></a:txBody></a:tc>"#,
+            tc("", "Q2"),
+            tc("", "a"),
+            tc("", "b"),
+            tc("", "c"),
         );
-        assert_eq!(derive_rels_path("file.xml"), "_rels/file.xml.rels");
+        let (headers, rows) = parse_single_table(&tbl);
+        assert_eq!(headers, vec!["Q1", "", "Q2"]);
+        assert_eq!(rows, vec![vec!["a", "b", "c"]]);
     }
 
     #[test]
-    fn test_pptx_resolve_relative_to_file() {
-        assert_eq!(
-            resolve_relative_to_file("ppt/slides/slide1.xml", "../media/image1.png"),
-            "ppt/media/image1.png"
-        );
-        assert_eq!(
-            resolve_relative_to_file("ppt/slides/slide1.xml", "/ppt/media/image1.png"),
-            "ppt/media/image1.png"
-        );
-        assert_eq!(
-            resolve_relative_to_file("ppt/slides/slide1.xml", "../notesSlides/notesSlide1.xml"),
-            "ppt/notesSlides/notesSlide1.xml"
-        );
-        assert_eq!(
-            resolve_relative_to_file("ppt/slides/slide1.xml", "chart1.xml"),
-            "ppt/slides/chart1.xml"
+    fn test_pptx_table_grid_span_without_continuation_cells() {
+        let tbl = format!(
+            r#"<a:tblPr firstRow="1"/><a:tr>{}{}</a:tr><a:tr>{}{}{}</a:tr>"#,
+            tc(r#" gridSpan="2""#, "Wide"),
+            tc("", "Last"),
+            tc("", "a"),
+            tc("", "b"),
+            tc("", "c"),
         );
+        let (headers, _) = parse_single_table(&tbl);
+        assert_eq!(headers, vec!["Wide", "", "Last"]);
     }
 
     #[test]
-    fn test_pptx_image_reference_detected() {
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:pic><p:nvPicPr><p:cNvPr id="1" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId2"/></p:blipFill></p:pic></p:spTree></p:cSld></p:sld>"#;
-
-        let (shapes, _) = parse_slide(slide_xml);
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Image { rel_id, .. } => assert_eq!(rel_id, "rId2"),
-            other => panic!("expected Image, got {:?}", other),
-        }
+    fn test_pptx_table_row_span_with_vmerge_continuation() {
+        let tbl = format!(
+            r#"<a:tblPr firstRow="1"/><a:tr>{}{}</a:tr><a:tr>{}{}</a:tr><a:tr>{}{}</a:tr>"#,
+            tc("", "Region"),
+            tc("", "Sales"),
+            tc(r#" rowSpan="2""#, "North"),
+            tc("", "10"),
+            r#"<a:tc vMerge="1"/>"#,
+            tc("", "20"),
+        );
+        let (headers, rows) = parse_single_table(&tbl);
+        assert_eq!(headers, vec!["Region", "Sales"]);
+        assert_eq!(rows, vec![vec!["North", "10"], vec!["", "20"]]);
     }
 
     #[test]
-    fn test_pptx_image_alt_text_extracted() {
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:pic><p:nvPicPr><p:cNvPr id="10" descr="A beautiful chart" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId2"/></p:blipFill></p:pic></p:spTree></p:cSld></p:sld>"#;
-
-        let (shapes, _) = parse_slide(slide_xml);
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Image { rel_id, alt_text } => {
-                assert_eq!(rel_id, "rId2");
-                assert_eq!(alt_text.as_deref(), Some("A beautiful chart"));
-            }
-            other => panic!("expected Image, got {:?}", other),
-        }
+    fn test_pptx_table_row_span_without_continuation_cells() {
+        let tbl = format!(
+            r#"<a:tr>{}{}{}</a:tr><a:tr>{}{}</a:tr>"#,
+            tc("", "A"),
+            tc(r#" rowSpan="2""#, "Tall"),
+            tc("", "C"),
+            tc("", "x"),
+            tc("", "z"),
+        );
+        let (headers, rows) = parse_single_table(&tbl);
+        assert_eq!(headers, vec!["A", "Tall", "C"]);
+        assert_eq!(rows, vec![vec!["x", "", "z"]]);
     }
 
     #[test]
-    fn test_pptx_image_alt_text_missing() {
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:pic><p:nvPicPr><p:cNvPr id="10" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId3"/></p:blipFill></p:pic></p:spTree></p:cSld></p:sld>"#;
-
-        let (shapes, _) = parse_slide(slide_xml);
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Image { rel_id, alt_text } => {
-                assert_eq!(rel_id, "rId3");
-                assert!(alt_text.is_none());
-            }
-            other => panic!("expected Image, got {:?}", other),
-        }
+    fn test_pptx_table_absurd_spans_are_clamped() {
+        let tbl = format!(
+            r#"<a:tblPr firstRow="1"/><a:tr>{}{}</a:tr><a:tr>{}{}</a:tr>"#,
+            tc(r#" gridSpan="4000000000""#, "Wide"),
+            tc(r#" rowSpan="18446744073709551615""#, "Tall"),
+            tc("", "a"),
+            tc("", "b"),
+        );
+        let (headers, rows) = parse_single_table(&tbl);
+        // Column spans stop at twice the most cells in a row
+        assert_eq!(headers, vec!["Wide", "", "", "", "Tall"]);
+        assert_eq!(rows, vec![vec!["a", "b", "", "", ""]]);
+        assert_eq!(span_attr("4000000000", MAX_GRID_SPAN), MAX_GRID_SPAN);
+        assert_eq!(span_attr("0", MAX_ROW_SPAN), 1);
     }
 
     #[test]
-    fn test_pptx_image_alt_text_in_markdown() {
-        let data = build_test_pptx(&[TestSlide {
-            title: Some("Images"),
-            body_texts: vec![],
-            notes: None,
-            table: None,
-            images: vec!["rIdImg1"],
-            image_alt_texts: vec![Some("A diagram of the architecture")],
-        }]);
-        let converter = PptxConverter;
-        let result = converter
+    fn test_pptx_table_first_row_flag_off_emits_empty_header() {
+        let tbl = format!(
+            r#"<a:tblPr bandRow="1"/><a:tr>{}{}</a:tr><a:tr>{}{}</a:tr>"#,
+            tc("", "a"),
+            tc("", "b"),
+            tc("", "c"),
+            tc("", "d"),
+        );
+        let (headers, rows) = parse_single_table(&tbl);
+        assert_eq!(headers, vec!["", ""]);
+        assert_eq!(rows, vec![vec!["a", "b"], vec!["c", "d"]]);
+
+        let data = build_raw_pptx(&[(&table_slide_xml(&tbl), None)], "", &[]);
+        let result = PptxConverter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
         assert!(
             result
                 .markdown
-                .contains("![A diagram of the architecture](image1.png)"),
+                .contains("|  |  |\n|---|---|\n| a | b |\n| c | d |"),
             "markdown was: {}",
             result.markdown
         );
+        assert!(result.plain_text.contains("a\tb\nc\td"));
+        assert!(!result.plain_text.contains("\t\n"));
     }
 
     #[test]
-    fn test_pptx_line_break() {
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Content"/><p:cNvSpPr/><p:nvPr><p:ph type="body"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Line one</a:t><a:br/><a:t>Line two</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
-
-        let (shapes, _) = parse_slide(slide_xml);
-        match &shapes[0] {
-            ShapeContent::Body(text) => assert!(text.contains("Line one\nLine two")),
-            other => panic!("expected Body, got {:?}", other),
-        }
+    fn test_pptx_table_first_row_flag_on() {
+        let tbl = format!(
+            r#"<a:tblPr firstRow="1" bandRow="1"/><a:tr>{}</a:tr><a:tr>{}</a:tr>"#,
+            tc("", "Head"),
+            tc("", "cell"),
+        );
+        let (headers, rows) = parse_single_table(&tbl);
+        assert_eq!(headers, vec!["Head"]);
+        assert_eq!(rows, vec![vec!["cell"]]);
     }
 
-    // ---- Image describer tests ----
+    // ---- Comments tests ----
 
-    use crate::converter::ImageDescriber;
-    use std::sync::Arc;
+    const LEGACY_AUTHORS: &str = r#"<?xml version="1.0"?><p:cmAuthorLst xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cmAuthor id="0" name="Jane Doe" initials="JD" lastIdx="2" clrIdx="0"/><p:cmAuthor id="1" name="Bob &amp; Co" initials="B" lastIdx="1" clrIdx="1"/></p:cmAuthorLst>"#;
 
-    struct MockDescriber {
-        description: String,
-    }
+    const LEGACY_COMMENTS: &str = r#"<?xml version="1.0"?><p:cmLst xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cm authorId="0" dt="2024-03-01T10:00:00.000" idx="1"><p:pos x="10" y="10"/><p:text>Use the brand colours here.</p:text></p:cm><p:cm authorId="1" dt="2024-03-01T11:00:00.000" idx="1"><p:pos x="20" y="20"/><p:text>Chart axis is wrong</p:text></p:cm></p:cmLst>"#;
 
-    impl ImageDescriber for MockDescriber {
-        fn describe(
-            &self,
-            _image_bytes: &[u8],
-            _mime_type: &str,
-            _prompt: &str,
-        ) -> Result<String, ConvertError> {
-            Ok(self.description.clone())
-        }
+    const MODERN_AUTHORS: &str = r#"<?xml version="1.0"?><p188:authorLst xmlns:p188="http://schemas.microsoft.com/office/powerpoint/2018/8/main"><p188:author id="{A1}" name="Alice" initials="A" userId="alice" providerId="AD"/><p188:author id="{B2}" name="Carol" initials="C" userId="carol" providerId="AD"/></p188:authorLst>"#;
+
+    const MODERN_COMMENTS: &str = r#"<?xml version="1.0"?><p188:cmLst xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p188="http://schemas.microsoft.com/office/powerpoint/2018/8/main"><p188:cm id="{C1}" authorId="{A1}" created="2024-05-01T09:00:00.000"><p188:txBody><a:bodyPr/><a:lstStyle/><a:p><a:r><a:t>Should this be</a:t></a:r></a:p><a:p><a:r><a:t>two slides?</a:t></a:r></a:p></p188:txBody><p188:replyLst><p188:reply id="{R1}" authorId="{B2}" created="2024-05-01T10:00:00.000"><p188:txBody><a:bodyPr/><a:p><a:r><a:t>Yes, split it.</a:t></a:r></a:p></p188:txBody></p188:reply></p188:replyLst></p188:cm></p188:cmLst>"#;
+
+    #[test]
+    fn test_pptx_parse_legacy_comments() {
+        let mut authors = HashMap::new();
+        parse_comment_authors(LEGACY_AUTHORS, &mut authors);
+        assert_eq!(authors.get("1").map(String::as_str), Some("Bob & Co"));
+
+        let comments = parse_comments(LEGACY_COMMENTS, &authors);
+        assert_eq!(
+            comments,
+            vec![
+                SlideComment {
+                    author: "Jane Doe".to_string(),
+                    text: "Use the brand colours here.".to_string(),
+                    is_reply: false,
+                },
+                SlideComment {
+                    author: "Bob & Co".to_string(),
+                    text: "Chart axis is wrong".to_string(),
+                    is_reply: false,
+                },
+            ]
+        );
     }
 
-    struct FailingDescriber;
+    #[test]
+    fn test_pptx_parse_modern_comments_with_replies() {
+        let mut authors = HashMap::new();
+        parse_comment_authors(MODERN_AUTHORS, &mut authors);
+        let comments = parse_comments(MODERN_COMMENTS, &authors);
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].author, "Alice");
+        assert_eq!(comments[0].text, "Should this be two slides?");
+        assert!(!comments[0].is_reply);
+        assert_eq!(comments[1].author, "Carol");
+        assert_eq!(comments[1].text, "Yes, split it.");
+        assert!(comments[1].is_reply);
+    }
 
-    impl ImageDescriber for FailingDescriber {
-        fn describe(
-            &self,
-            _image_bytes: &[u8],
-            _mime_type: &str,
-            _prompt: &str,
-        ) -> Result<String, ConvertError> {
-            Err(ConvertError::ImageDescriptionError {
-                reason: "API error".to_string(),
-            })
-        }
+    #[test]
+    fn test_pptx_parse_comments_unknown_author() {
+        let comments = parse_comments(LEGACY_COMMENTS, &HashMap::new());
+        assert!(comments.iter().all(|c| c.author == "Unknown"));
     }
 
-    /// Build a PPTX with actual image data embedded in the ZIP for describer tests.
-    fn build_test_pptx_with_image_data(
-        slides: &[TestSlide],
-        image_data: &[(&str, &[u8])], // (path in zip, data)
-    ) -> Vec<u8> {
-        use std::io::Write;
-        use zip::ZipWriter;
-        use zip::write::SimpleFileOptions;
+    fn build_commented_pptx() -> Vec<u8> {
+        let slide = simple_slide_xml("", Some("Design"), &["Draft layout"]);
+        let rels = r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments/comment1.xml"/></Relationships>"#;
+        build_raw_pptx(
+            &[(&slide, Some(rels))],
+            "",
+            &[
+                ("ppt/commentAuthors.xml", LEGACY_AUTHORS.as_bytes()),
+                ("ppt/comments/comment1.xml", LEGACY_COMMENTS.as_bytes()),
+            ],
+        )
+    }
 
-        let buf = Vec::new();
-        let mut zip = ZipWriter::new(Cursor::new(buf));
-        let opts = SimpleFileOptions::default();
+    #[test]
+    fn test_pptx_comments_off_by_default() {
+        let data = build_commented_pptx();
+        let result = PptxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(!result.markdown.contains("Comments"));
+        assert!(!result.markdown.contains("brand colours"));
+    }
 
-        let mut ct = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
-        ct.push_str(
-            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+    #[test]
+    fn test_pptx_comments_rendered_under_slide() {
+        let data = build_commented_pptx();
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                comments: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
+        assert!(
+            result.markdown.contains(
+                "### Comments\n\n- Jane Doe: Use the brand colours here.\n- Bob & Co: Chart axis is wrong"
+            ),
+            "markdown was: {}",
+            result.markdown
         );
-        ct.push_str(
-            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        assert!(
+            result
+                .plain_text
+                .contains("Jane Doe: Use the brand colours here.")
         );
-        ct.push_str(r#"<Default Extension="xml" ContentType="application/xml"/>"#);
-        ct.push_str(r#"<Default Extension="png" ContentType="image/png"/>"#);
-        ct.push_str("</Types>");
-        zip.start_file("[Content_Types].xml", opts).unwrap();
-        zip.write_all(ct.as_bytes()).unwrap();
+        assert!(result.warnings.is_empty());
+    }
 
-        let mut pres_xml = String::from(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst>"#,
-        );
-        let mut pres_rels_xml = String::from(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    #[test]
+    fn test_pptx_modern_comments_supersede_legacy() {
+        let slide = simple_slide_xml("", Some("Review"), &[]);
+        let rels = r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments/comment1.xml"/><Relationship Id="rId3" Type="http://schemas.microsoft.com/office/2018/10/relationships/comments" Target="../comments/modernComment_100_0.xml"/></Relationships>"#;
+        let data = build_raw_pptx(
+            &[(&slide, Some(rels))],
+            "",
+            &[
+                ("ppt/commentAuthors.xml", LEGACY_AUTHORS.as_bytes()),
+                ("ppt/authors.xml", MODERN_AUTHORS.as_bytes()),
+                ("ppt/comments/comment1.xml", LEGACY_COMMENTS.as_bytes()),
+                (
+                    "ppt/comments/modernComment_100_0.xml",
+                    MODERN_COMMENTS.as_bytes(),
+                ),
+            ],
         );
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                comments: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("- Alice: Should this be two slides?\n  - Carol: Yes, split it.")
+        );
+        assert!(!result.markdown.contains("brand colours"));
+    }
 
-        for (i, slide) in slides.iter().enumerate() {
-            let slide_num = i + 1;
-            let rid = format!("rId{slide_num}");
-            let slide_id = 256 + i;
-
-            pres_xml.push_str(&format!(r#"<p:sldId id="{slide_id}" r:id="{rid}"/>"#));
-            pres_rels_xml.push_str(&format!(
-                r#"<Relationship Id="{rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{slide_num}.xml"/>"#
-            ));
-
-            let slide_xml = build_slide_xml(slide);
-            zip.start_file(format!("ppt/slides/slide{slide_num}.xml"), opts)
-                .unwrap();
-            zip.write_all(slide_xml.as_bytes()).unwrap();
-
-            if slide.notes.is_some() || !slide.images.is_empty() {
-                let mut slide_rels = String::from(
-                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
-                );
-
-                if slide.notes.is_some() {
-                    slide_rels.push_str(&format!(
-                        r#"<Relationship Id="rIdNotes" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{slide_num}.xml"/>"#
-                    ));
-                }
+    // ---- Media and OLE object tests ----
+
+    const MEDIA_SLIDE: &str = r#"<?xml version="1.0"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p14="http://schemas.microsoft.com/office/powerpoint/2010/main"><p:cSld><p:spTree>
+        <p:pic><p:nvPicPr><p:cNvPr id="4" name="intro.mp4"/><p:cNvPicPr/><p:nvPr><a:videoFile r:link="rId2"/><p:extLst><p:ext uri="{DAA4B4D4-6D71-4841-9C94-3DE7FCFB9230}"><p14:media r:embed="rId3"/></p:ext></p:extLst></p:nvPr></p:nvPicPr><p:blipFill><a:blip r:embed="rId4"/></p:blipFill></p:pic>
+        <p:pic><p:nvPicPr><p:cNvPr id="5" name="chime.wav"/><p:cNvPicPr/><p:nvPr><a:audioFile r:link="rId5"/></p:nvPr></p:nvPicPr><p:blipFill><a:blip r:embed="rId4"/></p:blipFill></p:pic>
+    </p:spTree></p:cSld></p:sld>"#;
+
+    const MEDIA_RELS: &str = r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/video" Target="../media/media1.mp4"/><Relationship Id="rId3" Type="http://schemas.microsoft.com/office/2007/relationships/media" Target="../media/media1.mp4"/><Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/><Relationship Id="rId5" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/audio" Target="../media/media2.wav"/></Relationships>"#;
+
+    /// OLE object wrapped in mc:AlternateContent, as PowerPoint writes it.
+    const OLE_SLIDE: &str = r#"<?xml version="1.0"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"><p:cSld><p:spTree>
+        <p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="6" name="Object 5"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/presentationml/2006/ole"><mc:AlternateContent><mc:Choice Requires="v"><p:oleObj spid="_x0000_s1026" name="Worksheet" r:id="rId2" imgW="100" imgH="100" progId="Word.Document.12"/></mc:Choice><mc:Fallback><p:oleObj name="Worksheet" r:id="rId2" imgW="100" imgH="100" progId="Word.Document.12"><p:embed/><p:pic><p:nvPicPr><p:cNvPr id="0" name=""/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId3"/></p:blipFill></p:pic></p:oleObj></mc:Fallback></mc:AlternateContent></a:graphicData></a:graphic></p:graphicFrame>
+    </p:spTree></p:cSld></p:sld>"#;
+
+    const OLE_RELS: &str = r#"<?xml version="1.0"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/package" Target="../embeddings/Microsoft_Word_Document.docx"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.emf"/></Relationships>"#;
+
+    /// Minimal MP4: `ftyp`, then `moov` containing a version-0 `mvhd`.
+    fn build_mp4(timescale: u32, duration: u32) -> Vec<u8> {
+        let mut mvhd = Vec::new();
+        mvhd.extend_from_slice(&[0, 0, 0, 0]); // version + flags
+        mvhd.extend_from_slice(&[0; 8]); // creation + modification time
+        mvhd.extend_from_slice(&timescale.to_be_bytes());
+        mvhd.extend_from_slice(&duration.to_be_bytes());
+        mvhd.extend_from_slice(&[0; 80]);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&16u32.to_be_bytes());
+        data.extend_from_slice(b"ftypisom");
+        data.extend_from_slice(&[0, 0, 2, 0]);
+        // A free box before moov must be skipped
+        data.extend_from_slice(&12u32.to_be_bytes());
+        data.extend_from_slice(b"free");
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&(16 + mvhd.len() as u32).to_be_bytes());
+        data.extend_from_slice(b"moov");
+        data.extend_from_slice(&(8 + mvhd.len() as u32).to_be_bytes());
+        data.extend_from_slice(b"mvhd");
+        data.extend_from_slice(&mvhd);
+        data
+    }
 
-                for (img_idx, _) in slide.images.iter().enumerate() {
-                    let img_rid = format!("rIdImg{}", img_idx + 1);
-                    slide_rels.push_str(&format!(
-                        r#"<Relationship Id="{img_rid}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}.png"/>"#,
-                        img_idx + 1
-                    ));
-                }
+    /// Minimal PCM WAV with `data_len` bytes of silence.
+    fn build_wav(byte_rate: u32, data_len: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"RIFF");
+        data.extend_from_slice(&(36 + data_len).to_le_bytes());
+        data.extend_from_slice(b"WAVEfmt ");
+        data.extend_from_slice(&16u32.to_le_bytes());
+        data.extend_from_slice(&[1, 0, 1, 0]); // PCM, mono
+        data.extend_from_slice(&(byte_rate / 2).to_le_bytes()); // sample rate
+        data.extend_from_slice(&byte_rate.to_le_bytes());
+        data.extend_from_slice(&[2, 0, 16, 0]);
+        data.extend_from_slice(b"data");
+        data.extend_from_slice(&data_len.to_le_bytes());
+        data.extend(std::iter::repeat_n(0u8, data_len as usize));
+        data
+    }
 
-                slide_rels.push_str("</Relationships>");
-                zip.start_file(format!("ppt/slides/_rels/slide{slide_num}.xml.rels"), opts)
-                    .unwrap();
-                zip.write_all(slide_rels.as_bytes()).unwrap();
+    fn build_minimal_docx(text: &str) -> Vec<u8> {
+        use std::io::Write;
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(
+            "word/document.xml",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.write_all(format!(r#"<?xml version="1.0"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body><w:p><w:r><w:t>{text}</w:t></w:r></w:p></w:body></w:document>"#).as_bytes()).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_pptx_parse_media_shapes() {
+        let (shapes, _, _) = parse_slide(MEDIA_SLIDE);
+        assert_eq!(shapes.len(), 2);
+        match &shapes[0] {
+            ShapeContent::Media { kind, rel_id, .. } => {
+                assert_eq!(*kind, MediaKind::Video);
+                // p14:media embed wins over the a:videoFile link
+                assert_eq!(rel_id, "rId3");
             }
+            other => panic!("expected media, got {other:?}"),
+        }
+        match &shapes[1] {
+            ShapeContent::Media { kind, rel_id, .. } => {
+                assert_eq!(*kind, MediaKind::Audio);
+                assert_eq!(rel_id, "rId5");
+            }
+            other => panic!("expected media, got {other:?}"),
+        }
+    }
 
-            if let Some(notes_text) = slide.notes {
-                let notes_xml = build_notes_xml(notes_text);
-                zip.start_file(format!("ppt/notesSlides/notesSlide{slide_num}.xml"), opts)
-                    .unwrap();
-                zip.write_all(notes_xml.as_bytes()).unwrap();
+    #[test]
+    fn test_pptx_parse_ole_object_deduplicates_alternate_content() {
+        let (shapes, _, _) = parse_slide(OLE_SLIDE);
+        assert_eq!(shapes.len(), 1, "shapes: {shapes:?}");
+        match &shapes[0] {
+            ShapeContent::Embedded {
+                rel_id, prog_id, ..
+            } => {
+                assert_eq!(rel_id, "rId2");
+                assert_eq!(prog_id.as_deref(), Some("Word.Document.12"));
             }
+            other => panic!("expected embedded object, got {other:?}"),
         }
+    }
 
-        pres_xml.push_str("</p:sldIdLst></p:presentation>");
-        pres_rels_xml.push_str("</Relationships>");
+    #[test]
+    fn test_pptx_format_duration() {
+        assert_eq!(format_duration(5.4), "0:05");
+        assert_eq!(format_duration(65.0), "1:05");
+        assert_eq!(format_duration(3725.0), "1:02:05");
+    }
 
-        zip.start_file("ppt/presentation.xml", opts).unwrap();
-        zip.write_all(pres_xml.as_bytes()).unwrap();
+    #[test]
+    fn test_pptx_demote_headings_skips_code_fences() {
+        let md = "# Title\n\n```\n# not a heading\n```\n\n###### Deep\n#hashtag\n";
+        assert_eq!(
+            demote_headings(md, 2),
+            "### Title\n\n```\n# not a heading\n```\n\n###### Deep\n#hashtag\n"
+        );
+    }
 
-        zip.start_file("ppt/_rels/presentation.xml.rels", opts)
+    #[test]
+    fn test_pptx_media_placeholders_with_duration() {
+        let mp4 = build_mp4(1000, 65_000);
+        let wav = build_wav(8000, 16_000);
+        let data = build_raw_pptx(
+            &[(MEDIA_SLIDE, Some(MEDIA_RELS))],
+            "",
+            &[
+                ("ppt/media/media1.mp4", mp4.as_slice()),
+                ("ppt/media/media2.wav", wav.as_slice()),
+            ],
+        );
+        let result = PptxConverter
+            .convert(&data, &ConversionOptions::default())
             .unwrap();
-        zip.write_all(pres_rels_xml.as_bytes()).unwrap();
+        assert!(
+            result.markdown.contains("[Video: media1.mp4 (1:05)]"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("[Audio: media2.wav (0:02)]"));
+        assert!(result.plain_text.contains("[Video: media1.mp4 (1:05)]"));
+        // The poster frame is not reported as a separate image
+        assert!(!result.markdown.contains("!["));
+    }
 
-        // Add image files
-        for (path, data) in image_data {
-            zip.start_file(path.to_string(), opts).unwrap();
-            zip.write_all(data).unwrap();
-        }
+    #[test]
+    fn test_pptx_media_kind_from_relationship_and_content_type() {
+        let types = parse_content_types(
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="MP4" ContentType="video/mp4"/><Default Extension="m4a" ContentType="audio/mp4"/><Override PartName="/ppt/media/media3.bin" ContentType="audio/x-wav"/></Types>"#,
+        );
+        assert_eq!(types.content_type("ppt/media/a.mp4"), Some("video/mp4"));
+        assert_eq!(
+            types.content_type("ppt/media/media3.bin"),
+            Some("audio/x-wav")
+        );
+        assert_eq!(types.content_type("ppt/media/a.gif"), None);
 
-        let cursor = zip.finish().unwrap();
-        cursor.into_inner()
+        assert_eq!(
+            media_kind(REL_TYPE_AUDIO, Some("video/mp4")),
+            Some(MediaKind::Audio)
+        );
+        assert_eq!(media_kind(REL_TYPE_VIDEO, None), Some(MediaKind::Video));
+        let generic = "http://schemas.microsoft.com/office/2007/relationships/media";
+        assert_eq!(
+            media_kind(generic, Some("audio/mp4")),
+            Some(MediaKind::Audio)
+        );
+        assert_eq!(media_kind(generic, Some("application/octet-stream")), None);
+        // Near misses are not treated as audio
+        assert_eq!(media_kind(&format!("{REL_TYPE_AUDIO}x"), None), None);
     }
 
     #[test]
-    fn test_pptx_multiple_images_on_one_slide() {
-        let data = build_test_pptx_with_image_data(
-            &[TestSlide {
-                title: Some("Gallery"),
-                body_texts: vec![],
-                notes: None,
-                table: None,
-                images: vec!["rIdImg1", "rIdImg2"],
-                image_alt_texts: vec![Some("First image"), Some("Second image")],
-            }],
-            &[
-                ("ppt/media/image1.png", b"fake-png-1"),
-                ("ppt/media/image2.png", b"fake-png-2"),
-            ],
-        );
+    fn test_pptx_media_duration_moov_after_large_mdat() {
+        use std::io::Write;
+        // ftyp, then an mdat larger than the scan limit, then moov
+        let mp4 = build_mp4(1000, 90_000);
+        let mdat_len = MEDIA_SCAN_LIMIT as usize + 1024;
+        let mut data = mp4[..16].to_vec();
+        data.extend_from_slice(&((mdat_len + 8) as u32).to_be_bytes());
+        data.extend_from_slice(b"mdat");
+        data.resize(data.len() + mdat_len, 0);
+        data.extend_from_slice(&mp4[16..]);
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let stored = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("stored.mp4", stored).unwrap();
+        zip.write_all(&data).unwrap();
+        zip.start_file("deflated.mp4", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&data).unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+        // Stored entries are seeked past the mdat payload
+        assert_eq!(media_duration_secs(&mut archive, "stored.mp4"), Some(90.0));
+        // Compressed entries stop at the scan limit instead of inflating everything
+        assert_eq!(media_duration_secs(&mut archive, "deflated.mp4"), None);
+    }
 
-        let converter = PptxConverter;
-        let result = converter
+    #[test]
+    fn test_pptx_media_placeholder_without_part() {
+        let data = build_raw_pptx(&[(MEDIA_SLIDE, Some(MEDIA_RELS))], "", &[]);
+        let result = PptxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("[Video: media1.mp4]"));
+        assert!(result.markdown.contains("[Audio: media2.wav]"));
+    }
+
+    #[test]
+    fn test_pptx_embedded_object_placeholder_by_default() {
+        let docx = build_minimal_docx("Embedded quarterly figures");
+        let data = build_raw_pptx(
+            &[(OLE_SLIDE, Some(OLE_RELS))],
+            "",
+            &[(
+                "ppt/embeddings/Microsoft_Word_Document.docx",
+                docx.as_slice(),
+            )],
+        );
+        let result = PptxConverter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
         assert!(
-            result.markdown.contains("![First image](image1.png)"),
+            result
+                .markdown
+                .contains("[Embedded object: Microsoft_Word_Document.docx (Word.Document.12)]"),
             "markdown was: {}",
             result.markdown
         );
+        assert!(!result.markdown.contains("Embedded quarterly figures"));
+    }
+
+    #[test]
+    fn test_pptx_embedded_object_converted_when_enabled() {
+        let docx = build_minimal_docx("Embedded quarterly figures");
+        let data = build_raw_pptx(
+            &[(OLE_SLIDE, Some(OLE_RELS))],
+            "",
+            &[(
+                "ppt/embeddings/Microsoft_Word_Document.docx",
+                docx.as_slice(),
+            )],
+        );
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                convert_embedded: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter.convert(&data, &options).unwrap();
         assert!(
-            result.markdown.contains("![Second image](image2.png)"),
+            result.markdown.contains(
+                "[Embedded object: Microsoft_Word_Document.docx (Word.Document.12)]\n\nEmbedded quarterly figures"
+            ),
             "markdown was: {}",
             result.markdown
         );
-        // Verify ordering: image1 appears before image2
-        let pos1 = result.markdown.find("image1.png").unwrap();
-        let pos2 = result.markdown.find("image2.png").unwrap();
-        assert!(pos1 < pos2, "image1 should appear before image2");
+        assert!(result.plain_text.contains("Embedded quarterly figures"));
     }
 
     #[test]
-    fn test_pptx_image_describer_replaces_alt_text() {
-        let data = build_test_pptx_with_image_data(
-            &[TestSlide {
-                title: Some("Slide with Image"),
-                body_texts: vec![],
-                notes: None,
-                table: None,
-                images: vec!["rIdImg1"],
-                image_alt_texts: vec![None],
-            }],
-            &[("ppt/media/image1.png", b"fake-png-data")],
+    fn test_pptx_embedded_object_conversion_failure_warns() {
+        let data = build_raw_pptx(
+            &[(OLE_SLIDE, Some(OLE_RELS))],
+            "",
+            &[(
+                "ppt/embeddings/Microsoft_Word_Document.docx",
+                b"not a zip".as_slice(),
+            )],
         );
-
-        let converter = PptxConverter;
         let options = ConversionOptions {
-            image_describer: Some(Arc::new(MockDescriber {
-                description: "A diagram showing data flow".to_string(),
-            })),
+            pptx: PptxOptions {
+                convert_embedded: true,
+                ..Default::default()
+            },
             ..Default::default()
         };
-        let result = converter.convert(&data, &options).unwrap();
+        let result = PptxConverter.convert(&data, &options).unwrap();
         assert!(
             result
                 .markdown
-                .contains("![A diagram showing data flow](image1.png)"),
-            "markdown was: {}",
-            result.markdown
+                .contains("[Embedded object: Microsoft_Word_Document.docx")
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code == WarningCode::SkippedElement
+                    && w.location.as_deref() == Some("Microsoft_Word_Document.docx"))
         );
-        assert!(result.images.is_empty());
     }
 
     #[test]
-    fn test_pptx_image_describer_error_keeps_original_alt() {
-        let data = build_test_pptx_with_image_data(
+    fn test_pptx_embedded_package_shares_image_budget() {
+        let nested = build_test_pptx_with_image_data(
             &[TestSlide {
-                title: Some("Slide"),
+                title: Some("Nested"),
                 body_texts: vec![],
                 notes: None,
                 table: None,
                 images: vec!["rIdImg1"],
-                image_alt_texts: vec![Some("Original description")],
+                image_alt_texts: vec![None],
             }],
             &[("ppt/media/image1.png", b"fake-png-data")],
         );
-
-        let converter = PptxConverter;
         let options = ConversionOptions {
-            image_describer: Some(Arc::new(FailingDescriber)),
+            extract_images: true,
+            max_total_image_bytes: 20,
             ..Default::default()
         };
-        let result = converter.convert(&data, &options).unwrap();
-        assert!(
-            result
-                .markdown
-                .contains("![Original description](image1.png)"),
-            "markdown was: {}",
-            result.markdown
+
+        // The nested image fits the remaining budget and is counted
+        let mut images = Vec::new();
+        let mut total_image_bytes = 5;
+        let mut warnings = Vec::new();
+        convert_embedded_package(
+            &nested,
+            "deck.pptx",
+            &options,
+            &mut images,
+            &mut total_image_bytes,
+            &mut warnings,
         );
-        assert!(
-            result
-                .warnings
-                .iter()
-                .any(|w| w.code == WarningCode::SkippedElement
-                    && w.message.contains("image description failed")),
+        assert_eq!(images.len(), 1);
+        assert_eq!(total_image_bytes, 5 + b"fake-png-data".len());
+
+        // Once the outer deck has used the budget, nothing more is extracted
+        let mut images = Vec::new();
+        let mut total_image_bytes = 15;
+        convert_embedded_package(
+            &nested,
+            "deck.pptx",
+            &options,
+            &mut images,
+            &mut total_image_bytes,
+            &mut warnings,
         );
+        assert!(images.is_empty());
+        assert_eq!(total_image_bytes, 15);
     }
 
-    // ---- Group shape tests ----
+    // ---- Reading order tests ----
 
-    #[test]
-    fn test_pptx_group_shape_text_extracted() {
-        // A single <p:sp> inside a <p:grpSp> should have its text extracted
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group 1"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:sp><p:nvSpPr><p:cNvPr id="11" name="TextBox"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>Group text</a:t></a:r></a:p></p:txBody></p:sp></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+    fn bounds(x: f64, y: f64, cx: f64, cy: f64) -> Option<ShapeBounds> {
+        Some(ShapeBounds { x, y, cx, cy })
+    }
 
-        let (shapes, warnings) = parse_slide(slide_xml);
-        assert!(warnings.is_empty());
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Body(text) => assert_eq!(text, "Group text"),
-            other => panic!("expected Body, got {:?}", other),
-        }
+    /// Text shape with an explicit position (EMU).
+    fn positioned_shape(text: &str, x: u64, y: u64, cx: u64, cy: u64) -> String {
+        format!(
+            r#"<p:sp><p:nvSpPr><p:cNvPr id="9" name="{text}"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="{x}" y="{y}"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm></p:spPr><p:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p></p:txBody></p:sp>"#
+        )
     }
 
-    #[test]
-    fn test_pptx_group_shape_multiple_children() {
-        // Two <p:sp> shapes inside one <p:grpSp>
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:sp><p:nvSpPr><p:cNvPr id="11" name="Shape1"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>First shape</a:t></a:r></a:p></p:txBody></p:sp><p:sp><p:nvSpPr><p:cNvPr id="12" name="Shape2"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>Second shape</a:t></a:r></a:p></p:txBody></p:sp></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+    fn slide_with_shapes(shapes: &[String]) -> String {
+        format!(
+            r#"<?xml version="1.0"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="1" name="Title"/><p:cNvSpPr/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Layout</a:t></a:r></a:p></p:txBody></p:sp>{}</p:spTree></p:cSld></p:sld>"#,
+            shapes.concat()
+        )
+    }
 
-        let (shapes, warnings) = parse_slide(slide_xml);
-        assert!(warnings.is_empty());
+    #[test]
+    fn test_pptx_parse_slide_records_bounds() {
+        let xml = slide_with_shapes(&[positioned_shape("Body", 100, 200, 300, 400)]);
+        let (shapes, shape_bounds, _) = parse_slide(&xml);
         assert_eq!(shapes.len(), 2);
-        match &shapes[0] {
-            ShapeContent::Body(text) => assert_eq!(text, "First shape"),
-            other => panic!("expected Body, got {:?}", other),
-        }
-        match &shapes[1] {
-            ShapeContent::Body(text) => assert_eq!(text, "Second shape"),
-            other => panic!("expected Body, got {:?}", other),
-        }
+        // The title placeholder inherits its position from the layout
+        assert_eq!(shape_bounds, vec![None, bounds(100.0, 200.0, 300.0, 400.0)]);
     }
 
     #[test]
-    fn test_pptx_group_shape_nested() {
-        // <p:grpSp> inside <p:grpSp> with a <p:sp> child
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Outer"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:grpSp><p:nvGrpSpPr><p:cNvPr id="11" name="Inner"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:sp><p:nvSpPr><p:cNvPr id="12" name="Deep"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:txBody><a:p><a:r><a:t>Nested group text</a:t></a:r></a:p></p:txBody></p:sp></p:grpSp></p:grpSp></p:spTree></p:cSld></p:sld>"#;
-
-        let (shapes, warnings) = parse_slide(slide_xml);
-        assert!(warnings.is_empty());
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Body(text) => assert_eq!(text, "Nested group text"),
-            other => panic!("expected Body, got {:?}", other),
-        }
+    fn test_pptx_parse_slide_applies_group_transform() {
+        let group = format!(
+            r#"<p:grpSp><p:nvGrpSpPr><p:cNvPr id="5" name="Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="1000" y="2000"/><a:ext cx="200" cy="200"/><a:chOff x="0" y="0"/><a:chExt cx="100" cy="100"/></a:xfrm></p:grpSpPr>{}</p:grpSp>"#,
+            positioned_shape("Inner", 10, 20, 50, 50)
+        );
+        let (_, shape_bounds, _) = parse_slide(&slide_with_shapes(&[group]));
+        assert_eq!(shape_bounds[1], bounds(1020.0, 2040.0, 100.0, 100.0));
     }
 
     #[test]
-    fn test_pptx_group_shape_with_table() {
-        // <p:graphicFrame> (table) inside a <p:grpSp>
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="11" name="Table"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><a:graphic><a:graphicData><a:tbl><a:tr><a:tc><a:txBody><a:p><a:r><a:t>H1</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>H2</a:t></a:r></a:p></a:txBody></a:tc></a:tr><a:tr><a:tc><a:txBody><a:p><a:r><a:t>A</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>B</a:t></a:r></a:p></a:txBody></a:tc></a:tr></a:tbl></a:graphicData></a:graphic></p:graphicFrame></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+    fn test_pptx_reading_order_columns() {
+        let inch = 914_400.0;
+        let shape_bounds = vec![
+            // Footer added first in XML
+            bounds(0.0, 7.0 * inch, 10.0 * inch, 0.5 * inch),
+            // Right column
+            bounds(5.0 * inch, 1.0 * inch, 4.0 * inch, 2.0 * inch),
+            bounds(5.0 * inch, 3.5 * inch, 4.0 * inch, 2.5 * inch),
+            // Left column, one tall shape spanning both right-column shapes
+            bounds(0.5 * inch, 1.0 * inch, 4.0 * inch, 5.0 * inch),
+            // Unpositioned placeholder
+            None,
+        ];
+        assert_eq!(reading_order(&shape_bounds), vec![4, 3, 1, 2, 0]);
+    }
 
-        let (shapes, warnings) = parse_slide(slide_xml);
-        assert!(warnings.is_empty());
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Table { headers, rows } => {
-                assert_eq!(headers, &["H1", "H2"]);
-                assert_eq!(rows.len(), 1);
-                assert_eq!(rows[0], &["A", "B"]);
-            }
-            other => panic!("expected Table, got {:?}", other),
-        }
+    #[test]
+    fn test_pptx_reading_order_row_left_to_right() {
+        let shape_bounds = vec![
+            bounds(6_000_000.0, 0.0, 1_000_000.0, 1_000_000.0),
+            bounds(0.0, 0.0, 1_000_000.0, 1_000_000.0),
+            bounds(3_000_000.0, 0.0, 1_000_000.0, 1_000_000.0),
+        ];
+        assert_eq!(reading_order(&shape_bounds), vec![1, 2, 0]);
     }
 
     #[test]
-    fn test_pptx_group_shape_with_image() {
-        // <p:pic> inside a <p:grpSp>
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:pic><p:nvPicPr><p:cNvPr id="11" descr="Alt text" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rIdImg1"/></p:blipFill></p:pic></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+    fn test_pptx_reading_order_overlapping_falls_back_to_top_left() {
+        let shape_bounds = vec![
+            bounds(0.0, 500_000.0, 1_000_000.0, 1_000_000.0),
+            bounds(200_000.0, 0.0, 1_000_000.0, 1_000_000.0),
+        ];
+        assert_eq!(reading_order(&shape_bounds), vec![1, 0]);
+    }
 
-        let (shapes, warnings) = parse_slide(slide_xml);
-        assert!(warnings.is_empty());
-        assert_eq!(shapes.len(), 1);
-        match &shapes[0] {
-            ShapeContent::Image { rel_id, alt_text } => {
-                assert_eq!(rel_id, "rIdImg1");
-                assert_eq!(alt_text.as_deref(), Some("Alt text"));
-            }
-            other => panic!("expected Image, got {:?}", other),
-        }
+    fn build_reading_order_pptx() -> Vec<u8> {
+        let slide = slide_with_shapes(&[
+            positioned_shape("Bottom note", 457_200, 6_000_000, 8_000_000, 400_000),
+            positioned_shape("Top intro", 457_200, 1_200_000, 8_000_000, 800_000),
+        ]);
+        build_raw_pptx(&[(&slide, None)], "", &[])
     }
 
     #[test]
-    fn test_pptx_group_shape_empty() {
-        // Empty <p:grpSp> produces no shapes
-        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><p:cSld><p:spTree><p:grpSp><p:nvGrpSpPr><p:cNvPr id="10" name="Empty Group"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/></p:grpSp></p:spTree></p:cSld></p:sld>"#;
+    fn test_pptx_xml_order_is_default() {
+        let result = PptxConverter
+            .convert(&build_reading_order_pptx(), &ConversionOptions::default())
+            .unwrap();
+        let bottom = result.markdown.find("Bottom note").unwrap();
+        let top = result.markdown.find("Top intro").unwrap();
+        assert!(bottom < top);
+    }
 
-        let (shapes, warnings) = parse_slide(slide_xml);
-        assert!(warnings.is_empty());
-        assert!(shapes.is_empty());
+    #[test]
+    fn test_pptx_reading_order_option_sorts_shapes() {
+        let options = ConversionOptions {
+            pptx: PptxOptions {
+                reading_order: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = PptxConverter
+            .convert(&build_reading_order_pptx(), &options)
+            .unwrap();
+        assert!(result.markdown.starts_with("## Slide 1: Layout"));
+        let bottom = result.markdown.find("Bottom note").unwrap();
+        let top = result.markdown.find("Top intro").unwrap();
+        assert!(top < bottom, "markdown was: {}", result.markdown);
     }
 }
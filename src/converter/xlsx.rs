@@ -1,27 +1,35 @@
-//! XLSX/XLS spreadsheet to Markdown converter.
+//! XLSX/XLS/XLSB/ODS spreadsheet to Markdown converter.
 //!
-//! Uses the `calamine` crate to read both modern `.xlsx` (OOXML) and legacy
-//! `.xls` (BIFF) formats. Each sheet becomes a `## SheetName` section with
-//! a Markdown table. Handles dates, times, formulas, error cells, and
-//! embedded images.
-
-use std::collections::HashMap;
+//! Uses the `calamine` crate to read modern `.xlsx` (OOXML), legacy `.xls`
+//! (BIFF), binary `.xlsb`, and OpenDocument `.ods` workbooks. Features that
+//! read the OOXML parts directly (number formats, tables, comments, charts,
+//! pivot tables, hidden rows) apply to `.xlsx` only. Each sheet becomes a
+//! `## SheetName` section with a Markdown table. Handles dates, times, formulas,
+//! error cells, merged cell ranges, hidden sheets/rows/columns, number formats
+//! from `styles.xml`, optional formula annotations, chart series, pivot tables,
+//! and embedded images.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::Cursor;
 
-use calamine::{Data, Reader, open_workbook_auto_from_rs};
+use calamine::{Data, Dimensions, Reader, SheetVisible, Sheets, open_workbook_auto_from_rs};
 use chrono::{Datelike, Timelike};
 use quick_xml::events::Event;
+use quick_xml::name::{Namespace, ResolveResult};
 use zip::ZipArchive;
 
 use crate::converter::ooxml_utils::{
-    ImageInfo, PendingImageResolution, derive_rels_path, parse_relationships,
-    resolve_image_placeholders, resolve_relative_path,
+    ImageInfo, PendingImageResolution, Relationship, derive_rels_path, parse_relationships,
+    resolve_image_placeholders, resolve_relative_path, resolve_relative_to_file,
 };
 use crate::converter::{
-    ConversionOptions, ConversionResult, ConversionWarning, Converter, WarningCode,
+    CellValues, ConversionOptions, ConversionResult, ConversionWarning, Converter, Formulas,
+    HiddenContent, MergedCells, RowSampling, WarningCode,
 };
 use crate::error::ConvertError;
-use crate::markdown::{build_table, build_table_plain, format_heading};
+use crate::markdown::{
+    build_table, build_table_plain, format_heading, format_list_item, format_list_item_plain,
+};
 use crate::zip_utils::{read_zip_bytes, read_zip_text};
 
 /// Converts XLSX and XLS spreadsheet files to Markdown.
@@ -171,6 +179,81 @@ fn col_letter(col: usize) -> String {
     result
 }
 
+/// A formula and the absolute 0-based `(row, col)` of its cell.
+type CellFormula = ((u32, u32), String);
+
+/// Read a sheet's formulas as `"=FORMULA"` strings with absolute positions.
+fn read_formulas<RS: std::io::Read + std::io::Seek>(
+    workbook: &mut Sheets<RS>,
+    sheet_name: &str,
+) -> Result<Vec<CellFormula>, calamine::Error> {
+    let range = workbook.worksheet_formula(sheet_name)?;
+    let start = range.start().unwrap_or((0, 0));
+    Ok(range
+        .used_cells()
+        .filter(|(_, _, formula)| !formula.is_empty())
+        .map(|(r, c, formula)| {
+            let formula = formula.strip_prefix('=').unwrap_or(formula);
+            (
+                (start.0 + r as u32, start.1 + c as u32),
+                format!("={formula}"),
+            )
+        })
+        .collect())
+}
+
+/// Append `(=FORMULA)` to the cached value of each formula cell in a sheet grid.
+///
+/// Runs after merged regions are filled; only the anchor cell of a merged
+/// region is annotated, never the cells that repeat its value.
+fn annotate_formulas(
+    grid: &mut [Vec<String>],
+    origin: (u32, u32),
+    formulas: &[CellFormula],
+    merges: &[Dimensions],
+) {
+    for ((row, col), formula) in formulas {
+        let spanned = merges
+            .iter()
+            .any(|merge| merge.contains(*row, *col) && merge.start != (*row, *col));
+        if spanned {
+            continue;
+        }
+        let cell = row
+            .checked_sub(origin.0)
+            .zip(col.checked_sub(origin.1))
+            .and_then(|(r, c)| grid.get_mut(r as usize)?.get_mut(c as usize));
+        if let Some(cell) = cell {
+            *cell = if cell.is_empty() {
+                formula.clone()
+            } else {
+                format!("{cell} ({formula})")
+            };
+        }
+    }
+}
+
+/// Wrap text in a Markdown code span, using a backtick fence longer than any
+/// backtick run inside it.
+fn code_span(text: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        format!("{fence} {text} {fence}")
+    } else {
+        format!("{fence}{text}{fence}")
+    }
+}
+
+/// Render a float without a trailing `.0` for whole numbers.
+fn format_float(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        value.to_string()
+    }
+}
+
 /// Format a calamine cell value as a string for Markdown output.
 ///
 /// Whole-number floats display as integers (e.g. `3.0` → `"3"`).
@@ -178,20 +261,11 @@ fn col_letter(col: usize) -> String {
 /// Empty cells produce an empty string.
 /// Error cells display the error text (e.g. `#DIV/0!`) and emit a warning.
 /// DateTime cells are formatted as `YYYY-MM-DD` or `YYYY-MM-DD HH:MM:SS`.
-///
-/// Note: calamine returns computed values for formula cells, not the formula text.
-/// This means formulas like `=SUM(A1:A3)` appear as their computed numeric result.
 fn format_cell(cell: &Data, location: &str, warnings: &mut Vec<ConversionWarning>) -> String {
     match cell {
         Data::Empty => String::new(),
         Data::String(s) => s.clone(),
-        Data::Float(f) => {
-            if f.is_finite() && f.fract() == 0.0 {
-                format!("{:.0}", f)
-            } else {
-                f.to_string()
-            }
-        }
+        Data::Float(f) => format_float(*f),
         Data::Int(i) => i.to_string(),
         Data::Bool(b) => {
             if *b {
@@ -236,225 +310,4000 @@ fn format_cell(cell: &Data, location: &str, warnings: &mut Vec<ConversionWarning
     }
 }
 
-// ---- Internal conversion (parse + image extraction, no resolution) ----
+// ---- Workbook structure ----
+
+/// Namespaces of `r:id` attributes, in transitional and strict OOXML.
+const RELATIONSHIPS_NAMESPACES: [&[u8]; 2] = [
+    b"http://schemas.openxmlformats.org/officeDocument/2006/relationships",
+    b"http://purl.oclc.org/ooxml/officeDocument/relationships",
+];
+
+/// Map sheet names to their worksheet part paths (e.g. `xl/worksheets/sheet1.xml`)
+/// using `xl/workbook.xml` and its relationships.
+fn resolve_sheet_paths(archive: &mut ZipArchive<Cursor<&[u8]>>) -> HashMap<String, String> {
+    let mut paths = HashMap::new();
+    let (Ok(Some(workbook_xml)), Ok(Some(rels_xml))) = (
+        read_zip_text(archive, "xl/workbook.xml"),
+        read_zip_text(archive, "xl/_rels/workbook.xml.rels"),
+    ) else {
+        return paths;
+    };
+    let rels = parse_relationships(&rels_xml);
 
-impl XlsxConverter {
-    /// Parse the workbook and extract images without resolving placeholders.
-    ///
-    /// Returns the conversion result (with unresolved placeholders in markdown)
-    /// and pending image data for later resolution (sync or async).
-    pub(crate) fn convert_inner(
-        &self,
-        data: &[u8],
-        options: &ConversionOptions,
-    ) -> Result<(ConversionResult, PendingImageResolution), ConvertError> {
-        // Pre-scan ZIP budget before passing to calamine
-        if let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(data)) {
-            crate::zip_utils::validate_zip_budget(
-                &mut archive,
-                options.max_uncompressed_zip_bytes,
-            )?;
+    // The relationship ID is `id` in the relationships namespace, whatever its prefix
+    let mut reader = quick_xml::NsReader::from_str(&workbook_xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.local_name().as_ref() == b"sheet" =>
+            {
+                let mut name = None;
+                let mut rel_id = None;
+                for attr in e.attributes().flatten() {
+                    let value = attr.unescape_value().unwrap_or_default().to_string();
+                    match reader.resolve_attribute(attr.key) {
+                        (ResolveResult::Unbound, local) if local.as_ref() == b"name" => {
+                            name = Some(value)
+                        }
+                        (ResolveResult::Bound(Namespace(ns)), local)
+                            if local.as_ref() == b"id"
+                                && RELATIONSHIPS_NAMESPACES.contains(&ns) =>
+                        {
+                            rel_id = Some(value)
+                        }
+                        _ => {}
+                    }
+                }
+                if let (Some(name), Some(rel)) = (name, rel_id.and_then(|id| rels.get(&id))) {
+                    paths.insert(
+                        name,
+                        resolve_relative_to_file("xl/workbook.xml", &rel.target),
+                    );
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
         }
+    }
 
-        let cursor = Cursor::new(data);
-        let mut workbook = open_workbook_auto_from_rs(cursor)?;
-
-        let sheet_names = workbook.sheet_names().to_owned();
-        let mut sections = Vec::new();
-        let mut plain_sections = Vec::new();
-        let mut warnings = Vec::new();
-
-        // Track which sheet index each section corresponds to (for image attachment)
-        let mut section_sheet_indices: Vec<usize> = Vec::new();
-
-        for (sheet_idx, name) in sheet_names.iter().enumerate() {
-            let range = match workbook.worksheet_range(name) {
-                Ok(r) => r,
-                Err(e) => {
-                    warnings.push(ConversionWarning {
-                        code: WarningCode::SkippedElement,
-                        message: format!("failed to read sheet '{name}': {e}"),
-                        location: Some(name.clone()),
-                    });
-                    continue;
-                }
-            };
+    paths
+}
 
-            if range.is_empty() {
-                continue;
-            }
+/// Parse an A1-style cell reference (`"B7"`, `"$B$7"`) into 0-based `(row, col)`.
+fn parse_cell_ref(cell_ref: &str) -> Option<(u32, u32)> {
+    let cell_ref = cell_ref.replace('$', "");
+    let split = cell_ref.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell_ref.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let col = letters.chars().try_fold(0u32, |acc, c| {
+        acc.checked_mul(26)?
+            .checked_add(c.to_ascii_uppercase() as u32 - 'A' as u32 + 1)
+    })?;
+    let row: u32 = digits.parse().ok()?;
+    (row > 0).then(|| (row - 1, col - 1))
+}
 
-            let mut rows_iter = range.rows();
-            let header_row = match rows_iter.next() {
-                Some(row) => row,
-                None => continue,
-            };
+// ---- Number formats ----
 
-            let headers: Vec<String> = header_row
-                .iter()
-                .enumerate()
-                .map(|(ci, cell)| {
-                    let loc = format!("{}!{}1", name, col_letter(ci));
-                    format_cell(cell, &loc, &mut warnings)
-                })
-                .collect();
-            let header_refs: Vec<&str> = headers.iter().map(|s| s.as_str()).collect();
+/// Number formats of an XLSX workbook, read from `xl/styles.xml`.
+#[derive(Debug, Default)]
+struct NumberFormats {
+    /// Custom format codes (`<numFmt>`) by `numFmtId`.
+    custom: HashMap<u32, String>,
+    /// `numFmtId` of each cell style; a cell's `s` attribute indexes this list.
+    cell_xfs: Vec<u32>,
+}
 
-            let mut data_rows: Vec<Vec<String>> = Vec::new();
-            for (ri, row) in rows_iter.enumerate() {
-                let cells: Vec<String> = row
-                    .iter()
-                    .enumerate()
-                    .map(|(ci, cell)| {
-                        let loc = format!("{}!{}{}", name, col_letter(ci), ri + 2);
-                        format_cell(cell, &loc, &mut warnings)
-                    })
-                    .collect();
-                data_rows.push(cells);
+impl NumberFormats {
+    fn parse(xml: &str) -> Self {
+        let mut formats = NumberFormats::default();
+        let mut reader = quick_xml::Reader::from_str(xml);
+        let mut in_cell_xfs = false;
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                    match e.local_name().as_ref() {
+                        b"numFmt" => {
+                            let mut id = None;
+                            let mut code = None;
+                            for attr in e.attributes().flatten() {
+                                let value = attr.unescape_value().unwrap_or_default().to_string();
+                                match attr.key.as_ref() {
+                                    b"numFmtId" => id = value.parse::<u32>().ok(),
+                                    b"formatCode" => code = Some(value),
+                                    _ => {}
+                                }
+                            }
+                            if let (Some(id), Some(code)) = (id, code) {
+                                formats.custom.insert(id, code);
+                            }
+                        }
+                        b"cellXfs" => in_cell_xfs = true,
+                        b"xf" if in_cell_xfs => {
+                            let id = e
+                                .attributes()
+                                .flatten()
+                                .find(|a| a.key.as_ref() == b"numFmtId")
+                                .and_then(|a| std::str::from_utf8(&a.value).ok()?.parse().ok())
+                                .unwrap_or(0);
+                            formats.cell_xfs.push(id);
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::End(ref e)) if e.local_name().as_ref() == b"cellXfs" => {
+                    in_cell_xfs = false;
+                }
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
             }
+        }
 
-            let row_refs: Vec<Vec<&str>> = data_rows
-                .iter()
-                .map(|row| row.iter().map(|s| s.as_str()).collect())
-                .collect();
+        formats
+    }
 
-            let heading = format_heading(2, name);
-            let table = build_table(&header_refs, &row_refs);
-            sections.push(format!("{heading}{table}"));
+    /// Format code for a cell style index. `None` means the default rendering
+    /// applies (General, text, fractions, or a locale-dependent built-in date).
+    fn code_for_style(&self, style: u32) -> Option<&str> {
+        let id = *self.cell_xfs.get(style as usize)?;
+        let code = match self.custom.get(&id) {
+            Some(code) => code.as_str(),
+            None => builtin_format_code(id)?,
+        };
+        (!code.eq_ignore_ascii_case("general") && code != "@").then_some(code)
+    }
+}
 
-            let plain_table = build_table_plain(&header_refs, &row_refs);
-            plain_sections.push(format!("{name}\n{plain_table}"));
+/// Format codes of the locale-independent built-in number formats (ECMA-376 18.8.30).
+fn builtin_format_code(id: u32) -> Option<&'static str> {
+    Some(match id {
+        1 => "0",
+        2 => "0.00",
+        3 => "#,##0",
+        4 => "#,##0.00",
+        5 => "$#,##0_);($#,##0)",
+        6 => "$#,##0_);[Red]($#,##0)",
+        7 => "$#,##0.00_);($#,##0.00)",
+        8 => "$#,##0.00_);[Red]($#,##0.00)",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        37 => "#,##0_);(#,##0)",
+        38 => "#,##0_);[Red](#,##0)",
+        39 => "#,##0.00_);(#,##0.00)",
+        40 => "#,##0.00_);[Red](#,##0.00)",
+        48 => "##0.0E+0",
+        _ => return None,
+    })
+}
 
-            section_sheet_indices.push(sheet_idx);
+/// Collect the style index (`s` attribute) of every styled cell in worksheet XML.
+fn parse_cell_styles(xml: &str) -> HashMap<(u32, u32), u32> {
+    let mut styles = HashMap::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.local_name().as_ref() == b"c" =>
+            {
+                let mut position = None;
+                let mut style = None;
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"r" => {
+                            position = std::str::from_utf8(&attr.value)
+                                .ok()
+                                .and_then(parse_cell_ref)
+                        }
+                        b"s" => {
+                            style = std::str::from_utf8(&attr.value)
+                                .ok()
+                                .and_then(|v| v.parse::<u32>().ok())
+                        }
+                        _ => {}
+                    }
+                }
+                if let (Some(position), Some(style)) = (position, style) {
+                    styles.insert(position, style);
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
         }
+    }
+    styles
+}
 
-        // Extract embedded images if requested or if describer needs them
-        let need_image_bytes = options.extract_images || options.image_describer.is_some();
-        let mut images: Vec<(String, Vec<u8>)> = Vec::new();
-        let mut image_bytes_map: HashMap<String, Vec<u8>> = HashMap::new();
-        let mut image_infos: Vec<ImageInfo> = Vec::new();
-        let mut image_counter: usize = 0;
-
-        if need_image_bytes {
-            // Open a fresh ZipArchive (calamine consumed the original cursor)
-            let mut archive = ZipArchive::new(Cursor::new(data))?;
-            let mut total_image_bytes: usize = 0;
+/// Split a format code into its `;`-separated sections, honouring quotes and escapes.
+fn split_format_sections(code: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut chars = code.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\\' if !in_quotes => {
+                chars.next();
+            }
+            ';' if !in_quotes => {
+                sections.push(&code[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    sections.push(&code[start..]);
+    sections
+}
 
-            for (section_idx, &sheet_idx) in section_sheet_indices.iter().enumerate() {
-                let sheet_images = extract_sheet_images(&mut archive, sheet_idx);
+/// A lexical element of a format code section.
+#[derive(Debug, Clone, PartialEq)]
+enum FormatToken {
+    Literal(String),
+    /// A bracketed code other than a currency/locale tag, e.g. `[Red]`, `[h]`, `[>100]`.
+    Bracket(String),
+    /// Any other character with format meaning (`0`, `#`, `y`, `.`, `%`, ...).
+    Code(char),
+}
 
-                let mut image_lines = Vec::new();
-                let mut plain_image_lines = Vec::new();
-                for (filename, img_data) in sheet_images {
-                    total_image_bytes += img_data.len();
-                    if total_image_bytes <= options.max_total_image_bytes {
-                        let placeholder = format!("__img_{n}__", n = image_counter);
-                        let bytes_key = placeholder.clone();
-                        image_counter += 1;
-                        image_infos.push(ImageInfo {
-                            placeholder: placeholder.clone(),
-                            original_alt: String::new(),
-                            filename: filename.clone(),
-                            bytes_key: bytes_key.clone(),
-                        });
-                        image_lines.push(format!("![{placeholder}]({filename})"));
-                        plain_image_lines.push(placeholder);
-                        if options.extract_images {
-                            images.push((filename.clone(), img_data.clone()));
-                        }
-                        image_bytes_map.insert(bytes_key, img_data);
-                    } else {
-                        warnings.push(ConversionWarning {
-                            code: WarningCode::ResourceLimitReached,
-                            message: format!(
-                                "total image bytes exceeded limit ({})",
-                                options.max_total_image_bytes
-                            ),
-                            location: Some(filename),
-                        });
+fn tokenize_format(section: &str) -> Vec<FormatToken> {
+    let mut tokens = Vec::new();
+    let mut chars = section.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let text: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                tokens.push(FormatToken::Literal(text));
+            }
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    tokens.push(FormatToken::Literal(next.to_string()));
+                }
+            }
+            // `_x` pads with the width of `x`, `*x` repeats `x` to fill the cell
+            '_' | '*' => {
+                chars.next();
+            }
+            '[' => {
+                let inner: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if let Some(currency) = inner.strip_prefix('$') {
+                    // [$€-407] → "€"; [$-409] is a locale tag only
+                    let symbol = currency.split('-').next().unwrap_or("");
+                    if !symbol.is_empty() {
+                        tokens.push(FormatToken::Literal(symbol.to_string()));
                     }
+                } else {
+                    tokens.push(FormatToken::Bracket(inner.to_ascii_lowercase()));
                 }
-
-                if !image_lines.is_empty() {
-                    sections[section_idx].push_str(&format!("\n{}", image_lines.join("\n")));
-                    plain_sections[section_idx]
-                        .push_str(&format!("\n{}", plain_image_lines.join("\n")));
+            }
+            '0' | '#' | '?' | '.' | ',' | '%' | '/' | '@' => tokens.push(FormatToken::Code(c)),
+            'E' | 'e' if matches!(chars.peek(), Some('+') | Some('-')) => {
+                tokens.push(FormatToken::Code('E'));
+                if let Some(sign) = chars.next() {
+                    tokens.push(FormatToken::Code(sign));
+                }
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                tokens.push(FormatToken::Code(c.to_ascii_lowercase()))
+            }
+            'A' | 'a' => {
+                let rest: String = chars.clone().take(4).collect();
+                if rest.eq_ignore_ascii_case("m/pm") {
+                    chars.nth(3);
+                    tokens.push(FormatToken::Bracket("am/pm".to_string()));
+                } else if rest.len() >= 2 && rest[..2].eq_ignore_ascii_case("/p") {
+                    chars.nth(1);
+                    tokens.push(FormatToken::Bracket("a/p".to_string()));
+                } else {
+                    tokens.push(FormatToken::Literal(c.to_string()));
                 }
             }
+            _ => tokens.push(FormatToken::Literal(c.to_string())),
         }
+    }
+    tokens
+}
 
-        let markdown = sections.join("\n");
-        let plain_text = plain_sections.join("\n");
+/// Whether a tokenized section formats dates/times rather than numbers.
+fn is_date_section(tokens: &[FormatToken]) -> bool {
+    tokens.iter().any(|t| match t {
+        FormatToken::Code(c) => matches!(c, 'y' | 'm' | 'd' | 'h' | 's'),
+        FormatToken::Bracket(b) => matches!(b.as_str(), "h" | "hh" | "m" | "mm" | "s" | "ss"),
+        FormatToken::Literal(_) => false,
+    })
+}
 
-        let result = ConversionResult {
-            markdown,
-            plain_text,
-            images,
-            warnings,
-            ..Default::default()
-        };
+/// Render a numeric or date cell using an Excel number format code.
+///
+/// Returns `None` when the value is not numeric or the format is not supported
+/// (fractions, text sections), so the caller falls back to the raw value.
+fn apply_number_format(cell: &Data, code: &str) -> Option<String> {
+    let serial = match cell {
+        Data::Float(f) => *f,
+        Data::Int(i) => *i as f64,
+        Data::DateTime(dt) => dt.as_f64(),
+        _ => return None,
+    };
+    if !serial.is_finite() {
+        return None;
+    }
 
-        let pending = PendingImageResolution {
-            infos: image_infos,
-            bytes: image_bytes_map,
-        };
+    let sections = split_format_sections(code);
+    let (section, value, negative) = if serial < 0.0 && sections.len() >= 2 {
+        (sections[1], -serial, false)
+    } else if serial == 0.0 && sections.len() >= 3 {
+        (sections[2], serial, false)
+    } else {
+        (sections[0], serial.abs(), serial < 0.0)
+    };
 
-        Ok((result, pending))
+    let tokens = tokenize_format(section);
+    if is_date_section(&tokens) {
+        let datetime = match cell {
+            Data::DateTime(dt) => dt.as_datetime(),
+            _ => excel_serial_to_datetime(serial),
+        }?;
+        return Some(format_date(&tokens, datetime, serial));
     }
+
+    let number = format_number(&tokens, value)?;
+    Some(if negative {
+        format!("-{number}")
+    } else {
+        number
+    })
 }
 
-// ---- Converter trait impl ----
+/// Convert an Excel 1900-system serial date to a date-time.
+fn excel_serial_to_datetime(serial: f64) -> Option<chrono::NaiveDateTime> {
+    let epoch = chrono::NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let millis = (serial * 86_400_000.0).round() as i64;
+    epoch.checked_add_signed(chrono::Duration::milliseconds(millis))
+}
 
-impl Converter for XlsxConverter {
-    fn supported_extensions(&self) -> &[&str] {
-        &["xlsx", "xls"]
+/// Render a number section: digit placeholders (`0`, `#`, `?`), decimal point,
+/// thousands separator and scaling commas, percent, and scientific notation.
+fn format_number(tokens: &[FormatToken], value: f64) -> Option<String> {
+    use FormatToken::{Code, Literal};
+
+    if tokens.iter().any(|t| matches!(t, Code('/') | Code('@'))) {
+        return None;
+    }
+    let is_digit = |t: &FormatToken| matches!(t, Code('0') | Code('#') | Code('?'));
+    let decimal_pos = tokens.iter().position(|t| *t == Code('.'));
+    let exponent_pos = tokens.iter().position(|t| *t == Code('E'));
+    let mantissa_end = exponent_pos.unwrap_or(tokens.len());
+    let int_end = decimal_pos.unwrap_or(mantissa_end).min(mantissa_end);
+
+    let int_placeholders: Vec<char> = tokens[..int_end]
+        .iter()
+        .filter_map(|t| match t {
+            Code(c @ ('0' | '#' | '?')) => Some(*c),
+            _ => None,
+        })
+        .collect();
+    let frac_placeholders: Vec<char> = tokens[int_end..mantissa_end]
+        .iter()
+        .filter_map(|t| match t {
+            Code(c @ ('0' | '#' | '?')) => Some(*c),
+            _ => None,
+        })
+        .collect();
+    // A section without placeholders (e.g. `"-"` for zero) prints only its text
+    if int_placeholders.is_empty() && frac_placeholders.is_empty() {
+        return Some(
+            tokens
+                .iter()
+                .filter_map(|t| match t {
+                    Literal(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect(),
+        );
     }
 
-    fn convert(
-        &self,
-        data: &[u8],
-        options: &ConversionOptions,
-    ) -> Result<ConversionResult, ConvertError> {
-        let (mut result, pending) = self.convert_inner(data, options)?;
-        resolve_image_placeholders(
-            &mut result.markdown,
-            &mut result.plain_text,
-            &pending.infos,
-            &pending.bytes,
-            options.image_describer.as_deref(),
-            &mut result.warnings,
+    // A comma between digit placeholders groups thousands; commas right after
+    // the last placeholder scale the value down by 1000 each
+    let last_digit = tokens[..mantissa_end]
+        .iter()
+        .rposition(is_digit)
+        .unwrap_or(0);
+    let mut grouping = false;
+    let mut scale_commas = 0;
+    for (i, token) in tokens[..mantissa_end].iter().enumerate() {
+        if *token == Code(',') {
+            if i > last_digit {
+                scale_commas += 1;
+            } else if tokens[..i].iter().any(is_digit) {
+                grouping = true;
+            }
+        }
+    }
+    let percents = tokens.iter().filter(|t| **t == Code('%')).count();
+    let mut value = value * 100f64.powi(percents as i32) / 1000f64.powi(scale_commas);
+
+    // Scientific notation: pick the exponent so the mantissa fills the integer placeholders
+    let mut exponent_text = String::new();
+    if let Some(pos) = exponent_pos {
+        let show_plus = tokens.get(pos + 1) == Some(&Code('+'));
+        let exponent_digits = tokens[pos + 1..]
+            .iter()
+            .filter(|t| **t == Code('0'))
+            .count();
+        let step = int_placeholders.len().max(1) as i32;
+        let mut exponent = if value == 0.0 {
+            0
+        } else {
+            value.log10().floor() as i32
+        };
+        if step > 1 {
+            exponent -= exponent.rem_euclid(step);
+        }
+        value /= 10f64.powi(exponent);
+        // Rounding may carry into an extra digit (9.99 → 10.0)
+        let rounded: f64 = round_half_away(value, frac_placeholders.len())
+            .parse()
+            .unwrap_or(value);
+        if rounded >= 10f64.powi(step) {
+            value /= 10f64.powi(step);
+            exponent += step;
+        }
+        let sign = if exponent < 0 {
+            "-"
+        } else if show_plus {
+            "+"
+        } else {
+            ""
+        };
+        exponent_text = format!(
+            "E{sign}{:0width$}",
+            exponent.unsigned_abs(),
+            width = exponent_digits
         );
-        Ok(result)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    let rounded = round_half_away(value, frac_placeholders.len());
+    let (int_digits, frac_digits) = rounded.split_once('.').unwrap_or((&rounded, ""));
 
-    // -- Helper: build minimal XLSX bytes from sheet definitions --
+    // Integer part: pad to the number of `0` placeholders, drop a lone zero for `#`
+    let min_int = int_placeholders.iter().filter(|c| **c == '0').count();
+    let mut int_text = int_digits.trim_start_matches('0').to_string();
+    if int_text.len() < min_int {
+        int_text = format!("{int_text:0>min_int$}");
+    }
+    if grouping {
+        int_text = group_thousands(&int_text);
+    }
 
-    /// Cell value for test XLSX generation.
-    enum TestCell {
-        Str(&'static str),
-        Num(f64),
-        Bool(bool),
-        Empty,
+    // Fraction part: optional placeholders (`#`, `?`) drop trailing zeros
+    let min_frac = frac_placeholders
+        .iter()
+        .rposition(|c| *c == '0')
+        .map_or(0, |p| p + 1);
+    let mut frac_text = frac_digits.to_string();
+    while frac_text.len() > min_frac && frac_text.ends_with('0') {
+        frac_text.pop();
     }
 
-    /// Convert a 0-based column index to an Excel column letter (A-Z) for test XML.
-    fn test_col_letter(col: usize) -> char {
-        (b'A' + col as u8) as char
+    let mut out = String::new();
+    let mut wrote_int = false;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Literal(text) => out.push_str(text),
+            Code('%') => out.push('%'),
+            Code('.') if Some(i) == decimal_pos => {
+                if !wrote_int {
+                    out.push_str(&int_text);
+                    wrote_int = true;
+                }
+                out.push('.');
+                out.push_str(&frac_text);
+            }
+            Code('E') => out.push_str(&exponent_text),
+            Code('0' | '#' | '?') if i < int_end && !wrote_int => {
+                out.push_str(&int_text);
+                wrote_int = true;
+            }
+            // Remaining placeholders, commas, and exponent signs are consumed above
+            Code(_) => {}
+            FormatToken::Bracket(_) => {}
+        }
     }
+    Some(out)
+}
 
-    /// Build a minimal XLSX file in memory from the given sheet definitions.
-    fn build_test_xlsx(sheets: &[(&str, &[&[TestCell]])]) -> Vec<u8> {
-        use std::io::Write;
-        use zip::ZipWriter;
-        use zip::write::SimpleFileOptions;
+/// Format a non-negative value with `digits` decimals, rounding halves away from
+/// zero as Excel does (`format!` rounds half to even). The scaled value is first
+/// reduced to Excel's 15 significant digits so `1.005` rounds to `1.01`.
+fn round_half_away(value: f64, digits: usize) -> String {
+    let scale = 10f64.powi(digits as i32);
+    let scaled = value * scale;
+    let scaled: f64 = format!("{scaled:.14e}").parse().unwrap_or(scaled);
+    format!("{:.*}", digits, scaled.round() / scale)
+}
 
-        let buf = Vec::new();
-        let mut zip = ZipWriter::new(Cursor::new(buf));
+/// Insert `,` thousands separators into a string of digits.
+fn group_thousands(digits: &str) -> String {
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Render a date/time section (`yyyy-mm-dd`, `d mmm yyyy`, `h:mm AM/PM`, `[h]:mm`, ...).
+///
+/// `serial` is the raw Excel value, used for elapsed-time codes like `[h]`.
+fn format_date(tokens: &[FormatToken], dt: chrono::NaiveDateTime, serial: f64) -> String {
+    use FormatToken::{Bracket, Code, Literal};
+
+    // Group runs of the same code letter: "yyyy" → ('y', 4)
+    let mut parts: Vec<(char, usize)> = Vec::new();
+    let mut literals: HashMap<usize, String> = HashMap::new();
+    for token in tokens {
+        match token {
+            Code(c) if matches!(c, 'y' | 'm' | 'd' | 'h' | 's' | '0') => match parts.last_mut() {
+                Some((last, count)) if last == c => *count += 1,
+                _ => parts.push((*c, 1)),
+            },
+            Code('.') => parts.push(('.', 1)),
+            Bracket(b) if b == "am/pm" => parts.push(('A', 5)),
+            Bracket(b) if b == "a/p" => parts.push(('A', 3)),
+            Bracket(b) if matches!(b.chars().next(), Some('h' | 'm' | 's')) => {
+                parts.push(('[', b.len()));
+                literals.insert(parts.len() - 1, b.clone());
+            }
+            Bracket(_) => {}
+            Literal(text) => {
+                parts.push(('L', 0));
+                literals.insert(parts.len() - 1, text.clone());
+            }
+            Code(c) => {
+                parts.push(('L', 0));
+                literals.insert(parts.len() - 1, c.to_string());
+            }
+        }
+    }
+
+    let twelve_hour = parts.iter().any(|(c, _)| *c == 'A');
+    let mut out = String::new();
+    for (i, &(code, count)) in parts.iter().enumerate() {
+        match code {
+            'y' if count <= 2 => out.push_str(&format!("{:02}", dt.year() % 100)),
+            'y' => out.push_str(&format!("{:04}", dt.year())),
+            'm' => {
+                // "m" after an hour or before a second means minutes
+                let prev = parts[..i]
+                    .iter()
+                    .rev()
+                    .find(|(c, _)| *c != 'L')
+                    .map(|p| p.0);
+                let next = parts[i + 1..].iter().find(|(c, _)| *c != 'L').map(|p| p.0);
+                let is_minute =
+                    prev.is_some_and(|c| c == 'h' || c == '[') || next.is_some_and(|c| c == 's');
+                if is_minute && count <= 2 {
+                    out.push_str(&format!("{:0width$}", dt.minute(), width = count));
+                } else {
+                    let month = dt.month() as usize;
+                    match count {
+                        1 | 2 => out.push_str(&format!("{month:0count$}")),
+                        3 => out.push_str(&MONTH_NAMES[month - 1][..3]),
+                        5 => out.push_str(&MONTH_NAMES[month - 1][..1]),
+                        _ => out.push_str(MONTH_NAMES[month - 1]),
+                    }
+                }
+            }
+            'd' => {
+                let weekday = dt.weekday().num_days_from_monday() as usize;
+                match count {
+                    1 | 2 => out.push_str(&format!("{:0count$}", dt.day())),
+                    3 => out.push_str(&WEEKDAY_NAMES[weekday][..3]),
+                    _ => out.push_str(WEEKDAY_NAMES[weekday]),
+                }
+            }
+            'h' => {
+                let hour = if twelve_hour {
+                    (dt.hour() + 11) % 12 + 1
+                } else {
+                    dt.hour()
+                };
+                out.push_str(&format!("{hour:0width$}", width = count.min(2)));
+            }
+            's' => out.push_str(&format!("{:0width$}", dt.second(), width = count.min(2))),
+            '.' if parts.get(i + 1).is_some_and(|(c, _)| *c == '0') => {
+                let digits = parts[i + 1].1.min(3) as u32;
+                let sub = dt.nanosecond() / 10u32.pow(9 - digits);
+                out.push_str(&format!(".{sub:0width$}", width = digits as usize));
+            }
+            '0' if i > 0 && parts[i - 1].0 == '.' => {}
+            'A' => {
+                let pm = dt.hour() >= 12;
+                out.push_str(match (count, pm) {
+                    (5, false) => "AM",
+                    (5, true) => "PM",
+                    (_, false) => "A",
+                    (_, true) => "P",
+                });
+            }
+            '[' => {
+                let unit = literals.get(&i).map(String::as_str).unwrap_or("h");
+                let seconds = (serial.abs() * 86_400.0).round() as u64;
+                let elapsed = match unit.chars().next() {
+                    Some('h') => seconds / 3600,
+                    Some('m') => seconds / 60,
+                    _ => seconds,
+                };
+                out.push_str(&format!("{elapsed:0width$}", width = unit.len()));
+            }
+            _ => {
+                if let Some(text) = literals.get(&i) {
+                    out.push_str(text);
+                } else if code == '.' {
+                    out.push('.');
+                }
+            }
+        }
+    }
+    out
+}
+
+// ---- Hidden rows and columns ----
+
+/// Hidden rows and columns of a worksheet, as 0-based absolute indices.
+#[derive(Debug, Default, PartialEq)]
+struct HiddenRowsCols {
+    rows: HashSet<u32>,
+    cols: HashSet<u32>,
+}
+
+/// Most columns a worksheet can have (`XFD`); `<col>` ranges are clamped to it.
+const MAX_SHEET_COLUMNS: u32 = 16384;
+
+fn is_true_attr(value: &[u8]) -> bool {
+    value == b"1" || value == b"true"
+}
+
+/// Collect `<row hidden="1">` and `<col min max hidden="1">` from worksheet XML.
+fn parse_hidden_rows_cols(xml: &str) -> HiddenRowsCols {
+    let mut hidden = HiddenRowsCols::default();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    // Rows may omit `r`, in which case they follow the previous row
+    let mut next_row: u32 = 0;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => match e.local_name().as_ref() {
+                b"row" => {
+                    let mut row = next_row;
+                    let mut is_hidden = false;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"r" => {
+                                if let Some(r) = std::str::from_utf8(&attr.value)
+                                    .ok()
+                                    .and_then(|v| v.parse::<u32>().ok())
+                                    .filter(|r| *r > 0)
+                                {
+                                    row = r - 1;
+                                }
+                            }
+                            b"hidden" => is_hidden = is_true_attr(&attr.value),
+                            _ => {}
+                        }
+                    }
+                    if is_hidden {
+                        hidden.rows.insert(row);
+                    }
+                    next_row = row + 1;
+                }
+                b"col" => {
+                    let (mut min, mut max, mut is_hidden) = (0u32, 0u32, false);
+                    for attr in e.attributes().flatten() {
+                        let number = || {
+                            std::str::from_utf8(&attr.value)
+                                .ok()
+                                .and_then(|v| v.parse::<u32>().ok())
+                                .unwrap_or(0)
+                        };
+                        match attr.key.as_ref() {
+                            b"min" => min = number(),
+                            b"max" => max = number(),
+                            b"hidden" => is_hidden = is_true_attr(&attr.value),
+                            _ => {}
+                        }
+                    }
+                    if is_hidden && (1..=MAX_SHEET_COLUMNS).contains(&min) {
+                        let max = max.clamp(min, MAX_SHEET_COLUMNS);
+                        hidden.cols.extend(min - 1..max);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    hidden
+}
+
+/// 1-based row numbers and column letters of the hidden rows and columns
+/// inside a sheet grid whose top-left cell is at absolute `origin`.
+fn hidden_in_range(
+    grid: &[Vec<String>],
+    origin: (u32, u32),
+    hidden: &HiddenRowsCols,
+) -> (Vec<String>, Vec<String>) {
+    let height = grid.len() as u32;
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0) as u32;
+    let mut rows: Vec<u32> = hidden
+        .rows
+        .iter()
+        .copied()
+        .filter(|r| (origin.0..origin.0 + height).contains(r))
+        .collect();
+    let mut cols: Vec<u32> = hidden
+        .cols
+        .iter()
+        .copied()
+        .filter(|c| (origin.1..origin.1 + width).contains(c))
+        .collect();
+    rows.sort_unstable();
+    cols.sort_unstable();
+    (
+        rows.iter().map(|r| (r + 1).to_string()).collect(),
+        cols.iter().map(|c| col_letter(*c as usize)).collect(),
+    )
+}
+
+/// Report hidden content in a sheet. Skipped content is a `SkippedElement`;
+/// included or marked content is an `UnsupportedFeature`, since Markdown has
+/// no way to hide it from readers.
+fn hidden_content_warning(mode: HiddenContent, what: &str, sheet: &str) -> ConversionWarning {
+    let (code, action) = match mode {
+        HiddenContent::Skip => (WarningCode::SkippedElement, "skipped"),
+        HiddenContent::Include => (WarningCode::UnsupportedFeature, "included"),
+        HiddenContent::Mark => (WarningCode::UnsupportedFeature, "marked"),
+    };
+    ConversionWarning {
+        code,
+        message: format!("{action} {what}"),
+        location: Some(sheet.to_string()),
+    }
+}
+
+/// Drop or label the hidden rows and columns of a sheet table.
+fn apply_hidden_rows_cols(table: &mut SheetTable, hidden: &HiddenRowsCols, mode: HiddenContent) {
+    let row_hidden = |id: &Option<u32>| id.is_some_and(|r| hidden.rows.contains(&r));
+    let col_hidden: Vec<bool> = table
+        .col_ids
+        .iter()
+        .map(|c| hidden.cols.contains(c))
+        .collect();
+
+    match mode {
+        HiddenContent::Include => {}
+        HiddenContent::Skip => {
+            let keep_rows: Vec<bool> = table.row_ids.iter().map(|id| !row_hidden(id)).collect();
+            let mut keep = keep_rows.iter();
+            table.rows.retain(|_| *keep.next().unwrap_or(&true));
+            table.row_ids.retain(|id| !row_hidden(id));
+            for row in table.rows.iter_mut() {
+                let mut hidden_flags = col_hidden.iter();
+                row.retain(|_| !*hidden_flags.next().unwrap_or(&false));
+            }
+            let mut hidden_flags = col_hidden.iter();
+            table
+                .col_ids
+                .retain(|_| !*hidden_flags.next().unwrap_or(&false));
+        }
+        HiddenContent::Mark => {
+            for (ri, row) in table.rows.iter_mut().enumerate() {
+                if ri == 0 {
+                    for (cell, is_hidden) in row.iter_mut().zip(&col_hidden) {
+                        if *is_hidden {
+                            cell.push_str(" (hidden)");
+                        }
+                    }
+                }
+                if table.row_ids.get(ri).is_some_and(row_hidden)
+                    && let Some(first) = row.first_mut()
+                {
+                    first.insert_str(0, "(hidden) ");
+                }
+            }
+        }
+    }
+}
+
+// ---- Excel Tables and sheet layout ----
+
+/// A rectangular part of a sheet rendered as one Markdown table. `rows[0]` is the header.
+#[derive(Debug, Clone, PartialEq)]
+struct SheetTable {
+    /// Heading shown above the table (the Excel Table name), if any.
+    title: Option<String>,
+    /// Render the single cell as a paragraph instead of a table (a lone title-like cell).
+    paragraph: bool,
+    /// Rows and columns left out by the `max_rows`/`max_columns` caps.
+    truncation: Option<Truncation>,
+    rows: Vec<Vec<String>>,
+    /// Absolute sheet row of each entry in `rows` (`None` for a synthesized header).
+    row_ids: Vec<Option<u32>>,
+    /// Absolute sheet column of each column.
+    col_ids: Vec<u32>,
+}
+
+impl SheetTable {
+    /// Slice the cells of `area` out of a sheet grid whose top-left cell is at `origin`.
+    fn from_grid(grid: &[Vec<String>], origin: (u32, u32), area: Dimensions) -> Self {
+        let row_ids: Vec<Option<u32>> = (area.start.0..=area.end.0).map(Some).collect();
+        let col_ids: Vec<u32> = (area.start.1..=area.end.1).collect();
+        let rows = (area.start.0..=area.end.0)
+            .map(|r| {
+                col_ids
+                    .iter()
+                    .map(|&c| grid_cell(grid, origin, r, c))
+                    .collect()
+            })
+            .collect();
+        SheetTable {
+            title: None,
+            paragraph: false,
+            truncation: None,
+            rows,
+            row_ids,
+            col_ids,
+        }
+    }
+}
+
+/// The value at absolute `(row, col)` of a sheet grid, or an empty string.
+fn grid_cell(grid: &[Vec<String>], origin: (u32, u32), row: u32, col: u32) -> String {
+    grid_slot(grid, origin, row, col)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// The grid slot at absolute `(row, col)`, or `None` outside the grid.
+fn grid_slot(grid: &[Vec<String>], origin: (u32, u32), row: u32, col: u32) -> Option<&String> {
+    row.checked_sub(origin.0)
+        .zip(col.checked_sub(origin.1))
+        .and_then(|(r, c)| grid.get(r as usize)?.get(c as usize))
+}
+
+/// Whether the cell at absolute `(row, col)` of a sheet grid has a value.
+fn grid_filled(grid: &[Vec<String>], origin: (u32, u32), row: u32, col: u32) -> bool {
+    grid_slot(grid, origin, row, col).is_some_and(|value| !value.is_empty())
+}
+
+/// An Excel Table (ListObject) defined in `xl/tables/tableN.xml`.
+#[derive(Debug, Clone, PartialEq)]
+struct ExcelTable {
+    name: String,
+    area: Dimensions,
+    /// Whether the first row of `area` is the header row (`headerRowCount` ≠ 0).
+    header_row: bool,
+    columns: Vec<String>,
+}
+
+/// Parse an A1-style range reference (`"B2:E10"` or a single cell) into dimensions.
+fn parse_range_ref(range_ref: &str) -> Option<Dimensions> {
+    let (start, end) = range_ref.split_once(':').unwrap_or((range_ref, range_ref));
+    Some(Dimensions::new(
+        parse_cell_ref(start)?,
+        parse_cell_ref(end)?,
+    ))
+}
+
+fn parse_table_definition(xml: &str) -> Option<ExcelTable> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut table: Option<ExcelTable> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => match e.local_name().as_ref() {
+                b"table" => {
+                    let (mut name, mut display_name, mut area) = (None, None, None);
+                    let mut header_row = true;
+                    for attr in e.attributes().flatten() {
+                        let value = attr.unescape_value().unwrap_or_default().to_string();
+                        match attr.key.as_ref() {
+                            b"name" => name = Some(value),
+                            b"displayName" => display_name = Some(value),
+                            b"ref" => area = parse_range_ref(&value),
+                            b"headerRowCount" => header_row = value != "0",
+                            _ => {}
+                        }
+                    }
+                    table = Some(ExcelTable {
+                        name: display_name.or(name).unwrap_or_default(),
+                        area: area?,
+                        header_row,
+                        columns: Vec::new(),
+                    });
+                }
+                b"tableColumn" => {
+                    if let Some(table) = table.as_mut() {
+                        let name = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"name")
+                            .map(|a| a.unescape_value().unwrap_or_default().to_string())
+                            .unwrap_or_default();
+                        table.columns.push(name);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    table
+}
+
+/// Read a worksheet's relationships, or an empty map if it has none.
+fn load_sheet_rels(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    sheet_path: &str,
+) -> Result<HashMap<String, Relationship>, ConvertError> {
+    Ok(read_zip_text(archive, &derive_rels_path(sheet_path))?
+        .map(|xml| parse_relationships(&xml))
+        .unwrap_or_default())
+}
+
+/// Sorted archive paths of the parts related to `sheet_path` whose relationship
+/// type ends with `type_suffix` (e.g. `"/table"`).
+fn related_parts(
+    sheet_path: &str,
+    rels: &HashMap<String, Relationship>,
+    type_suffix: &str,
+) -> Vec<String> {
+    let mut paths: Vec<String> = rels
+        .values()
+        .filter(|rel| rel.rel_type.ends_with(type_suffix))
+        .map(|rel| resolve_relative_to_file(sheet_path, &rel.target))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Load the Excel Tables attached to a worksheet through its relationships.
+fn load_sheet_tables(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    sheet_path: &str,
+    rels: &HashMap<String, Relationship>,
+) -> Result<Vec<ExcelTable>, ConvertError> {
+    let mut tables = Vec::new();
+    for path in related_parts(sheet_path, rels, "/table") {
+        if let Some(table) = read_zip_text(archive, &path)?
+            .as_deref()
+            .and_then(parse_table_definition)
+        {
+            tables.push(table);
+        }
+    }
+    Ok(tables)
+}
+
+/// Split a sheet grid into its Excel Tables and the remaining cells.
+///
+/// Cells inside `reserved` areas (pivot tables, rendered separately) are left out.
+/// Without Excel Tables the whole grid is one untitled table, as before. Otherwise
+/// each Excel Table becomes a titled table with its column names as header, and
+/// the non-empty cells outside all tables form one more untitled table. With
+/// `detect_regions`, those remaining cells are split further into islands (see
+/// [`find_regions`]). Tables are ordered by their top-left cell.
+fn split_sheet_tables(
+    grid: &[Vec<String>],
+    origin: (u32, u32),
+    excel_tables: &[ExcelTable],
+    reserved: &[Dimensions],
+    detect_regions: bool,
+) -> Vec<SheetTable> {
+    let Some(used) = used_area(grid, origin) else {
+        return Vec::new();
+    };
+    if excel_tables.is_empty() && reserved.is_empty() && !detect_regions {
+        return vec![SheetTable::from_grid(grid, origin, used)];
+    }
+
+    let mut rest = grid.to_vec();
+    let mut blank_out = |area: Dimensions| {
+        let Some(area) = intersect(area, used) else {
+            return;
+        };
+        for r in area.start.0..=area.end.0 {
+            for c in area.start.1..=area.end.1 {
+                if let Some(cell) = r
+                    .checked_sub(origin.0)
+                    .zip(c.checked_sub(origin.1))
+                    .and_then(|(r, c)| rest.get_mut(r as usize)?.get_mut(c as usize))
+                {
+                    cell.clear();
+                }
+            }
+        }
+    };
+    for area in reserved {
+        blank_out(*area);
+    }
+    let mut tables: Vec<((u32, u32), SheetTable)> = Vec::new();
+    for excel_table in excel_tables {
+        // A table's declared ref is untrusted; only its cells in the used range count
+        let Some(area) = intersect(excel_table.area, used) else {
+            continue;
+        };
+        let header_in_grid = excel_table.header_row && area.start.0 == excel_table.area.start.0;
+        let mut table = SheetTable::from_grid(grid, origin, area);
+        let header: Vec<String> = table
+            .col_ids
+            .iter()
+            .map(|&col| {
+                let index = (col - excel_table.area.start.1) as usize;
+                match excel_table.columns.get(index) {
+                    Some(name) if !name.is_empty() => name.clone(),
+                    _ if header_in_grid => grid_cell(grid, origin, area.start.0, col),
+                    _ => String::new(),
+                }
+            })
+            .collect();
+        if header_in_grid {
+            table.rows[0] = header;
+        } else {
+            table.rows.insert(0, header);
+            table.row_ids.insert(0, None);
+        }
+        table.title = Some(excel_table.name.clone());
+        tables.push((area.start, table));
+
+        // Blank out the table's cells so they do not reappear in the remainder
+        blank_out(area);
+    }
+
+    let is_filled = |r: u32, c: u32| grid_filled(&rest, origin, r, c);
+    if detect_regions {
+        for area in find_regions(&is_filled, used) {
+            tables.push((area.start, region_table(&rest, origin, area)));
+        }
+    } else if let Some(area) = trim_area(&is_filled, used) {
+        // Bounding box of whatever is left outside the tables
+        tables.push((area.start, SheetTable::from_grid(&rest, origin, area)));
+    }
+
+    tables.sort_by_key(|(position, _)| *position);
+    tables.into_iter().map(|(_, table)| table).collect()
+}
+
+/// The absolute area covered by a sheet grid, or `None` if it is empty.
+fn used_area(grid: &[Vec<String>], origin: (u32, u32)) -> Option<Dimensions> {
+    let height = grid.len() as u32;
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0) as u32;
+    (height > 0 && width > 0)
+        .then(|| Dimensions::new(origin, (origin.0 + height - 1, origin.1 + width - 1)))
+}
+
+/// The overlap of two areas, or `None` if they do not overlap.
+fn intersect(a: Dimensions, b: Dimensions) -> Option<Dimensions> {
+    let start = (a.start.0.max(b.start.0), a.start.1.max(b.start.1));
+    let end = (a.end.0.min(b.end.0), a.end.1.min(b.end.1));
+    (start.0 <= end.0 && start.1 <= end.1).then(|| Dimensions::new(start, end))
+}
+
+/// Shrink `area` to the bounding box of its filled cells, or `None` if it has none.
+fn trim_area(is_filled: &impl Fn(u32, u32) -> bool, area: Dimensions) -> Option<Dimensions> {
+    let row_filled = |r: u32| (area.start.1..=area.end.1).any(|c| is_filled(r, c));
+    let top = (area.start.0..=area.end.0).find(|&r| row_filled(r))?;
+    let bottom = (area.start.0..=area.end.0).rev().find(|&r| row_filled(r))?;
+    let col_filled = |c: u32| (top..=bottom).any(|r| is_filled(r, c));
+    let left = (area.start.1..=area.end.1).find(|&c| col_filled(c))?;
+    let right = (area.start.1..=area.end.1).rev().find(|&c| col_filled(c))?;
+    Some(Dimensions::new((top, left), (bottom, right)))
+}
+
+/// Group consecutive indices in `range` for which `filled` holds into inclusive runs.
+fn filled_runs(
+    range: std::ops::RangeInclusive<u32>,
+    filled: impl Fn(u32) -> bool,
+) -> Vec<(u32, u32)> {
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for i in range.filter(|&i| filled(i)) {
+        match runs.last_mut() {
+            Some(run) if run.1 + 1 == i => run.1 = i,
+            _ => runs.push((i, i)),
+        }
+    }
+    runs
+}
+
+/// Split `area` into contiguous islands of cells separated by fully blank rows
+/// or columns, recursively (blank rows first, then blank columns), in reading order.
+fn find_regions(is_filled: &impl Fn(u32, u32) -> bool, area: Dimensions) -> Vec<Dimensions> {
+    let Some(area) = trim_area(is_filled, area) else {
+        return Vec::new();
+    };
+
+    let bands = filled_runs(area.start.0..=area.end.0, |r| {
+        (area.start.1..=area.end.1).any(|c| is_filled(r, c))
+    });
+    if bands.len() > 1 {
+        return bands
+            .into_iter()
+            .flat_map(|(top, bottom)| {
+                find_regions(
+                    is_filled,
+                    Dimensions::new((top, area.start.1), (bottom, area.end.1)),
+                )
+            })
+            .collect();
+    }
+
+    let columns = filled_runs(area.start.1..=area.end.1, |c| {
+        (area.start.0..=area.end.0).any(|r| is_filled(r, c))
+    });
+    if columns.len() > 1 {
+        return columns
+            .into_iter()
+            .flat_map(|(left, right)| {
+                find_regions(
+                    is_filled,
+                    Dimensions::new((area.start.0, left), (area.end.0, right)),
+                )
+            })
+            .collect();
+    }
+
+    vec![area]
+}
+
+/// Build the table for one detected region.
+///
+/// A single cell becomes a paragraph. The first row is used as the header when
+/// the region has data below it and every header cell is non-empty, non-numeric
+/// text; otherwise the sheet's column letters are used as the header.
+fn region_table(grid: &[Vec<String>], origin: (u32, u32), area: Dimensions) -> SheetTable {
+    let mut table = SheetTable::from_grid(grid, origin, area);
+    if table.rows.len() == 1 && table.col_ids.len() == 1 {
+        table.paragraph = true;
+        return table;
+    }
+
+    let looks_like_header = table.rows.len() > 1
+        && table.rows[0].iter().all(|cell| {
+            !cell.is_empty() && cell.replace([',', '%'], "").trim().parse::<f64>().is_err()
+        });
+    if !looks_like_header {
+        let letters = table
+            .col_ids
+            .iter()
+            .map(|&c| col_letter(c as usize))
+            .collect();
+        table.rows.insert(0, letters);
+        table.row_ids.insert(0, None);
+    }
+    table
+}
+
+// ---- Row and column caps ----
+
+/// Most omitted column headers named in a truncation summary.
+const MAX_LISTED_COLUMNS: usize = 20;
+
+/// What [`truncate_table`] left out of a table.
+#[derive(Debug, Clone, PartialEq)]
+struct Truncation {
+    omitted_rows: usize,
+    kept_rows: usize,
+    sampling: RowSampling,
+    /// Header text (or column letter, for blank headers) of each omitted column.
+    omitted_columns: Vec<String>,
+}
+
+impl Truncation {
+    /// E.g. `"48,210 more rows (showing first 100), 2 more columns: Tax, Total"`.
+    fn details(&self) -> String {
+        let mut parts = Vec::new();
+        if self.omitted_rows > 0 {
+            let kept = self.kept_rows;
+            let shown = match self.sampling {
+                _ if kept == 0 => "sheet row limit reached".to_string(),
+                RowSampling::Head => format!("showing first {kept}"),
+                RowSampling::Tail => format!("showing last {kept}"),
+                RowSampling::Evenly => format!("showing {kept} evenly spaced"),
+            };
+            parts.push(format!(
+                "{} more {} ({shown})",
+                group_thousands(&self.omitted_rows.to_string()),
+                if self.omitted_rows == 1 {
+                    "row"
+                } else {
+                    "rows"
+                }
+            ));
+        }
+        if !self.omitted_columns.is_empty() {
+            let count = self.omitted_columns.len();
+            let mut names = self.omitted_columns[..count.min(MAX_LISTED_COLUMNS)].join(", ");
+            if count > MAX_LISTED_COLUMNS {
+                names.push_str(", …");
+            }
+            parts.push(format!(
+                "{} more {}: {names}",
+                group_thousands(&count.to_string()),
+                if count == 1 { "column" } else { "columns" }
+            ));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Cap a table at `max_rows` data rows (the header is always kept) and
+/// `max_columns` columns (at least one). Returns what was left out, if anything.
+///
+/// `max_rows` is what is left of the sheet's row budget; see `convert_inner`.
+fn truncate_table(
+    table: &mut SheetTable,
+    max_rows: Option<usize>,
+    max_columns: Option<usize>,
+    sampling: RowSampling,
+) -> Option<Truncation> {
+    let data_rows = table.rows.len().saturating_sub(1);
+    let mut omitted_rows = 0;
+    let mut kept_rows = data_rows;
+    if let Some(max) = max_rows
+        && data_rows > max
+    {
+        // Indices into `rows`; 0 is the header
+        let keep: HashSet<usize> = match sampling {
+            RowSampling::Head => (1..=max).collect(),
+            RowSampling::Tail => (table.rows.len() - max..table.rows.len()).collect(),
+            RowSampling::Evenly => (0..max).map(|i| 1 + i * data_rows / max).collect(),
+        };
+        let mut index = 0..;
+        table.rows.retain(|_| {
+            let i = index.next().unwrap_or(0);
+            i == 0 || keep.contains(&i)
+        });
+        let mut index = 0..;
+        table.row_ids.retain(|_| {
+            let i = index.next().unwrap_or(0);
+            i == 0 || keep.contains(&i)
+        });
+        omitted_rows = data_rows - max;
+        kept_rows = max;
+    }
+
+    let mut omitted_columns = Vec::new();
+    if let Some(max) = max_columns.map(|max| max.max(1))
+        && table.col_ids.len() > max
+    {
+        let header = table.rows.first();
+        omitted_columns = table.col_ids[max..]
+            .iter()
+            .enumerate()
+            .map(|(i, col)| match header.and_then(|h| h.get(max + i)) {
+                Some(name) if !name.is_empty() => name.clone(),
+                _ => col_letter(*col as usize),
+            })
+            .collect();
+        for row in table.rows.iter_mut() {
+            row.truncate(max);
+        }
+        table.col_ids.truncate(max);
+    }
+
+    (omitted_rows > 0 || !omitted_columns.is_empty()).then_some(Truncation {
+        omitted_rows,
+        kept_rows,
+        sampling,
+        omitted_columns,
+    })
+}
+
+/// Render a sheet table as `(markdown, plain_text)`, including its title line.
+/// Hyperlinked cells become Markdown links; the plain text keeps the cell text.
+fn render_sheet_table(table: &SheetTable, links: &SheetLinks) -> (String, String) {
+    let mut markdown = String::new();
+    let mut plain = String::new();
+    if let Some(title) = &table.title {
+        markdown.push_str(&format_heading(3, title));
+        plain.push_str(title);
+        plain.push('\n');
+    }
+    let linked: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .zip(&table.row_ids)
+        .map(|(row, row_id)| {
+            row.iter()
+                .zip(&table.col_ids)
+                .map(
+                    |(cell, col)| match row_id.and_then(|r| links.get(r, *col)) {
+                        Some(url) if !cell.is_empty() => {
+                            format!("[{}]({})", escape_link_text(cell), escape_link_url(url))
+                        }
+                        _ => cell.clone(),
+                    },
+                )
+                .collect()
+        })
+        .collect();
+
+    if table.paragraph {
+        markdown.push_str(&linked.concat().concat());
+        markdown.push('\n');
+        plain.push_str(&table.rows.concat().concat());
+        plain.push('\n');
+        return (markdown, plain);
+    }
+    let (Some((headers, rows)), Some((plain_headers, plain_rows))) =
+        (linked.split_first(), table.rows.split_first())
+    else {
+        return (markdown, plain);
+    };
+    fn as_refs(row: &[String]) -> Vec<&str> {
+        row.iter().map(String::as_str).collect()
+    }
+    let row_refs: Vec<Vec<&str>> = rows.iter().map(|row| as_refs(row)).collect();
+    let plain_row_refs: Vec<Vec<&str>> = plain_rows.iter().map(|row| as_refs(row)).collect();
+    markdown.push_str(&build_table(&as_refs(headers), &row_refs));
+    plain.push_str(&build_table_plain(&as_refs(plain_headers), &plain_row_refs));
+    if let Some(truncation) = &table.truncation {
+        let summary = format!("… {}", truncation.details());
+        markdown.push_str(&format!("\n{summary}\n"));
+        plain.push_str(&format!("\n{summary}\n"));
+    }
+    (markdown, plain)
+}
+
+// ---- Hyperlinks ----
+
+/// The hyperlinks of a sheet. Single-cell links are looked up directly; a link
+/// over a range is kept as its area, since a ref like `A1:XFD1048576` would be
+/// too large to expand cell by cell.
+#[derive(Debug, Default)]
+struct SheetLinks {
+    cells: HashMap<(u32, u32), String>,
+    ranges: Vec<(Dimensions, String)>,
+}
+
+impl SheetLinks {
+    /// The URL linked from absolute `(row, col)`, if any.
+    fn get(&self, row: u32, col: u32) -> Option<&str> {
+        self.cells
+            .get(&(row, col))
+            .or_else(|| {
+                self.ranges
+                    .iter()
+                    .rev()
+                    .find(|(area, _)| area.contains(row, col))
+                    .map(|(_, url)| url)
+            })
+            .map(String::as_str)
+    }
+}
+
+/// Replace the characters that would end or break Markdown link text with
+/// character references. Backslash escapes are not used because table cells
+/// escape backslashes again.
+fn escape_link_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("&#92;"),
+            '[' => escaped.push_str("&#91;"),
+            ']' => escaped.push_str("&#93;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode the characters that would end or break a Markdown link
+/// destination: whitespace, parentheses and angle brackets.
+fn escape_link_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            ' ' => escaped.push_str("%20"),
+            '(' => escaped.push_str("%28"),
+            ')' => escaped.push_str("%29"),
+            '<' => escaped.push_str("%3C"),
+            '>' => escaped.push_str("%3E"),
+            c if c.is_whitespace() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Parse a worksheet's `<hyperlinks>`, resolving the targets through the sheet
+/// relationships. A `location` is appended as a fragment; links that only
+/// point inside the workbook are ignored.
+fn parse_hyperlinks(xml: &str, rels: &HashMap<String, Relationship>) -> SheetLinks {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut links = SheetLinks::default();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.local_name().as_ref() == b"hyperlink" =>
+            {
+                let (mut area, mut rel_id, mut location) = (None, None, None);
+                for attr in e.attributes().flatten() {
+                    let value = attr.unescape_value().unwrap_or_default().to_string();
+                    match attr.key.local_name().as_ref() {
+                        b"ref" => area = parse_range_ref(&value),
+                        b"id" => rel_id = Some(value),
+                        b"location" => location = Some(value),
+                        _ => {}
+                    }
+                }
+                let Some(target) = rel_id.and_then(|id| rels.get(&id)) else {
+                    continue;
+                };
+                let url = match location {
+                    Some(location) if !location.is_empty() => {
+                        format!("{}#{location}", target.target)
+                    }
+                    _ => target.target.clone(),
+                };
+                match area {
+                    Some(area) if area.start == area.end => {
+                        links.cells.insert(area.start, url);
+                    }
+                    Some(area) => links.ranges.push((area, url)),
+                    None => {}
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    links
+}
+
+// ---- Notes and threaded comments ----
+
+/// A cell note or threaded comment.
+#[derive(Debug, Clone, PartialEq)]
+struct CellComment {
+    /// Absolute `(row, col)` of the commented cell.
+    cell: (u32, u32),
+    author: String,
+    text: String,
+    /// `true` for replies in a comment thread.
+    is_reply: bool,
+}
+
+/// Parse a legacy notes part (`xl/commentsN.xml`).
+///
+/// Excel prefixes note text with `"Author:"`; that prefix is dropped since the
+/// author is reported separately.
+fn parse_notes(xml: &str) -> Vec<CellComment> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut authors: Vec<String> = Vec::new();
+    let mut notes = Vec::new();
+    let mut current: Option<(CellComment, usize)> = None;
+    let mut in_author = false;
+    let mut in_text = false;
+    let mut in_phonetic = false;
+    let mut author_text = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"author" => {
+                    in_author = true;
+                    author_text.clear();
+                }
+                b"comment" => {
+                    let (mut cell, mut author_id) = (None, 0);
+                    for attr in e.attributes().flatten() {
+                        let value = attr.unescape_value().unwrap_or_default().to_string();
+                        match attr.key.as_ref() {
+                            b"ref" => cell = parse_cell_ref(&value),
+                            b"authorId" => author_id = value.parse().unwrap_or(0),
+                            _ => {}
+                        }
+                    }
+                    current = cell.map(|cell| {
+                        let comment = CellComment {
+                            cell,
+                            author: authors.get(author_id).cloned().unwrap_or_default(),
+                            text: String::new(),
+                            is_reply: false,
+                        };
+                        (comment, author_id)
+                    });
+                }
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Ok(Event::Text(ref t)) => {
+                let text = t.unescape().unwrap_or_default();
+                if in_author {
+                    author_text.push_str(&text);
+                } else if in_text
+                    && !in_phonetic
+                    && let Some((comment, _)) = current.as_mut()
+                {
+                    comment.text.push_str(&text);
+                }
+            }
+            Ok(Event::End(ref e)) => match e.local_name().as_ref() {
+                b"author" => {
+                    in_author = false;
+                    authors.push(author_text.clone());
+                }
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                b"comment" => {
+                    if let Some((mut comment, _)) = current.take() {
+                        let text = comment.text.trim();
+                        let text = text
+                            .strip_prefix(&format!("{}:", comment.author))
+                            .filter(|_| !comment.author.is_empty())
+                            .unwrap_or(text);
+                        comment.text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                        if !comment.text.is_empty() {
+                            notes.push(comment);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    notes
+}
+
+/// Parse the person list (`xl/persons/person.xml`) into id → display name.
+fn parse_persons(xml: &str) -> HashMap<String, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut persons = HashMap::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.local_name().as_ref() == b"person" =>
+            {
+                let (mut id, mut display_name) = (None, None);
+                for attr in e.attributes().flatten() {
+                    let value = attr.unescape_value().unwrap_or_default().to_string();
+                    match attr.key.as_ref() {
+                        b"id" => id = Some(value),
+                        b"displayName" => display_name = Some(value),
+                        _ => {}
+                    }
+                }
+                if let (Some(id), Some(name)) = (id, display_name) {
+                    persons.insert(id, name);
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    persons
+}
+
+/// Parse a threaded comments part (`xl/threadedComments/threadedCommentN.xml`).
+fn parse_threaded_comments(xml: &str, persons: &HashMap<String, String>) -> Vec<CellComment> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut comments = Vec::new();
+    let mut current: Option<CellComment> = None;
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"threadedComment" => {
+                    let (mut cell, mut author, mut is_reply) = (None, String::new(), false);
+                    for attr in e.attributes().flatten() {
+                        let value = attr.unescape_value().unwrap_or_default().to_string();
+                        match attr.key.as_ref() {
+                            b"ref" => cell = parse_cell_ref(&value),
+                            b"personId" => {
+                                author = persons.get(&value).cloned().unwrap_or_default()
+                            }
+                            b"parentId" => is_reply = true,
+                            _ => {}
+                        }
+                    }
+                    current = cell.map(|cell| CellComment {
+                        cell,
+                        author,
+                        text: String::new(),
+                        is_reply,
+                    });
+                }
+                b"text" => in_text = true,
+                _ => {}
+            },
+            Ok(Event::Text(ref t)) if in_text => {
+                if let Some(comment) = current.as_mut() {
+                    comment.text.push_str(&t.unescape().unwrap_or_default());
+                }
+            }
+            Ok(Event::End(ref e)) => match e.local_name().as_ref() {
+                b"text" => in_text = false,
+                b"threadedComment" => {
+                    if let Some(mut comment) = current.take() {
+                        comment.text = comment
+                            .text
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        if !comment.text.is_empty() {
+                            comments.push(comment);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    comments
+}
+
+/// Load the notes and threaded comments of a worksheet, ordered by cell.
+///
+/// Excel keeps a legacy note next to each threaded comment for older readers;
+/// those placeholders are dropped in favour of the thread.
+fn load_sheet_comments(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    sheet_path: &str,
+    rels: &HashMap<String, Relationship>,
+    persons: &HashMap<String, String>,
+) -> Result<Vec<CellComment>, ConvertError> {
+    let mut threaded = Vec::new();
+    for path in related_parts(sheet_path, rels, "/threadedComment") {
+        if let Some(xml) = read_zip_text(archive, &path)? {
+            threaded.extend(parse_threaded_comments(&xml, persons));
+        }
+    }
+    let threaded_cells: HashSet<(u32, u32)> = threaded.iter().map(|c| c.cell).collect();
+
+    let mut comments = Vec::new();
+    for path in related_parts(sheet_path, rels, "/comments") {
+        if let Some(xml) = read_zip_text(archive, &path)? {
+            comments.extend(
+                parse_notes(&xml)
+                    .into_iter()
+                    .filter(|note| !threaded_cells.contains(&note.cell)),
+            );
+        }
+    }
+    comments.extend(threaded);
+    // Stable sort keeps replies after the comment they answer
+    comments.sort_by_key(|comment| comment.cell);
+    Ok(comments)
+}
+
+// ---- Charts ----
+
+/// A chart anchored in a sheet drawing (`xl/charts/chartN.xml`).
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Chart {
+    title: Option<String>,
+    /// Plot type, e.g. `"bar"` or `"line"` (the first plot of combo charts).
+    kind: Option<&'static str>,
+    series: Vec<ChartSeries>,
+}
+
+/// One chart series with its cached categories and values, keyed by point index.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ChartSeries {
+    name: Option<String>,
+    categories: BTreeMap<usize, String>,
+    values: BTreeMap<usize, String>,
+}
+
+/// Map a DrawingML plot element (`c:barChart`, `c:pie3DChart`, ...) to a chart kind.
+fn chart_kind(local_name: &[u8]) -> Option<&'static str> {
+    let name = std::str::from_utf8(local_name)
+        .ok()?
+        .strip_suffix("Chart")?;
+    Some(match name.strip_suffix("3D").unwrap_or(name) {
+        "bar" => "bar",
+        "line" => "line",
+        "pie" | "ofPie" => "pie",
+        "doughnut" => "doughnut",
+        "area" => "area",
+        "scatter" => "scatter",
+        "radar" => "radar",
+        "bubble" => "bubble",
+        "stock" => "stock",
+        "surface" => "surface",
+        _ => return None,
+    })
+}
+
+/// Parse a chart part, reading series names, categories and values from the
+/// caches Excel stores next to each cell reference.
+fn parse_chart(xml: &str) -> Chart {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut chart = Chart::default();
+    let mut stack: Vec<Vec<u8>> = Vec::new();
+    let mut point_idx = 0;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => {
+                let local = e.local_name().as_ref().to_vec();
+                match local.as_slice() {
+                    b"ser" => chart.series.push(ChartSeries::default()),
+                    b"pt" => {
+                        point_idx = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"idx")
+                            .and_then(|a| a.unescape_value().ok()?.parse().ok())
+                            .unwrap_or(0);
+                    }
+                    name if stack.last().is_some_and(|p| p == b"plotArea")
+                        && chart.kind.is_none() =>
+                    {
+                        chart.kind = chart_kind(name);
+                    }
+                    _ => {}
+                }
+                stack.push(local);
+            }
+            Ok(Event::Text(ref t)) => {
+                let text = t.unescape().unwrap_or_default();
+                let inside = |name: &[u8]| stack.iter().any(|n| n == name);
+                let Some(current) = stack.last() else {
+                    continue;
+                };
+                if !inside(b"ser") {
+                    // Chart title: `c:chart/c:title`, as rich text or a cached cell value
+                    let is_chart_title = stack
+                        .windows(2)
+                        .any(|w| w[0] == b"chart" && w[1] == b"title");
+                    if is_chart_title && (current == b"t" || current == b"v") {
+                        chart.title.get_or_insert_with(String::new).push_str(&text);
+                    }
+                } else if current == b"v"
+                    && let Some(series) = chart.series.last_mut()
+                {
+                    if inside(b"tx") {
+                        series.name = Some(text.to_string());
+                    } else if inside(b"cat") || inside(b"xVal") {
+                        // Multi-level categories: the first level holds the leaf labels
+                        series
+                            .categories
+                            .entry(point_idx)
+                            .or_insert_with(|| text.to_string());
+                    } else if inside(b"val") || inside(b"yVal") {
+                        series.values.insert(point_idx, text.to_string());
+                    }
+                }
+            }
+            Ok(Event::End(_)) => {
+                stack.pop();
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    chart.title = chart
+        .title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    chart
+}
+
+/// Relationship ids of the charts in a drawing part, in drawing order.
+fn parse_drawing_charts(xml: &str) -> Vec<String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut rel_ids = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.local_name().as_ref() == b"chart" =>
+            {
+                if let Some(id) = e
+                    .attributes()
+                    .flatten()
+                    .find(|a| a.key.local_name().as_ref() == b"id")
+                {
+                    rel_ids.push(id.unescape_value().unwrap_or_default().to_string());
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    rel_ids
+}
+
+/// Load the charts of a worksheet's drawings.
+fn load_sheet_charts(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    sheet_path: &str,
+    rels: &HashMap<String, Relationship>,
+) -> Result<Vec<Chart>, ConvertError> {
+    let mut charts = Vec::new();
+    for drawing_path in related_parts(sheet_path, rels, "/drawing") {
+        let Some(drawing_xml) = read_zip_text(archive, &drawing_path)? else {
+            continue;
+        };
+        let drawing_rels = load_sheet_rels(archive, &drawing_path)?;
+        for rel_id in parse_drawing_charts(&drawing_xml) {
+            let Some(rel) = drawing_rels.get(&rel_id) else {
+                continue;
+            };
+            let chart_path = resolve_relative_to_file(&drawing_path, &rel.target);
+            if let Some(chart_xml) = read_zip_text(archive, &chart_path)? {
+                charts.push(parse_chart(&chart_xml));
+            }
+        }
+    }
+    Ok(charts)
+}
+
+/// Render a chart as a titled table with one column per series, as
+/// `(markdown, plain_text)`. `number` is the 1-based chart index in the sheet,
+/// used when the chart has no title.
+fn render_chart(chart: &Chart, number: usize) -> (String, String) {
+    let mut title = match &chart.title {
+        Some(title) => format!("Chart: {title}"),
+        None => format!("Chart {number}"),
+    };
+    if let Some(kind) = chart.kind {
+        title.push_str(&format!(" ({kind} chart)"));
+    }
+
+    // Only the cached point indices; a stray `idx="1000000"` must not add empty rows
+    let points: BTreeSet<usize> = chart
+        .series
+        .iter()
+        .flat_map(|s| s.categories.keys().chain(s.values.keys()))
+        .copied()
+        .collect();
+    let category_header = if chart.kind == Some("scatter") {
+        "X"
+    } else {
+        "Category"
+    };
+    let mut headers = vec![category_header.to_string()];
+    headers.extend(chart.series.iter().enumerate().map(|(i, s)| {
+        s.name
+            .clone()
+            .unwrap_or_else(|| format!("Series {}", i + 1))
+    }));
+    let rows: Vec<Vec<String>> = points
+        .into_iter()
+        .map(|idx| {
+            let category = chart
+                .series
+                .iter()
+                .find_map(|s| s.categories.get(&idx))
+                .cloned()
+                .unwrap_or_else(|| (idx + 1).to_string());
+            std::iter::once(category)
+                .chain(chart.series.iter().map(|s| {
+                    s.values
+                        .get(&idx)
+                        .map(|v| v.parse::<f64>().map_or_else(|_| v.clone(), format_float))
+                        .unwrap_or_default()
+                }))
+                .collect()
+        })
+        .collect();
+
+    let header_refs: Vec<&str> = headers.iter().map(String::as_str).collect();
+    let row_refs: Vec<Vec<&str>> = rows
+        .iter()
+        .map(|row| row.iter().map(String::as_str).collect())
+        .collect();
+    (
+        format!(
+            "{}{}",
+            format_heading(3, &title),
+            build_table(&header_refs, &row_refs)
+        ),
+        format!("{title}\n{}", build_table_plain(&header_refs, &row_refs)),
+    )
+}
+
+// ---- Pivot tables ----
+
+/// A pivot table definition (`xl/pivotTables/pivotTableN.xml`).
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PivotTable {
+    name: String,
+    /// Where Excel renders the pivot table on the sheet.
+    location: Option<Dimensions>,
+    /// Cache field indices on each axis.
+    row_fields: Vec<usize>,
+    col_fields: Vec<usize>,
+    page_fields: Vec<usize>,
+    data_fields: Vec<PivotDataField>,
+    cache: PivotCache,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PivotDataField {
+    /// Display name, e.g. `"Sum of Revenue"`.
+    name: String,
+    field: usize,
+    /// Aggregation function (`sum`, `count`, `average`, `max`, `min`, ...).
+    subtotal: String,
+}
+
+/// The pivot cache: source range, field names, and records resolved to text.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PivotCache {
+    source: Option<String>,
+    fields: Vec<String>,
+    records: Vec<Vec<String>>,
+}
+
+fn parse_pivot_table_definition(xml: &str) -> PivotTable {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut pivot = PivotTable::default();
+    let mut axis: Option<Vec<u8>> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                let attr = |name: &[u8]| {
+                    e.attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == name)
+                        .map(|a| a.unescape_value().unwrap_or_default().to_string())
+                };
+                match e.local_name().as_ref() {
+                    b"pivotTableDefinition" => pivot.name = attr(b"name").unwrap_or_default(),
+                    b"location" => pivot.location = attr(b"ref").and_then(|r| parse_range_ref(&r)),
+                    name @ (b"rowFields" | b"colFields") => axis = Some(name.to_vec()),
+                    b"field" => {
+                        // `x="-2"` is the "Values" pseudo-field
+                        let index = attr(b"x").and_then(|x| x.parse::<usize>().ok());
+                        match (axis.as_deref(), index) {
+                            (Some(b"rowFields"), Some(x)) => pivot.row_fields.push(x),
+                            (Some(b"colFields"), Some(x)) => pivot.col_fields.push(x),
+                            _ => {}
+                        }
+                    }
+                    b"pageField" => {
+                        if let Some(field) = attr(b"fld").and_then(|f| f.parse().ok()) {
+                            pivot.page_fields.push(field);
+                        }
+                    }
+                    b"dataField" => {
+                        if let Some(field) = attr(b"fld").and_then(|f| f.parse().ok()) {
+                            pivot.data_fields.push(PivotDataField {
+                                name: attr(b"name").unwrap_or_default(),
+                                field,
+                                subtotal: attr(b"subtotal").unwrap_or_else(|| "sum".to_string()),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(ref e))
+                if matches!(e.local_name().as_ref(), b"rowFields" | b"colFields") =>
+            {
+                axis = None;
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    pivot
+}
+
+/// Text of a cached pivot value element (`<s v="..."/>`, `<n>`, `<b>`, `<d>`, `<e>`, `<m/>`).
+fn pivot_value_text(e: &quick_xml::events::BytesStart) -> Option<String> {
+    let value = e
+        .attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == b"v")
+        .map(|a| a.unescape_value().unwrap_or_default().to_string())
+        .unwrap_or_default();
+    Some(match e.local_name().as_ref() {
+        b"s" | b"e" => value,
+        b"n" => value.parse::<f64>().map_or(value, format_float),
+        b"b" => if value == "1" { "TRUE" } else { "FALSE" }.to_string(),
+        b"d" => value
+            .strip_suffix("T00:00:00")
+            .unwrap_or(&value)
+            .to_string(),
+        b"m" => String::new(),
+        _ => return None,
+    })
+}
+
+/// Parse a pivot cache definition into its source and fields, plus the shared
+/// items of each field (referenced by index from the records).
+fn parse_pivot_cache_definition(xml: &str) -> (PivotCache, Vec<Vec<String>>) {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut cache = PivotCache::default();
+    let mut shared_items: Vec<Vec<String>> = Vec::new();
+    let mut in_shared_items = false;
+
+    loop {
+        match reader.read_event() {
+            // `<sharedItems/>`: a field without shared items
+            Ok(Event::Empty(ref e)) if e.local_name().as_ref() == b"sharedItems" => {}
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => match e.local_name().as_ref() {
+                b"worksheetSource" => {
+                    let attr = |name: &[u8]| {
+                        e.attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == name)
+                            .map(|a| a.unescape_value().unwrap_or_default().to_string())
+                    };
+                    cache.source = match (attr(b"sheet"), attr(b"ref"), attr(b"name")) {
+                        (Some(sheet), Some(range), _) => Some(format!("{sheet}!{range}")),
+                        (_, _, Some(name)) => Some(name),
+                        (_, range, _) => range,
+                    };
+                }
+                b"cacheField" => {
+                    let name = e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == b"name")
+                        .map(|a| a.unescape_value().unwrap_or_default().to_string())
+                        .unwrap_or_default();
+                    cache.fields.push(name);
+                    shared_items.push(Vec::new());
+                }
+                b"sharedItems" => in_shared_items = true,
+                _ if in_shared_items => {
+                    if let (Some(text), Some(items)) =
+                        (pivot_value_text(e), shared_items.last_mut())
+                    {
+                        items.push(text);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::End(ref e)) if e.local_name().as_ref() == b"sharedItems" => {
+                in_shared_items = false;
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    (cache, shared_items)
+}
+
+/// Parse pivot cache records, resolving shared item indices (`<x v="0"/>`).
+fn parse_pivot_cache_records(xml: &str, shared_items: &[Vec<String>]) -> Vec<Vec<String>> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut in_record = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => match e.local_name().as_ref() {
+                b"r" => {
+                    records.push(Vec::new());
+                    in_record = true;
+                }
+                b"x" if in_record => {
+                    if let Some(record) = records.last_mut() {
+                        let index: usize = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"v")
+                            .and_then(|a| a.unescape_value().ok()?.parse().ok())
+                            .unwrap_or(0);
+                        let field = record.len();
+                        let text = shared_items
+                            .get(field)
+                            .and_then(|items| items.get(index))
+                            .cloned()
+                            .unwrap_or_default();
+                        record.push(text);
+                    }
+                }
+                _ if in_record => {
+                    if let (Some(text), Some(record)) = (pivot_value_text(e), records.last_mut()) {
+                        record.push(text);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::End(ref e)) if e.local_name().as_ref() == b"r" => in_record = false,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    records
+}
+
+/// Load the pivot tables of a worksheet together with their caches.
+fn load_sheet_pivots(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    sheet_path: &str,
+    rels: &HashMap<String, Relationship>,
+) -> Result<Vec<PivotTable>, ConvertError> {
+    let mut pivots = Vec::new();
+    for pivot_path in related_parts(sheet_path, rels, "/pivotTable") {
+        let Some(xml) = read_zip_text(archive, &pivot_path)? else {
+            continue;
+        };
+        let mut pivot = parse_pivot_table_definition(&xml);
+
+        let pivot_rels = load_sheet_rels(archive, &pivot_path)?;
+        if let Some(cache_path) =
+            related_parts(&pivot_path, &pivot_rels, "/pivotCacheDefinition").first()
+            && let Some(cache_xml) = read_zip_text(archive, cache_path)?
+        {
+            let (mut cache, shared_items) = parse_pivot_cache_definition(&cache_xml);
+            let cache_rels = load_sheet_rels(archive, cache_path)?;
+            if let Some(records_path) =
+                related_parts(cache_path, &cache_rels, "/pivotCacheRecords").first()
+                && let Some(records_xml) = read_zip_text(archive, records_path)?
+            {
+                cache.records = parse_pivot_cache_records(&records_xml, &shared_items);
+            }
+            pivot.cache = cache;
+        }
+        pivots.push(pivot);
+    }
+    Ok(pivots)
+}
+
+/// The `subtotal` functions of a pivot data field (ECMA-376 `ST_DataConsolidateFunction`).
+const PIVOT_SUBTOTALS: [&str; 11] = [
+    "average",
+    "count",
+    "countNums",
+    "max",
+    "min",
+    "product",
+    "stdDev",
+    "stdDevp",
+    "sum",
+    "var",
+    "varp",
+];
+
+/// Running aggregate of one pivot value cell.
+#[derive(Debug, Clone, Default)]
+struct PivotAggregate {
+    count: usize,
+    numbers: Vec<f64>,
+}
+
+impl PivotAggregate {
+    fn add(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.count += 1;
+        if let Ok(number) = text.parse::<f64>() {
+            self.numbers.push(number);
+        }
+    }
+
+    /// The aggregate for a `subtotal` function. Functions outside
+    /// [`PIVOT_SUBTOTALS`] fall back to sum; the caller reports them.
+    fn value(&self, subtotal: &str) -> String {
+        let numbers = &self.numbers;
+        let n = numbers.len() as f64;
+        let mean = || numbers.iter().sum::<f64>() / n;
+        let squares = || numbers.iter().map(|x| (x - mean()).powi(2)).sum::<f64>();
+        let result = match subtotal {
+            "count" => return self.count.to_string(),
+            "countNums" => return numbers.len().to_string(),
+            _ if numbers.is_empty() => return String::new(),
+            "stdDev" | "var" if numbers.len() < 2 => return "#DIV/0!".to_string(),
+            "average" => mean(),
+            "max" => numbers.iter().copied().fold(f64::MIN, f64::max),
+            "min" => numbers.iter().copied().fold(f64::MAX, f64::min),
+            "product" => numbers.iter().product(),
+            "stdDev" => (squares() / (n - 1.0)).sqrt(),
+            "stdDevp" => (squares() / n).sqrt(),
+            "var" => squares() / (n - 1.0),
+            "varp" => squares() / n,
+            _ => numbers.iter().sum(),
+        };
+        format_float(result)
+    }
+}
+
+/// Aggregate the cached records of a pivot table into `(headers, rows)`: one
+/// row per distinct row-field combination (in first-seen order) and one value
+/// column per column-field combination and data field.
+fn aggregate_pivot(pivot: &PivotTable) -> (Vec<String>, Vec<Vec<String>>) {
+    let records = &pivot.cache.records;
+    let key = |record: &[String], fields: &[usize]| -> Vec<String> {
+        fields
+            .iter()
+            .map(|&f| record.get(f).cloned().unwrap_or_default())
+            .collect()
+    };
+
+    // Distinct keys in first-seen order, with an index for constant-time lookup
+    fn intern(
+        keys: &mut Vec<Vec<String>>,
+        index: &mut HashMap<Vec<String>, usize>,
+        key: Vec<String>,
+    ) -> usize {
+        if let Some(&i) = index.get(&key) {
+            return i;
+        }
+        keys.push(key.clone());
+        index.insert(key, keys.len() - 1);
+        keys.len() - 1
+    }
+
+    let mut row_keys: Vec<Vec<String>> = Vec::new();
+    let mut col_keys: Vec<Vec<String>> = Vec::new();
+    let mut row_index: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut col_index: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut cells: HashMap<(usize, usize, usize), PivotAggregate> = HashMap::new();
+    for record in records {
+        let ri = intern(
+            &mut row_keys,
+            &mut row_index,
+            key(record, &pivot.row_fields),
+        );
+        let ci = intern(
+            &mut col_keys,
+            &mut col_index,
+            key(record, &pivot.col_fields),
+        );
+        for (di, data_field) in pivot.data_fields.iter().enumerate() {
+            let text = record.get(data_field.field).map_or("", String::as_str);
+            cells.entry((ri, ci, di)).or_default().add(text);
+        }
+    }
+
+    let field_name = |f: usize| pivot.cache.fields.get(f).cloned().unwrap_or_default();
+    let mut headers: Vec<String> = pivot.row_fields.iter().map(|&f| field_name(f)).collect();
+    for col_key in &col_keys {
+        for data_field in &pivot.data_fields {
+            headers.push(match (col_key.is_empty(), pivot.data_fields.len()) {
+                (true, _) => data_field.name.clone(),
+                (false, 1) => col_key.join(" / "),
+                (false, _) => format!("{} - {}", col_key.join(" / "), data_field.name),
+            });
+        }
+    }
+
+    let rows = row_keys
+        .iter()
+        .enumerate()
+        .map(|(ri, row_key)| {
+            let mut row = row_key.clone();
+            for ci in 0..col_keys.len() {
+                for (di, data_field) in pivot.data_fields.iter().enumerate() {
+                    row.push(
+                        cells
+                            .get(&(ri, ci, di))
+                            .map(|agg| agg.value(&data_field.subtotal))
+                            .unwrap_or_default(),
+                    );
+                }
+            }
+            row
+        })
+        .collect();
+
+    (headers, rows)
+}
+
+/// Render a pivot table as `(markdown, plain_text)`: its source and fields as a
+/// list, then the values aggregated from the cached records. Without cached
+/// records, `fallback` (the pivot's rendered cells on the sheet) is used.
+fn render_pivot(
+    pivot: &PivotTable,
+    fallback: Option<&SheetTable>,
+    links: &SheetLinks,
+) -> (String, String) {
+    let title = format!("Pivot Table: {}", pivot.name);
+    let mut markdown = format_heading(3, &title);
+    let mut plain = format!("{title}\n");
+
+    let field_names = |fields: &[usize]| {
+        fields
+            .iter()
+            .map(|&f| pivot.cache.fields.get(f).cloned().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let data_names = pivot
+        .data_fields
+        .iter()
+        .map(|d| d.name.clone())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut entries: Vec<(&str, String)> = Vec::new();
+    if let Some(source) = &pivot.cache.source {
+        entries.push(("Source", source.clone()));
+    }
+    for (label, value) in [
+        ("Rows", field_names(&pivot.row_fields)),
+        ("Columns", field_names(&pivot.col_fields)),
+        ("Values", data_names),
+        ("Filters", field_names(&pivot.page_fields)),
+    ] {
+        if !value.is_empty() {
+            entries.push((label, value));
+        }
+    }
+    for (label, value) in &entries {
+        markdown.push_str(&format_list_item(0, false, 0, &format!("{label}: {value}")));
+        markdown.push('\n');
+        plain.push_str(&format!("{label}: {value}\n"));
+    }
+
+    let table = if pivot.cache.records.is_empty() {
+        fallback.map(|table| render_sheet_table(table, links))
+    } else {
+        let (headers, rows) = aggregate_pivot(pivot);
+        (!headers.is_empty()).then(|| {
+            let header_refs: Vec<&str> = headers.iter().map(String::as_str).collect();
+            let row_refs: Vec<Vec<&str>> = rows
+                .iter()
+                .map(|row| row.iter().map(String::as_str).collect())
+                .collect();
+            (
+                build_table(&header_refs, &row_refs),
+                build_table_plain(&header_refs, &row_refs),
+            )
+        })
+    };
+    if let Some((table_md, table_plain)) = table {
+        if !entries.is_empty() {
+            markdown.push('\n');
+            plain.push('\n');
+        }
+        markdown.push_str(&table_md);
+        plain.push_str(&table_plain);
+    }
+
+    (markdown, plain)
+}
+
+// ---- Merged cells ----
+
+/// Read the merged cell ranges of a sheet. Only XLSX and XLS record merges.
+fn merged_regions<RS: std::io::Read + std::io::Seek>(
+    workbook: &mut Sheets<RS>,
+    sheet_name: &str,
+) -> Vec<Dimensions> {
+    match workbook {
+        Sheets::Xlsx(xlsx) => xlsx
+            .worksheet_merge_cells(sheet_name)
+            .and_then(Result::ok)
+            .unwrap_or_default(),
+        Sheets::Xls(xls) => xls.worksheet_merge_cells(sheet_name).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Fill or mark the spanned cells of each merged region in a sheet grid.
+///
+/// `origin` is the absolute `(row, col)` of `grid[0][0]`. Regions are clamped
+/// to the used range, so a merge such as `A1:XFD1` never grows the grid.
+fn apply_merged_regions(
+    grid: &mut [Vec<String>],
+    origin: (u32, u32),
+    merges: &[Dimensions],
+    mode: MergedCells,
+) {
+    if mode == MergedCells::TopLeft {
+        return;
+    }
+    for merge in merges {
+        // Regions starting before the used range have no anchor value to spread
+        if merge.start.0 < origin.0 || merge.start.1 < origin.1 {
+            continue;
+        }
+        let (top, left) = (
+            (merge.start.0 - origin.0) as usize,
+            (merge.start.1 - origin.1) as usize,
+        );
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        if top >= grid.len() || left >= width {
+            continue;
+        }
+        let (bottom, right) = (
+            ((merge.end.0 - origin.0) as usize).min(grid.len() - 1),
+            ((merge.end.1 - origin.1) as usize).min(width - 1),
+        );
+        for row in grid.iter_mut() {
+            row.resize(width, String::new());
+        }
+
+        let anchor = grid[top][left].clone();
+        for (ri, row) in grid.iter_mut().enumerate().take(bottom + 1).skip(top) {
+            for (ci, cell) in row.iter_mut().enumerate().take(right + 1).skip(left) {
+                if (ri, ci) == (top, left) {
+                    continue;
+                }
+                *cell = match mode {
+                    MergedCells::Fill => anchor.clone(),
+                    MergedCells::Mark if ri == top => "←".to_string(),
+                    _ => "↑".to_string(),
+                };
+            }
+        }
+    }
+}
+
+// ---- Internal conversion (parse + image extraction, no resolution) ----
+
+/// Unwrap the result of reading an auxiliary part (styles, relationships,
+/// tables, charts, ...), or report the failure as a warning and fall back to
+/// the empty value, so one bad part does not fail the whole workbook.
+fn or_warn<T: Default>(
+    result: Result<T, ConvertError>,
+    what: &str,
+    sheet: Option<&str>,
+    warnings: &mut Vec<ConversionWarning>,
+) -> T {
+    result.unwrap_or_else(|e| {
+        let message = match sheet {
+            Some(sheet) => format!("failed to read {what} of sheet '{sheet}': {e}"),
+            None => format!("failed to read {what}: {e}"),
+        };
+        warnings.push(ConversionWarning {
+            code: WarningCode::SkippedElement,
+            message,
+            location: sheet.map(str::to_string),
+        });
+        T::default()
+    })
+}
+
+impl XlsxConverter {
+    /// Parse the workbook and extract images without resolving placeholders.
+    ///
+    /// Returns the conversion result (with unresolved placeholders in markdown)
+    /// and pending image data for later resolution (sync or async).
+    pub(crate) fn convert_inner(
+        &self,
+        data: &[u8],
+        options: &ConversionOptions,
+    ) -> Result<(ConversionResult, PendingImageResolution), ConvertError> {
+        // Pre-scan ZIP budget before passing to calamine
+        let mut package = zip::ZipArchive::new(Cursor::new(data)).ok();
+        if let Some(archive) = package.as_mut() {
+            crate::zip_utils::validate_zip_budget(archive, options.max_uncompressed_zip_bytes)?;
+        }
+        let mut warnings = Vec::new();
+        // Worksheet part paths, for details calamine does not expose (XLSX only)
+        let sheet_paths = package
+            .as_mut()
+            .map(resolve_sheet_paths)
+            .unwrap_or_default();
+        let number_formats = match (options.xlsx.cell_values, package.as_mut()) {
+            (CellValues::Formatted, Some(archive)) => or_warn(
+                read_zip_text(archive, "xl/styles.xml"),
+                "styles",
+                None,
+                &mut warnings,
+            )
+            .map(|xml| NumberFormats::parse(&xml)),
+            _ => None,
+        };
+
+        let cursor = Cursor::new(data);
+        let mut workbook = open_workbook_auto_from_rs(cursor)?;
+
+        let sheet_names = workbook.sheet_names().to_owned();
+        let hidden_sheets: HashSet<String> = workbook
+            .sheets_metadata()
+            .iter()
+            .filter(|sheet| sheet.visible != SheetVisible::Visible)
+            .map(|sheet| sheet.name.clone())
+            .collect();
+        let mut sections = Vec::new();
+        let mut plain_sections = Vec::new();
+
+        // Track which sheet index each section corresponds to (for image attachment)
+        let mut section_sheet_indices: Vec<usize> = Vec::new();
+
+        // Threaded comment authors are shared by all sheets; only needed for comments
+        let persons = match package.as_mut() {
+            Some(archive) if options.xlsx.comments => {
+                let targets = or_warn(
+                    read_zip_text(archive, "xl/_rels/workbook.xml.rels"),
+                    "workbook relationships",
+                    None,
+                    &mut warnings,
+                )
+                .map(|xml| related_parts("xl/workbook.xml", &parse_relationships(&xml), "/person"))
+                .unwrap_or_default();
+                let mut persons = HashMap::new();
+                for path in targets {
+                    let xml = or_warn(read_zip_text(archive, &path), &path, None, &mut warnings);
+                    if let Some(xml) = xml {
+                        persons.extend(parse_persons(&xml));
+                    }
+                }
+                persons
+            }
+            _ => HashMap::new(),
+        };
+
+        for (sheet_idx, name) in sheet_names.iter().enumerate() {
+            let sheet_hidden = hidden_sheets.contains(name);
+            if sheet_hidden {
+                warnings.push(hidden_content_warning(
+                    options.xlsx.hidden,
+                    &format!("hidden sheet '{name}'"),
+                    name,
+                ));
+                if options.xlsx.hidden == HiddenContent::Skip {
+                    continue;
+                }
+            }
+
+            let range = match workbook.worksheet_range(name) {
+                Ok(r) => r,
+                Err(e) => {
+                    warnings.push(ConversionWarning {
+                        code: WarningCode::SkippedElement,
+                        message: format!("failed to read sheet '{name}': {e}"),
+                        location: Some(name.clone()),
+                    });
+                    continue;
+                }
+            };
+
+            // Raw worksheet XML and relationships, for details calamine does not expose
+            let (sheet_xml, sheet_rels) = match (sheet_paths.get(name), package.as_mut()) {
+                (Some(path), Some(archive)) => (
+                    or_warn(
+                        read_zip_text(archive, path),
+                        path,
+                        Some(name),
+                        &mut warnings,
+                    ),
+                    or_warn(
+                        load_sheet_rels(archive, path),
+                        "sheet relationships",
+                        Some(name),
+                        &mut warnings,
+                    ),
+                ),
+                _ => (None, HashMap::new()),
+            };
+            let links = sheet_xml
+                .as_deref()
+                .map(|xml| parse_hyperlinks(xml, &sheet_rels))
+                .unwrap_or_default();
+            let cell_styles = match (&number_formats, &sheet_xml) {
+                (Some(_), Some(xml)) => parse_cell_styles(xml),
+                _ => HashMap::new(),
+            };
+
+            // Absolute (row, col) of the range's top-left cell, for cell references
+            let origin = range.start().unwrap_or((0, 0));
+            let mut grid: Vec<Vec<String>> = range
+                .rows()
+                .enumerate()
+                .map(|(ri, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(ci, cell)| {
+                            let loc = format!(
+                                "{}!{}{}",
+                                name,
+                                col_letter(origin.1 as usize + ci),
+                                origin.0 as usize + ri + 1
+                            );
+                            let position = (origin.0 + ri as u32, origin.1 + ci as u32);
+                            number_formats
+                                .as_ref()
+                                .zip(cell_styles.get(&position))
+                                .and_then(|(formats, style)| formats.code_for_style(*style))
+                                .and_then(|code| apply_number_format(cell, code))
+                                .unwrap_or_else(|| format_cell(cell, &loc, &mut warnings))
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let hidden = sheet_xml.as_deref().map(parse_hidden_rows_cols);
+
+            let mut formulas = Vec::new();
+            if options.xlsx.formulas != Formulas::Off {
+                match read_formulas(&mut workbook, name) {
+                    Ok(list) => formulas = list,
+                    Err(e) => warnings.push(ConversionWarning {
+                        code: WarningCode::SkippedElement,
+                        message: format!("failed to read formulas of sheet '{name}': {e}"),
+                        location: Some(name.clone()),
+                    }),
+                }
+                // Formulas of skipped hidden cells stay out of the output too
+                if let Some(hidden) = &hidden
+                    && options.xlsx.hidden == HiddenContent::Skip
+                {
+                    formulas.retain(|((row, col), _)| {
+                        !hidden.rows.contains(row) && !hidden.cols.contains(col)
+                    });
+                }
+            }
+            let merges = if options.xlsx.merged_cells != MergedCells::TopLeft {
+                merged_regions(&mut workbook, name)
+            } else {
+                Vec::new()
+            };
+            apply_merged_regions(&mut grid, origin, &merges, options.xlsx.merged_cells);
+
+            if options.xlsx.formulas == Formulas::Inline {
+                annotate_formulas(&mut grid, origin, &formulas, &merges);
+            }
+
+            if let Some(hidden) = &hidden {
+                let (hidden_rows, hidden_cols) = hidden_in_range(&grid, origin, hidden);
+                if !hidden_rows.is_empty() {
+                    warnings.push(hidden_content_warning(
+                        options.xlsx.hidden,
+                        &format!(
+                            "{} hidden row(s): {}",
+                            hidden_rows.len(),
+                            hidden_rows.join(", ")
+                        ),
+                        name,
+                    ));
+                }
+                if !hidden_cols.is_empty() {
+                    warnings.push(hidden_content_warning(
+                        options.xlsx.hidden,
+                        &format!(
+                            "{} hidden column(s): {}",
+                            hidden_cols.len(),
+                            hidden_cols.join(", ")
+                        ),
+                        name,
+                    ));
+                }
+            }
+
+            // Excel Tables (ListObjects) are rendered as their own titled tables
+            // Charts and pivot tables are rendered after the sheet's tables
+            let (excel_tables, charts, pivots) = match (sheet_paths.get(name), package.as_mut()) {
+                (Some(path), Some(archive)) => (
+                    or_warn(
+                        load_sheet_tables(archive, path, &sheet_rels),
+                        "tables",
+                        Some(name),
+                        &mut warnings,
+                    ),
+                    or_warn(
+                        load_sheet_charts(archive, path, &sheet_rels),
+                        "charts",
+                        Some(name),
+                        &mut warnings,
+                    ),
+                    or_warn(
+                        load_sheet_pivots(archive, path, &sheet_rels),
+                        "pivot tables",
+                        Some(name),
+                        &mut warnings,
+                    ),
+                ),
+                _ => (Vec::new(), Vec::new(), Vec::new()),
+            };
+            for pivot in &pivots {
+                for data_field in &pivot.data_fields {
+                    if !PIVOT_SUBTOTALS.contains(&data_field.subtotal.as_str()) {
+                        warnings.push(ConversionWarning {
+                            code: WarningCode::UnsupportedFeature,
+                            message: format!(
+                                "unsupported subtotal '{}' in pivot table '{}', summed instead",
+                                data_field.subtotal, pivot.name
+                            ),
+                            location: Some(name.clone()),
+                        });
+                    }
+                }
+            }
+            let pivot_areas: Vec<Dimensions> =
+                pivots.iter().filter_map(|pivot| pivot.location).collect();
+            let mut tables = split_sheet_tables(
+                &grid,
+                origin,
+                &excel_tables,
+                &pivot_areas,
+                options.xlsx.detect_regions,
+            );
+            if let Some(hidden) = &hidden {
+                for table in &mut tables {
+                    apply_hidden_rows_cols(table, hidden, options.xlsx.hidden);
+                }
+            }
+            tables.retain(|table| !table.rows.is_empty());
+            if tables.is_empty() && charts.is_empty() && pivots.is_empty() {
+                continue;
+            }
+
+            if options.xlsx.max_rows.is_some() || options.xlsx.max_columns.is_some() {
+                // The row cap is one budget for the sheet, used up by its tables in order
+                let mut rows_left = options.xlsx.max_rows;
+                for table in tables.iter_mut().filter(|table| !table.paragraph) {
+                    table.truncation = truncate_table(
+                        table,
+                        rows_left,
+                        options.xlsx.max_columns,
+                        options.xlsx.row_sampling,
+                    );
+                    if let Some(left) = rows_left.as_mut() {
+                        *left = left.saturating_sub(table.rows.len().saturating_sub(1));
+                    }
+                    if let Some(truncation) = &table.truncation {
+                        let what = match &table.title {
+                            Some(title) => format!("table '{title}' in sheet '{name}'"),
+                            None => format!("sheet '{name}'"),
+                        };
+                        warnings.push(ConversionWarning {
+                            code: WarningCode::ResourceLimitReached,
+                            message: format!("truncated {what}: {}", truncation.details()),
+                            location: Some(name.clone()),
+                        });
+                    }
+                }
+            }
+
+            let title = if sheet_hidden && options.xlsx.hidden == HiddenContent::Mark {
+                format!("{name} (hidden)")
+            } else {
+                name.clone()
+            };
+            let mut section = format_heading(2, &title);
+            let mut plain_section = format!("{title}\n");
+            let blocks = tables
+                .iter()
+                .map(|table| render_sheet_table(table, &links))
+                .chain(
+                    charts
+                        .iter()
+                        .enumerate()
+                        .map(|(i, chart)| render_chart(chart, i + 1)),
+                )
+                .chain(pivots.iter().map(|pivot| {
+                    // Without cached records, fall back to the cells Excel rendered
+                    let is_filled = |r: u32, c: u32| grid_filled(&grid, origin, r, c);
+                    let fallback = pivot
+                        .location
+                        .zip(used_area(&grid, origin))
+                        .and_then(|(area, used)| intersect(area, used))
+                        .and_then(|area| trim_area(&is_filled, area))
+                        .map(|area| SheetTable::from_grid(&grid, origin, area));
+                    render_pivot(pivot, fallback.as_ref(), &links)
+                }));
+            for (i, (block_md, block_plain)) in blocks.enumerate() {
+                if i > 0 {
+                    section.push('\n');
+                    plain_section.push('\n');
+                }
+                section.push_str(&block_md);
+                plain_section.push_str(&block_plain);
+            }
+
+            if options.xlsx.formulas == Formulas::Appendix && !formulas.is_empty() {
+                formulas.sort_by_key(|((row, col), _)| (*row, *col));
+                section.push('\n');
+                section.push_str(&format_heading(3, "Formulas"));
+                plain_section.push_str("\nFormulas\n");
+                for ((row, col), formula) in &formulas {
+                    let cell_ref = format!("{}{}", col_letter(*col as usize), row + 1);
+                    section.push_str(&format!("- `{cell_ref}`: {}\n", code_span(formula)));
+                    plain_section.push_str(&format!("{cell_ref}: {formula}\n"));
+                }
+            }
+
+            if options.xlsx.comments
+                && let (Some(path), Some(archive)) = (sheet_paths.get(name), package.as_mut())
+            {
+                let mut comments = or_warn(
+                    load_sheet_comments(archive, path, &sheet_rels, &persons),
+                    "comments",
+                    Some(name),
+                    &mut warnings,
+                );
+                // Comments on skipped hidden cells stay out of the output too
+                if let Some(hidden) = &hidden
+                    && options.xlsx.hidden == HiddenContent::Skip
+                {
+                    comments.retain(|comment| {
+                        !hidden.rows.contains(&comment.cell.0)
+                            && !hidden.cols.contains(&comment.cell.1)
+                    });
+                }
+                if !comments.is_empty() {
+                    section.push('\n');
+                    section.push_str(&format_heading(3, "Comments"));
+                    plain_section.push_str("\nComments\n");
+                    for comment in &comments {
+                        let level = u8::from(comment.is_reply);
+                        let cell_ref = format!(
+                            "{}{}",
+                            col_letter(comment.cell.1 as usize),
+                            comment.cell.0 + 1
+                        );
+                        let by = if comment.author.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" ({})", comment.author)
+                        };
+                        section.push_str(&format_list_item(
+                            level,
+                            false,
+                            0,
+                            &format!("`{cell_ref}`{by}: {}", comment.text),
+                        ));
+                        section.push('\n');
+                        plain_section.push_str(&format_list_item_plain(
+                            level,
+                            &format!("{cell_ref}{by}: {}", comment.text),
+                        ));
+                        plain_section.push('\n');
+                    }
+                }
+            }
+
+            sections.push(section);
+            plain_sections.push(plain_section);
+
+            section_sheet_indices.push(sheet_idx);
+        }
+
+        // Extract embedded images if requested or if describer needs them
+        let need_image_bytes = options.extract_images || options.image_describer.is_some();
+        let mut images: Vec<(String, Vec<u8>)> = Vec::new();
+        let mut image_bytes_map: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut image_infos: Vec<ImageInfo> = Vec::new();
+        let mut image_counter: usize = 0;
+
+        if need_image_bytes {
+            // Open a fresh ZipArchive (calamine consumed the original cursor)
+            let mut archive = ZipArchive::new(Cursor::new(data))?;
+            let mut total_image_bytes: usize = 0;
+
+            for (section_idx, &sheet_idx) in section_sheet_indices.iter().enumerate() {
+                let sheet_images = extract_sheet_images(&mut archive, sheet_idx);
+
+                let mut image_lines = Vec::new();
+                let mut plain_image_lines = Vec::new();
+                for (filename, img_data) in sheet_images {
+                    total_image_bytes += img_data.len();
+                    if total_image_bytes <= options.max_total_image_bytes {
+                        let placeholder = format!("__img_{n}__", n = image_counter);
+                        let bytes_key = placeholder.clone();
+                        image_counter += 1;
+                        image_infos.push(ImageInfo {
+                            placeholder: placeholder.clone(),
+                            original_alt: String::new(),
+                            filename: filename.clone(),
+                            bytes_key: bytes_key.clone(),
+                        });
+                        image_lines.push(format!("![{placeholder}]({filename})"));
+                        plain_image_lines.push(placeholder);
+                        if options.extract_images {
+                            images.push((filename.clone(), img_data.clone()));
+                        }
+                        image_bytes_map.insert(bytes_key, img_data);
+                    } else {
+                        warnings.push(ConversionWarning {
+                            code: WarningCode::ResourceLimitReached,
+                            message: format!(
+                                "total image bytes exceeded limit ({})",
+                                options.max_total_image_bytes
+                            ),
+                            location: Some(filename),
+                        });
+                    }
+                }
+
+                if !image_lines.is_empty() {
+                    sections[section_idx].push_str(&format!("\n{}", image_lines.join("\n")));
+                    plain_sections[section_idx]
+                        .push_str(&format!("\n{}", plain_image_lines.join("\n")));
+                }
+            }
+        }
+
+        // Workbook summary after the sheets: named ranges, excluding Excel's
+        // internal `_xlnm.` names
+        let named_ranges: Vec<&(String, String)> = workbook
+            .defined_names()
+            .iter()
+            .filter(|(name, _)| !name.starts_with("_xlnm."))
+            .collect();
+        if !named_ranges.is_empty() {
+            let mut summary = format_heading(2, "Named Ranges");
+            let mut plain_summary = String::from("Named Ranges\n");
+            for (name, formula) in named_ranges {
+                summary.push_str(&format!("- {}: {}\n", code_span(name), code_span(formula)));
+                plain_summary.push_str(&format!("{name}: {formula}\n"));
+            }
+            sections.push(summary);
+            plain_sections.push(plain_summary);
+        }
+
+        let markdown = sections.join("\n");
+        let plain_text = plain_sections.join("\n");
+
+        let result = ConversionResult {
+            markdown,
+            plain_text,
+            images,
+            warnings,
+            ..Default::default()
+        };
+
+        let pending = PendingImageResolution {
+            infos: image_infos,
+            bytes: image_bytes_map,
+        };
+
+        Ok((result, pending))
+    }
+}
+
+// ---- Converter trait impl ----
+
+impl Converter for XlsxConverter {
+    fn supported_extensions(&self) -> &[&str] {
+        &["xlsx", "xls", "xlsb", "ods"]
+    }
+
+    fn convert(
+        &self,
+        data: &[u8],
+        options: &ConversionOptions,
+    ) -> Result<ConversionResult, ConvertError> {
+        let (mut result, pending) = self.convert_inner(data, options)?;
+        resolve_image_placeholders(
+            &mut result.markdown,
+            &mut result.plain_text,
+            &pending.infos,
+            &pending.bytes,
+            options.image_describer.as_deref(),
+            &mut result.warnings,
+        );
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::XlsxOptions;
+
+    // -- Helper: build minimal XLSX bytes from sheet definitions --
+
+    /// Cell value for test XLSX generation.
+    enum TestCell {
+        Str(&'static str),
+        Num(f64),
+        Bool(bool),
+        Empty,
+    }
+
+    /// Convert a 0-based column index to an Excel column letter (A-Z) for test XML.
+    fn test_col_letter(col: usize) -> char {
+        (b'A' + col as u8) as char
+    }
+
+    /// Build a minimal XLSX file in memory from the given sheet definitions.
+    fn build_test_xlsx(sheets: &[(&str, &[&[TestCell]])]) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let buf = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(buf));
+        let opts = SimpleFileOptions::default();
+
+        // [Content_Types].xml
+        let mut ct = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+             <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+             <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
+        );
+        for (i, _) in sheets.iter().enumerate() {
+            ct.push_str(&format!(
+                "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
+                 ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+                i + 1
+            ));
+        }
+        ct.push_str("</Types>");
+        zip.start_file("[Content_Types].xml", opts).unwrap();
+        zip.write_all(ct.as_bytes()).unwrap();
+
+        // _rels/.rels
+        zip.start_file("_rels/.rels", opts).unwrap();
+        zip.write_all(
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+              <Relationship Id=\"rId1\" \
+              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" \
+              Target=\"xl/workbook.xml\"/>\
+              </Relationships>",
+        )
+        .unwrap();
+
+        // xl/workbook.xml
+        let mut wb = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+             <sheets>",
+        );
+        for (i, (name, _)) in sheets.iter().enumerate() {
+            wb.push_str(&format!(
+                "<sheet name=\"{name}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
+                i + 1,
+                i + 1
+            ));
+        }
+        wb.push_str("</sheets></workbook>");
+        zip.start_file("xl/workbook.xml", opts).unwrap();
+        zip.write_all(wb.as_bytes()).unwrap();
+
+        // xl/_rels/workbook.xml.rels
+        let mut rels = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+        );
+        for (i, _) in sheets.iter().enumerate() {
+            rels.push_str(&format!(
+                "<Relationship Id=\"rId{}\" \
+                 Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
+                 Target=\"worksheets/sheet{}.xml\"/>",
+                i + 1,
+                i + 1
+            ));
+        }
+        rels.push_str("</Relationships>");
+        zip.start_file("xl/_rels/workbook.xml.rels", opts).unwrap();
+        zip.write_all(rels.as_bytes()).unwrap();
+
+        // Each worksheet
+        for (i, (_, rows)) in sheets.iter().enumerate() {
+            let mut ws = String::from(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+                 <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+                 <sheetData>",
+            );
+            for (ri, row) in rows.iter().enumerate() {
+                ws.push_str(&format!("<row r=\"{}\">", ri + 1));
+                for (ci, cell) in row.iter().enumerate() {
+                    let col = test_col_letter(ci);
+                    let r = ri + 1;
+                    match cell {
+                        TestCell::Str(s) => {
+                            let escaped = s
+                                .replace('&', "&amp;")
+                                .replace('<', "&lt;")
+                                .replace('>', "&gt;")
+                                .replace('"', "&quot;");
+                            ws.push_str(&format!(
+                                "<c r=\"{col}{r}\" t=\"inlineStr\"><is><t>{escaped}</t></is></c>"
+                            ));
+                        }
+                        TestCell::Num(f) => {
+                            ws.push_str(&format!("<c r=\"{col}{r}\"><v>{f}</v></c>"));
+                        }
+                        TestCell::Bool(b) => {
+                            let v = if *b { 1 } else { 0 };
+                            ws.push_str(&format!("<c r=\"{col}{r}\" t=\"b\"><v>{v}</v></c>"));
+                        }
+                        TestCell::Empty => {}
+                    }
+                }
+                ws.push_str("</row>");
+            }
+            ws.push_str("</sheetData></worksheet>");
+            zip.start_file(format!("xl/worksheets/sheet{}.xml", i + 1), opts)
+                .unwrap();
+            zip.write_all(ws.as_bytes()).unwrap();
+        }
+
+        let cursor = zip.finish().unwrap();
+        cursor.into_inner()
+    }
+
+    // -- Tests --
+
+    #[test]
+    fn test_xlsx_supported_extensions() {
+        let converter = XlsxConverter;
+        assert_eq!(
+            converter.supported_extensions(),
+            &["xlsx", "xls", "xlsb", "ods"]
+        );
+    }
+
+    #[test]
+    fn test_xlsx_can_convert() {
+        let converter = XlsxConverter;
+        assert!(converter.can_convert("xlsx", &[]));
+        assert!(!converter.can_convert("csv", &[]));
+        assert!(!converter.can_convert("json", &[]));
+    }
+
+    #[test]
+    fn test_xls_supported_extension() {
+        let converter = XlsxConverter;
+        assert!(converter.can_convert("xls", &[]));
+    }
+
+    #[test]
+    fn test_xls_not_confused_with_other_formats() {
+        let converter = XlsxConverter;
+        assert!(!converter.can_convert("csv", &[]));
+        assert!(!converter.can_convert("json", &[]));
+        assert!(!converter.can_convert("docx", &[]));
+        assert!(!converter.can_convert("pptx", &[]));
+    }
+
+    #[test]
+    fn test_xlsx_empty_workbook() {
+        let data = build_test_xlsx(&[("Sheet1", &[])]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert_eq!(result.markdown, "");
+    }
+
+    #[test]
+    fn test_xlsx_single_sheet_basic() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[(
+            "Sheet1",
+            &[
+                &[Str("Name"), Str("Age")][..],
+                &[Str("Alice"), Num(30.0)],
+                &[Str("Bob"), Num(25.0)],
+            ],
+        )]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## Sheet1"));
+        assert!(result.markdown.contains("| Name | Age |"));
+        assert!(result.markdown.contains("| Alice | 30 |"));
+        assert!(result.markdown.contains("| Bob | 25 |"));
+    }
+
+    #[test]
+    fn test_xlsx_multiple_sheets() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[
+            ("First", &[&[Str("A")][..], &[Str("1")]]),
+            ("Second", &[&[Str("B")][..], &[Str("2")]]),
+        ]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## First"));
+        assert!(result.markdown.contains("| A |"));
+        assert!(result.markdown.contains("| 1 |"));
+        assert!(result.markdown.contains("## Second"));
+        assert!(result.markdown.contains("| B |"));
+        assert!(result.markdown.contains("| 2 |"));
+    }
+
+    #[test]
+    fn test_xlsx_empty_sheet_skipped() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[("HasData", &[&[Str("X")][..], &[Str("1")]]), ("Empty", &[])]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## HasData"));
+        assert!(!result.markdown.contains("## Empty"));
+    }
+
+    #[test]
+    fn test_xlsx_header_only_sheet() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[("Sheet1", &[&[Str("Col1"), Str("Col2")][..]])]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## Sheet1"));
+        assert!(result.markdown.contains("| Col1 | Col2 |"));
+        assert!(result.markdown.contains("|---|---|"));
+        // Verify no data rows after separator
+        let sep_pos = result.markdown.find("|---|---|").unwrap();
+        let after_sep = &result.markdown[sep_pos + "|---|---|".len()..];
+        assert!(
+            !after_sep.trim().contains('|'),
+            "expected no data rows after separator"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_numeric_cells() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[(
+            "Numbers",
+            &[
+                &[Str("Int"), Str("Float"), Str("Whole")][..],
+                &[Num(42.0), Num(3.25), Num(100.0)],
+            ],
+        )]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("| 42 |"));
+        assert!(result.markdown.contains("3.25"));
+        assert!(result.markdown.contains("| 100 |"));
+    }
+
+    #[test]
+    fn test_xlsx_bool_cells() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[(
+            "Bools",
+            &[&[Str("Value")][..], &[Bool(true)], &[Bool(false)]],
+        )]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("| TRUE |"));
+        assert!(result.markdown.contains("| FALSE |"));
+    }
+
+    #[test]
+    fn test_xlsx_unicode_cjk() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[(
+            "CJK",
+            &[
+                &[Str("한국어"), Str("中文"), Str("日本語")][..],
+                &[Str("서울"), Str("北京"), Str("東京")],
+            ],
+        )]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("한국어"));
+        assert!(result.markdown.contains("中文"));
+        assert!(result.markdown.contains("日本語"));
+        assert!(result.markdown.contains("서울"));
+        assert!(result.markdown.contains("北京"));
+        assert!(result.markdown.contains("東京"));
+    }
+
+    #[test]
+    fn test_xlsx_emoji() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[(
+            "Emoji",
+            &[&[Str("Icon")][..], &[Str("🚀")], &[Str("✨")], &[Str("🌍")]],
+        )]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("🚀"));
+        assert!(result.markdown.contains("✨"));
+        assert!(result.markdown.contains("🌍"));
+    }
+
+    #[test]
+    fn test_xlsx_mixed_types() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[(
+            "Mixed",
+            &[
+                &[Str("Str"), Str("Num"), Str("Bool"), Str("Empty")][..],
+                &[Str("hello"), Num(42.0), Bool(true), Empty],
+            ],
+        )]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("| hello | 42 | TRUE |  |"));
+    }
+
+    #[test]
+    fn test_xlsx_uneven_row_lengths() {
+        use TestCell::*;
+        // Row 1 has 3 cols (header), row 2 has 2 cols, row 3 has 4 cols
+        // calamine pads shorter rows with Empty, truncates longer rows to header width
+        let data = build_test_xlsx(&[(
+            "Sheet1",
+            &[
+                &[Str("A"), Str("B"), Str("C")][..],
+                &[Str("1"), Str("2")],
+                &[Str("x"), Str("y"), Str("z")],
+            ],
+        )]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        // Header row should be intact
+        assert!(result.markdown.contains("| A | B | C |"));
+        // Short row: calamine returns fewer cells, build_table pads
+        assert!(result.markdown.contains("1"));
+        assert!(result.markdown.contains("2"));
+        // Full row should be intact
+        assert!(result.markdown.contains("| x | y | z |"));
+    }
+
+    #[test]
+    fn test_xlsx_zip_budget_exceeded_returns_error() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[("Sheet1", &[&[Str("A")][..], &[Str("1")]])]);
+        let converter = XlsxConverter;
+        let options = ConversionOptions {
+            max_uncompressed_zip_bytes: 1, // impossibly small
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options);
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(
+            format!("{err}").contains("exceeds limit"),
+            "error was: {err}"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_no_title_images() {
+        use TestCell::*;
+        let data = build_test_xlsx(&[("Sheet1", &[&[Str("A")][..], &[Str("1")]])]);
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(result.title.is_none());
+        assert!(result.images.is_empty());
+    }
+
+    #[test]
+    fn test_xlsx_invalid_data_returns_error() {
+        let converter = XlsxConverter;
+        let result = converter.convert(b"not a valid xlsx file", &ConversionOptions::default());
+        assert!(result.is_err());
+    }
+
+    // -- col_letter tests --
+
+    #[test]
+    fn test_col_letter_single() {
+        assert_eq!(col_letter(0), "A");
+        assert_eq!(col_letter(1), "B");
+        assert_eq!(col_letter(25), "Z");
+    }
+
+    #[test]
+    fn test_col_letter_multi() {
+        assert_eq!(col_letter(26), "AA");
+        assert_eq!(col_letter(27), "AB");
+        assert_eq!(col_letter(51), "AZ");
+        assert_eq!(col_letter(52), "BA");
+        assert_eq!(col_letter(701), "ZZ");
+        assert_eq!(col_letter(702), "AAA");
+    }
+
+    // -- Error cell tests --
+
+    #[test]
+    fn test_xlsx_format_cell_error_displays_text() {
+        let mut warnings = Vec::new();
+        let cell = Data::Error(calamine::CellErrorType::Div0);
+        let result = format_cell(&cell, "Sheet1!A1", &mut warnings);
+        assert!(
+            result.contains("DIV"),
+            "expected error text containing 'DIV', got: {result}"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_format_cell_error_na() {
+        let mut warnings = Vec::new();
+        let cell = Data::Error(calamine::CellErrorType::NA);
+        let result = format_cell(&cell, "Sheet1!B2", &mut warnings);
+        assert!(
+            result.contains("N/A"),
+            "expected error text containing 'N/A', got: {result}"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_format_cell_error_emits_warning() {
+        let mut warnings = Vec::new();
+        let cell = Data::Error(calamine::CellErrorType::Div0);
+        format_cell(&cell, "Sheet1!C3", &mut warnings);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::MalformedSegment);
+        assert_eq!(warnings[0].location.as_deref(), Some("Sheet1!C3"));
+        assert!(warnings[0].message.contains("error"));
+    }
+
+    // -- DateTime formatting tests --
+
+    #[test]
+    fn test_xlsx_format_cell_datetime_date_only() {
+        use calamine::ExcelDateTimeType;
+        let mut warnings = Vec::new();
+        // Excel serial date for 2024-01-15 = 45306
+        let dt = Data::DateTime(calamine::ExcelDateTime::new(
+            45306.0,
+            ExcelDateTimeType::DateTime,
+            false,
+        ));
+        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
+        assert!(warnings.is_empty());
+        assert_eq!(result, "2024-01-15");
+    }
+
+    #[test]
+    fn test_xlsx_format_cell_datetime_full() {
+        use calamine::ExcelDateTimeType;
+        let mut warnings = Vec::new();
+        // 45306.5 = 2024-01-15 12:00:00
+        let dt = Data::DateTime(calamine::ExcelDateTime::new(
+            45306.5,
+            ExcelDateTimeType::DateTime,
+            false,
+        ));
+        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
+        assert!(warnings.is_empty());
+        assert_eq!(result, "2024-01-15 12:00:00");
+    }
+
+    #[test]
+    fn test_xlsx_format_cell_datetime_with_time() {
+        use calamine::ExcelDateTimeType;
+        let mut warnings = Vec::new();
+        // 45306.0 + 14h30m15s = 45306 + (14*3600+30*60+15)/86400
+        let fractional = (14.0 * 3600.0 + 30.0 * 60.0 + 15.0) / 86400.0;
+        let dt = Data::DateTime(calamine::ExcelDateTime::new(
+            45306.0 + fractional,
+            ExcelDateTimeType::DateTime,
+            false,
+        ));
+        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
+        assert!(warnings.is_empty());
+        assert_eq!(result, "2024-01-15 14:30:15");
+    }
+
+    #[test]
+    fn test_xlsx_format_cell_datetime_time_only() {
+        use calamine::ExcelDateTimeType;
+        let mut warnings = Vec::new();
+        // Time-only: fractional day < 1.0, e.g. 0.5 = 12:00:00
+        let dt = Data::DateTime(calamine::ExcelDateTime::new(
+            0.5,
+            ExcelDateTimeType::TimeDelta,
+            false,
+        ));
+        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
+        assert!(warnings.is_empty());
+        // Could be either "1899-12-30 12:00:00" or "12:00:00" depending on calamine behavior
+        assert!(
+            result.contains("12:00:00"),
+            "expected time 12:00:00 in output, got: {result}"
+        );
+    }
+
+    // -- Image extraction tests --
+
+    use crate::converter::ImageDescriber;
+    use std::sync::Arc;
+
+    struct MockDescriber {
+        description: String,
+    }
+
+    impl ImageDescriber for MockDescriber {
+        fn describe(
+            &self,
+            _image_bytes: &[u8],
+            _mime_type: &str,
+            _prompt: &str,
+        ) -> Result<String, ConvertError> {
+            Ok(self.description.clone())
+        }
+    }
+
+    struct FailingDescriber;
+
+    impl ImageDescriber for FailingDescriber {
+        fn describe(
+            &self,
+            _image_bytes: &[u8],
+            _mime_type: &str,
+            _prompt: &str,
+        ) -> Result<String, ConvertError> {
+            Err(ConvertError::ImageDescriptionError {
+                reason: "API error".to_string(),
+            })
+        }
+    }
+
+    /// Build a minimal XLSX file with an embedded image in sheet 1.
+    ///
+    /// The ZIP contains:
+    /// - Standard XLSX structure (workbook, sheet with data)
+    /// - `xl/worksheets/_rels/sheet1.xml.rels` → points to `../drawings/drawing1.xml`
+    /// - `xl/drawings/drawing1.xml` → contains `<a:blip r:embed="rId1"/>`
+    /// - `xl/drawings/_rels/drawing1.xml.rels` → points to `../media/image1.png`
+    /// - `xl/media/image1.png` → fake image bytes
+    fn build_test_xlsx_with_image(
+        sheets: &[(&str, &[&[TestCell]])],
+        image_filename: &str,
+        image_data: &[u8],
+    ) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let buf = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(buf));
+        let opts = SimpleFileOptions::default();
+
+        // [Content_Types].xml — add image content type
+        let mut ct = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+             <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+             <Default Extension=\"png\" ContentType=\"image/png\"/>\
+             <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
+        );
+        for (i, _) in sheets.iter().enumerate() {
+            ct.push_str(&format!(
+                "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
+                 ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+                i + 1
+            ));
+        }
+        ct.push_str("</Types>");
+        zip.start_file("[Content_Types].xml", opts).unwrap();
+        zip.write_all(ct.as_bytes()).unwrap();
+
+        // _rels/.rels
+        zip.start_file("_rels/.rels", opts).unwrap();
+        zip.write_all(
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+              <Relationship Id=\"rId1\" \
+              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" \
+              Target=\"xl/workbook.xml\"/>\
+              </Relationships>",
+        )
+        .unwrap();
+
+        // xl/workbook.xml
+        let mut wb = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+             <sheets>",
+        );
+        for (i, (name, _)) in sheets.iter().enumerate() {
+            wb.push_str(&format!(
+                "<sheet name=\"{name}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
+                i + 1,
+                i + 1
+            ));
+        }
+        wb.push_str("</sheets></workbook>");
+        zip.start_file("xl/workbook.xml", opts).unwrap();
+        zip.write_all(wb.as_bytes()).unwrap();
+
+        // xl/_rels/workbook.xml.rels
+        let mut rels = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+        );
+        for (i, _) in sheets.iter().enumerate() {
+            rels.push_str(&format!(
+                "<Relationship Id=\"rId{}\" \
+                 Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
+                 Target=\"worksheets/sheet{}.xml\"/>",
+                i + 1,
+                i + 1
+            ));
+        }
+        rels.push_str("</Relationships>");
+        zip.start_file("xl/_rels/workbook.xml.rels", opts).unwrap();
+        zip.write_all(rels.as_bytes()).unwrap();
+
+        // Each worksheet
+        for (i, (_, rows)) in sheets.iter().enumerate() {
+            let mut ws = String::from(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+                 <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+                 <sheetData>",
+            );
+            for (ri, row) in rows.iter().enumerate() {
+                ws.push_str(&format!("<row r=\"{}\">", ri + 1));
+                for (ci, cell) in row.iter().enumerate() {
+                    let col = test_col_letter(ci);
+                    let r = ri + 1;
+                    match cell {
+                        TestCell::Str(s) => {
+                            let escaped = s
+                                .replace('&', "&amp;")
+                                .replace('<', "&lt;")
+                                .replace('>', "&gt;")
+                                .replace('"', "&quot;");
+                            ws.push_str(&format!(
+                                "<c r=\"{col}{r}\" t=\"inlineStr\"><is><t>{escaped}</t></is></c>"
+                            ));
+                        }
+                        TestCell::Num(f) => {
+                            ws.push_str(&format!("<c r=\"{col}{r}\"><v>{f}</v></c>"));
+                        }
+                        TestCell::Bool(b) => {
+                            let v = if *b { 1 } else { 0 };
+                            ws.push_str(&format!("<c r=\"{col}{r}\" t=\"b\"><v>{v}</v></c>"));
+                        }
+                        TestCell::Empty => {}
+                    }
+                }
+                ws.push_str("</row>");
+            }
+            ws.push_str("</sheetData></worksheet>");
+            zip.start_file(format!("xl/worksheets/sheet{}.xml", i + 1), opts)
+                .unwrap();
+            zip.write_all(ws.as_bytes()).unwrap();
+        }
+
+        // Sheet 1 rels — point to drawing1.xml
+        zip.start_file("xl/worksheets/_rels/sheet1.xml.rels", opts)
+            .unwrap();
+        zip.write_all(
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+              <Relationship Id=\"rId1\" \
+              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing\" \
+              Target=\"../drawings/drawing1.xml\"/>\
+              </Relationships>",
+        )
+        .unwrap();
+
+        // xl/drawings/drawing1.xml — contains a blip reference
+        let drawing_xml = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <xdr:wsDr xmlns:xdr=\"http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing\" \
+             xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
+             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+             <xdr:twoCellAnchor>\
+             <xdr:pic>\
+             <xdr:nvPicPr><xdr:cNvPr id=\"1\" name=\"Picture 1\"/><xdr:cNvPicPr/></xdr:nvPicPr>\
+             <xdr:blipFill><a:blip r:embed=\"rId1\"/></xdr:blipFill>\
+             </xdr:pic>\
+             </xdr:twoCellAnchor>\
+             </xdr:wsDr>";
+        zip.start_file("xl/drawings/drawing1.xml", opts).unwrap();
+        zip.write_all(drawing_xml.as_bytes()).unwrap();
+
+        // xl/drawings/_rels/drawing1.xml.rels — resolve blip rId1 to media file
+        let drawing_rels = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" \
+             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" \
+             Target=\"../media/{image_filename}\"/>\
+             </Relationships>"
+        );
+        zip.start_file("xl/drawings/_rels/drawing1.xml.rels", opts)
+            .unwrap();
+        zip.write_all(drawing_rels.as_bytes()).unwrap();
+
+        // xl/media/image1.png — actual image bytes
+        zip.start_file(format!("xl/media/{image_filename}"), opts)
+            .unwrap();
+        zip.write_all(image_data).unwrap();
+
+        let cursor = zip.finish().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_xlsx_image_extraction_disabled_by_default() {
+        use TestCell::*;
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
+        let converter = XlsxConverter;
+        let result = converter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        // Default options: extract_images=false, image_describer=None
+        assert!(result.images.is_empty());
+        assert!(
+            !result.markdown.contains("!["),
+            "markdown should not contain image refs by default: {}",
+            result.markdown
+        );
+    }
+
+    #[test]
+    fn test_xlsx_image_extraction_with_extract_images() {
+        use TestCell::*;
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
+        let converter = XlsxConverter;
+        let options = ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        assert_eq!(result.images.len(), 1);
+        assert_eq!(result.images[0].0, "image1.png");
+        assert_eq!(result.images[0].1, b"fake-png-data");
+    }
+
+    #[test]
+    fn test_xlsx_image_in_markdown() {
+        use TestCell::*;
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
+        let converter = XlsxConverter;
+        let options = ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        assert!(
+            result.markdown.contains("![](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+        // Image refs should appear after the table
+        assert!(result.markdown.contains("## Sheet1"));
+    }
+
+    #[test]
+    fn test_xlsx_image_describer_replaces_alt_text() {
+        use TestCell::*;
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
+        let converter = XlsxConverter;
+        let options = ConversionOptions {
+            image_describer: Some(Arc::new(MockDescriber {
+                description: "A chart showing sales data".to_string(),
+            })),
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("![A chart showing sales data](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+        // Without extract_images, images vec should be empty
+        assert!(result.images.is_empty());
+    }
+
+    #[test]
+    fn test_xlsx_image_describer_error_keeps_original() {
+        use TestCell::*;
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
+        let converter = XlsxConverter;
+        let options = ConversionOptions {
+            image_describer: Some(Arc::new(FailingDescriber)),
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        // Original empty alt text should be preserved
+        assert!(
+            result.markdown.contains("![](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
+        // Should have a warning about the failure
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code == WarningCode::SkippedElement
+                    && w.message.contains("image description failed"))
+        );
+    }
+
+    #[test]
+    fn test_xlsx_image_byte_budget_enforced() {
+        use TestCell::*;
+        // Create an image that exceeds a small budget
+        let large_image = vec![0u8; 1000];
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            &large_image,
+        );
+        let converter = XlsxConverter;
+        let options = ConversionOptions {
+            extract_images: true,
+            max_total_image_bytes: 500, // budget smaller than image
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        // Image should not be extracted (exceeds budget)
+        assert!(result.images.is_empty());
+        // Should have a ResourceLimitReached warning
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code == WarningCode::ResourceLimitReached)
+        );
+    }
+
+    // -- Helper function unit tests --
+
+    #[test]
+    fn test_parse_relationships_basic() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+            <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+            <Relationship Id="rId1" Target="../drawings/drawing1.xml"
+             Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing"/>
+            </Relationships>"#;
+        let rels = parse_relationships(xml);
+        assert_eq!(
+            rels.get("rId1").map(|r| r.target.as_str()),
+            Some("../drawings/drawing1.xml")
+        );
+    }
+
+    #[test]
+    fn test_parse_drawing_blips_basic() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+            <xdr:wsDr xmlns:xdr="http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing"
+                      xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+                      xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+            <xdr:twoCellAnchor>
+            <xdr:pic><xdr:blipFill><a:blip r:embed="rId1"/></xdr:blipFill></xdr:pic>
+            </xdr:twoCellAnchor>
+            <xdr:oneCellAnchor>
+            <xdr:pic><xdr:blipFill><a:blip r:embed="rId2"/></xdr:blipFill></xdr:pic>
+            </xdr:oneCellAnchor>
+            </xdr:wsDr>"#;
+        let blips = parse_drawing_blips(xml);
+        assert_eq!(blips, vec!["rId1", "rId2"]);
+    }
+
+    #[test]
+    fn test_derive_rels_path() {
+        assert_eq!(
+            derive_rels_path("xl/drawings/drawing1.xml"),
+            "xl/drawings/_rels/drawing1.xml.rels"
+        );
+        assert_eq!(
+            derive_rels_path("xl/worksheets/sheet1.xml"),
+            "xl/worksheets/_rels/sheet1.xml.rels"
+        );
+        assert_eq!(derive_rels_path("file.xml"), "_rels/file.xml.rels");
+    }
+
+    #[test]
+    fn test_resolve_relative_path_parent_dir() {
+        assert_eq!(
+            resolve_relative_path("xl/drawings", "../media/image1.png"),
+            "xl/media/image1.png"
+        );
+    }
+
+    #[test]
+    fn test_resolve_relative_path_same_dir() {
+        assert_eq!(
+            resolve_relative_path("xl/drawings", "image1.png"),
+            "xl/drawings/image1.png"
+        );
+    }
+
+    #[test]
+    fn test_resolve_relative_path_empty_base() {
+        assert_eq!(
+            resolve_relative_path("", "media/image1.png"),
+            "media/image1.png"
+        );
+    }
+
+    // -- Absolute path (openpyxl-style) tests --
+
+    /// Build a minimal XLSX with absolute paths in rels (openpyxl style).
+    ///
+    /// Uses `/xl/drawings/drawing1.xml` and `/xl/media/image1.png` instead of
+    /// relative `../drawings/...` and `../media/...` paths.
+    fn build_test_xlsx_with_image_absolute_paths(
+        sheets: &[(&str, &[&[TestCell]])],
+        image_filename: &str,
+        image_data: &[u8],
+    ) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let buf = Vec::new();
+        let mut zip = ZipWriter::new(Cursor::new(buf));
         let opts = SimpleFileOptions::default();
 
         // [Content_Types].xml
@@ -463,6 +4312,7 @@ mod tests {
              <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
              <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
              <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+             <Default Extension=\"png\" ContentType=\"image/png\"/>\
              <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
         );
         for (i, _) in sheets.iter().enumerate() {
@@ -565,1132 +4415,1732 @@ mod tests {
             zip.write_all(ws.as_bytes()).unwrap();
         }
 
-        let cursor = zip.finish().unwrap();
-        cursor.into_inner()
-    }
-
-    // -- Tests --
-
-    #[test]
-    fn test_xlsx_supported_extensions() {
-        let converter = XlsxConverter;
-        assert_eq!(converter.supported_extensions(), &["xlsx", "xls"]);
-    }
-
-    #[test]
-    fn test_xlsx_can_convert() {
-        let converter = XlsxConverter;
-        assert!(converter.can_convert("xlsx", &[]));
-        assert!(!converter.can_convert("csv", &[]));
-        assert!(!converter.can_convert("json", &[]));
-    }
-
-    #[test]
-    fn test_xls_supported_extension() {
-        let converter = XlsxConverter;
-        assert!(converter.can_convert("xls", &[]));
-    }
+        // Sheet 1 rels — absolute path to drawing (openpyxl style)
+        zip.start_file("xl/worksheets/_rels/sheet1.xml.rels", opts)
+            .unwrap();
+        zip.write_all(
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+              <Relationship Id=\"rId1\" \
+              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing\" \
+              Target=\"/xl/drawings/drawing1.xml\"/>\
+              </Relationships>",
+        )
+        .unwrap();
 
-    #[test]
-    fn test_xls_not_confused_with_other_formats() {
-        let converter = XlsxConverter;
-        assert!(!converter.can_convert("csv", &[]));
-        assert!(!converter.can_convert("json", &[]));
-        assert!(!converter.can_convert("docx", &[]));
-        assert!(!converter.can_convert("pptx", &[]));
-    }
+        // xl/drawings/drawing1.xml
+        zip.start_file("xl/drawings/drawing1.xml", opts).unwrap();
+        zip.write_all(
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <xdr:wsDr xmlns:xdr=\"http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing\" \
+             xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
+             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+             <xdr:twoCellAnchor>\
+             <xdr:pic>\
+             <xdr:nvPicPr><xdr:cNvPr id=\"1\" name=\"Picture 1\"/><xdr:cNvPicPr/></xdr:nvPicPr>\
+             <xdr:blipFill><a:blip r:embed=\"rId1\"/></xdr:blipFill>\
+             </xdr:pic>\
+             </xdr:twoCellAnchor>\
+             </xdr:wsDr>",
+        )
+        .unwrap();
 
-    #[test]
-    fn test_xlsx_empty_workbook() {
-        let data = build_test_xlsx(&[("Sheet1", &[])]);
-        let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
+        // xl/drawings/_rels/drawing1.xml.rels — absolute path to media (openpyxl style)
+        let drawing_rels = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" \
+             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" \
+             Target=\"/xl/media/{image_filename}\"/>\
+             </Relationships>"
+        );
+        zip.start_file("xl/drawings/_rels/drawing1.xml.rels", opts)
             .unwrap();
-        assert_eq!(result.markdown, "");
-    }
+        zip.write_all(drawing_rels.as_bytes()).unwrap();
 
-    #[test]
-    fn test_xlsx_single_sheet_basic() {
-        use TestCell::*;
-        let data = build_test_xlsx(&[(
-            "Sheet1",
-            &[
-                &[Str("Name"), Str("Age")][..],
-                &[Str("Alice"), Num(30.0)],
-                &[Str("Bob"), Num(25.0)],
-            ],
-        )]);
-        let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
+        // xl/media/image1.png
+        zip.start_file(format!("xl/media/{image_filename}"), opts)
             .unwrap();
-        assert!(result.markdown.contains("## Sheet1"));
-        assert!(result.markdown.contains("| Name | Age |"));
-        assert!(result.markdown.contains("| Alice | 30 |"));
-        assert!(result.markdown.contains("| Bob | 25 |"));
-    }
+        zip.write_all(image_data).unwrap();
 
-    #[test]
-    fn test_xlsx_multiple_sheets() {
-        use TestCell::*;
-        let data = build_test_xlsx(&[
-            ("First", &[&[Str("A")][..], &[Str("1")]]),
-            ("Second", &[&[Str("B")][..], &[Str("2")]]),
-        ]);
-        let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("## First"));
-        assert!(result.markdown.contains("| A |"));
-        assert!(result.markdown.contains("| 1 |"));
-        assert!(result.markdown.contains("## Second"));
-        assert!(result.markdown.contains("| B |"));
-        assert!(result.markdown.contains("| 2 |"));
+        let cursor = zip.finish().unwrap();
+        cursor.into_inner()
     }
 
     #[test]
-    fn test_xlsx_empty_sheet_skipped() {
+    fn test_xlsx_image_extraction_absolute_paths() {
         use TestCell::*;
-        let data = build_test_xlsx(&[("HasData", &[&[Str("X")][..], &[Str("1")]]), ("Empty", &[])]);
+        let data = build_test_xlsx_with_image_absolute_paths(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
         let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("## HasData"));
-        assert!(!result.markdown.contains("## Empty"));
+        let options = ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        assert_eq!(result.images.len(), 1);
+        assert_eq!(result.images[0].0, "image1.png");
+        assert_eq!(result.images[0].1, b"fake-png-data");
     }
 
     #[test]
-    fn test_xlsx_header_only_sheet() {
+    fn test_xlsx_image_in_markdown_absolute_paths() {
         use TestCell::*;
-        let data = build_test_xlsx(&[("Sheet1", &[&[Str("Col1"), Str("Col2")][..]])]);
+        let data = build_test_xlsx_with_image_absolute_paths(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
         let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("## Sheet1"));
-        assert!(result.markdown.contains("| Col1 | Col2 |"));
-        assert!(result.markdown.contains("|---|---|"));
-        // Verify no data rows after separator
-        let sep_pos = result.markdown.find("|---|---|").unwrap();
-        let after_sep = &result.markdown[sep_pos + "|---|---|".len()..];
+        let options = ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
         assert!(
-            !after_sep.trim().contains('|'),
-            "expected no data rows after separator"
+            result.markdown.contains("![](image1.png)"),
+            "markdown was: {}",
+            result.markdown
         );
     }
 
     #[test]
-    fn test_xlsx_numeric_cells() {
+    fn test_xlsx_image_describer_absolute_paths() {
         use TestCell::*;
-        let data = build_test_xlsx(&[(
-            "Numbers",
-            &[
-                &[Str("Int"), Str("Float"), Str("Whole")][..],
-                &[Num(42.0), Num(3.14), Num(100.0)],
-            ],
-        )]);
+        let data = build_test_xlsx_with_image_absolute_paths(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
         let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("| 42 |"));
-        assert!(result.markdown.contains("3.14"));
-        assert!(result.markdown.contains("| 100 |"));
+        let options = ConversionOptions {
+            image_describer: Some(Arc::new(MockDescriber {
+                description: "A chart from openpyxl".to_string(),
+            })),
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("![A chart from openpyxl](image1.png)"),
+            "markdown was: {}",
+            result.markdown
+        );
     }
 
     #[test]
-    fn test_xlsx_bool_cells() {
+    fn test_xlsx_plain_text_contains_image_placeholder() {
         use TestCell::*;
-        let data = build_test_xlsx(&[(
-            "Bools",
-            &[&[Str("Value")][..], &[Bool(true)], &[Bool(false)]],
-        )]);
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
         let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("| TRUE |"));
-        assert!(result.markdown.contains("| FALSE |"));
+        let options = ConversionOptions {
+            extract_images: true,
+            ..Default::default()
+        };
+        // Use convert_inner to inspect pre-resolution state
+        let (result, _pending) = converter.convert_inner(&data, &options).unwrap();
+        // plain_text should contain the placeholder (before resolution)
+        assert!(
+            result.plain_text.contains("__img_0__"),
+            "plain_text should contain the image placeholder, was: {}",
+            result.plain_text
+        );
+        // plain_text should NOT contain image markdown syntax
+        assert!(
+            !result.plain_text.contains("!["),
+            "plain_text should not contain ![ image syntax, was: {}",
+            result.plain_text
+        );
     }
 
     #[test]
-    fn test_xlsx_unicode_cjk() {
+    fn test_xlsx_image_describer_updates_plain_text() {
         use TestCell::*;
-        let data = build_test_xlsx(&[(
-            "CJK",
-            &[
-                &[Str("한국어"), Str("中文"), Str("日本語")][..],
-                &[Str("서울"), Str("北京"), Str("東京")],
-            ],
-        )]);
+        let data = build_test_xlsx_with_image(
+            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
+            "image1.png",
+            b"fake-png-data",
+        );
         let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("한국어"));
-        assert!(result.markdown.contains("中文"));
-        assert!(result.markdown.contains("日本語"));
-        assert!(result.markdown.contains("서울"));
-        assert!(result.markdown.contains("北京"));
-        assert!(result.markdown.contains("東京"));
+        let options = ConversionOptions {
+            image_describer: Some(Arc::new(MockDescriber {
+                description: "A bar chart".to_string(),
+            })),
+            ..Default::default()
+        };
+        let result = converter.convert(&data, &options).unwrap();
+        // plain_text should contain the description
+        assert!(
+            result.plain_text.contains("A bar chart"),
+            "plain_text should contain the image description, was: {}",
+            result.plain_text
+        );
+        // plain_text should NOT contain image markdown syntax
+        assert!(
+            !result.plain_text.contains("!["),
+            "plain_text should not contain ![ image syntax, was: {}",
+            result.plain_text
+        );
     }
 
-    #[test]
-    fn test_xlsx_emoji() {
-        use TestCell::*;
-        let data = build_test_xlsx(&[(
-            "Emoji",
-            &[&[Str("Icon")][..], &[Str("🚀")], &[Str("✨")], &[Str("🌍")]],
-        )]);
-        let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.markdown.contains("🚀"));
-        assert!(result.markdown.contains("✨"));
-        assert!(result.markdown.contains("🌍"));
+    // -- Helper: build XLSX bytes from raw worksheet XML --
+
+    /// Build an XLSX from raw parts. Each sheet is `(name, extra <sheet> attributes,
+    /// worksheet body XML)`; the body goes inside `<worksheet>` (e.g. `<sheetData>`,
+    /// `<mergeCells>`). `parts` adds further package entries.
+    fn build_raw_xlsx(sheets: &[(&str, &str, &str)], parts: &[(&str, &str)]) -> Vec<u8> {
+        build_raw_xlsx_with_names(sheets, "", parts)
     }
 
-    #[test]
-    fn test_xlsx_mixed_types() {
-        use TestCell::*;
-        let data = build_test_xlsx(&[(
-            "Mixed",
-            &[
-                &[Str("Str"), Str("Num"), Str("Bool"), Str("Empty")][..],
-                &[Str("hello"), Num(42.0), Bool(true), Empty],
-            ],
-        )]);
-        let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
+    /// Like [`build_raw_xlsx`], with extra workbook XML after `<sheets>` (e.g. `<definedNames>`).
+    fn build_raw_xlsx_with_names(
+        sheets: &[(&str, &str, &str)],
+        workbook_extra: &str,
+        parts: &[(&str, &str)],
+    ) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let opts = SimpleFileOptions::default();
+
+        let mut wb = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        );
+        let mut rels = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for (i, (name, attrs, body)) in sheets.iter().enumerate() {
+            let n = i + 1;
+            wb.push_str(&format!(
+                r#"<sheet name="{name}" sheetId="{n}" {attrs} r:id="rId{n}"/>"#
+            ));
+            rels.push_str(&format!(
+                r#"<Relationship Id="rId{n}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{n}.xml"/>"#
+            ));
+            zip.start_file(format!("xl/worksheets/sheet{n}.xml"), opts)
+                .unwrap();
+            zip.write_all(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">{body}</worksheet>"#
+                )
+                .as_bytes(),
+            )
             .unwrap();
-        assert!(result.markdown.contains("| hello | 42 | TRUE |  |"));
+        }
+        wb.push_str("</sheets>");
+        wb.push_str(workbook_extra);
+        wb.push_str("</workbook>");
+        if parts
+            .iter()
+            .any(|(path, _)| *path == "xl/persons/person.xml")
+        {
+            rels.push_str(r#"<Relationship Id="rIdPersons" Type="http://schemas.microsoft.com/office/2017/10/relationships/person" Target="persons/person.xml"/>"#);
+        }
+        rels.push_str("</Relationships>");
+
+        zip.start_file("_rels/.rels", opts).unwrap();
+        zip.write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#).unwrap();
+        zip.start_file("xl/workbook.xml", opts).unwrap();
+        zip.write_all(wb.as_bytes()).unwrap();
+        zip.start_file("xl/_rels/workbook.xml.rels", opts).unwrap();
+        zip.write_all(rels.as_bytes()).unwrap();
+        for (path, content) in parts {
+            zip.start_file(path.to_string(), opts).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+
+        zip.finish().unwrap().into_inner()
+    }
+
+    /// Convert with default options except for the given XLSX options.
+    fn convert_xlsx_with(data: &[u8], xlsx: XlsxOptions) -> ConversionResult {
+        let options = ConversionOptions {
+            xlsx,
+            ..Default::default()
+        };
+        XlsxConverter.convert(data, &options).unwrap()
+    }
+
+    /// Inline-string cell XML.
+    fn str_cell(r: &str, text: &str) -> String {
+        format!(r#"<c r="{r}" t="inlineStr"><is><t>{text}</t></is></c>"#)
+    }
+
+    // -- Merged cell tests --
+
+    /// Two-row header block: "Region" spans A1:A2, "Revenue" spans B1:C1.
+    fn merged_header_sheet() -> String {
+        format!(
+            r#"<sheetData><row r="1">{}{}</row><row r="2">{}{}</row><row r="3">{}<c r="B3"><v>10</v></c><c r="C3"><v>12</v></c></row></sheetData><mergeCells count="2"><mergeCell ref="A1:A2"/><mergeCell ref="B1:C1"/></mergeCells>"#,
+            str_cell("A1", "Region"),
+            str_cell("B1", "Revenue"),
+            str_cell("B2", "2023"),
+            str_cell("C2", "2024"),
+            str_cell("A3", "North"),
+        )
     }
 
     #[test]
-    fn test_xlsx_uneven_row_lengths() {
-        use TestCell::*;
-        // Row 1 has 3 cols (header), row 2 has 2 cols, row 3 has 4 cols
-        // calamine pads shorter rows with Empty, truncates longer rows to header width
-        let data = build_test_xlsx(&[(
-            "Sheet1",
-            &[
-                &[Str("A"), Str("B"), Str("C")][..],
-                &[Str("1"), Str("2")],
-                &[Str("x"), Str("y"), Str("z")],
-            ],
-        )]);
-        let converter = XlsxConverter;
-        let result = converter
+    fn test_xlsx_merged_cells_top_left_by_default() {
+        let data = build_raw_xlsx(&[("Report", "", &merged_header_sheet())], &[]);
+        let result = XlsxConverter
             .convert(&data, &ConversionOptions::default())
             .unwrap();
-        // Header row should be intact
-        assert!(result.markdown.contains("| A | B | C |"));
-        // Short row: calamine returns fewer cells, build_table pads
-        assert!(result.markdown.contains("1"));
-        assert!(result.markdown.contains("2"));
-        // Full row should be intact
-        assert!(result.markdown.contains("| x | y | z |"));
+        assert!(
+            result.markdown.contains("| Region | Revenue |  |\n"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("|  | 2023 | 2024 |"));
     }
 
     #[test]
-    fn test_xlsx_zip_budget_exceeded_returns_error() {
-        use TestCell::*;
-        let data = build_test_xlsx(&[("Sheet1", &[&[Str("A")][..], &[Str("1")]])]);
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            max_uncompressed_zip_bytes: 1, // impossibly small
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options);
-        assert!(result.is_err());
-        let err = result.unwrap_err();
+    fn test_xlsx_merged_cells_fill() {
+        let data = build_raw_xlsx(&[("Report", "", &merged_header_sheet())], &[]);
+        let result = convert_xlsx_with(
+            &data,
+            XlsxOptions {
+                merged_cells: MergedCells::Fill,
+                ..Default::default()
+            },
+        );
         assert!(
-            format!("{err}").contains("exceeds limit"),
-            "error was: {err}"
+            result.markdown.contains("| Region | Revenue | Revenue |\n"),
+            "markdown was: {}",
+            result.markdown
         );
+        assert!(result.markdown.contains("| Region | 2023 | 2024 |"));
+        assert!(result.markdown.contains("| North | 10 | 12 |"));
     }
 
     #[test]
-    fn test_xlsx_no_title_images() {
-        use TestCell::*;
-        let data = build_test_xlsx(&[("Sheet1", &[&[Str("A")][..], &[Str("1")]])]);
-        let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        assert!(result.title.is_none());
-        assert!(result.images.is_empty());
+    fn test_xlsx_merged_cells_mark() {
+        let data = build_raw_xlsx(&[("Report", "", &merged_header_sheet())], &[]);
+        let result = convert_xlsx_with(
+            &data,
+            XlsxOptions {
+                merged_cells: MergedCells::Mark,
+                ..Default::default()
+            },
+        );
+        assert!(
+            result.markdown.contains("| Region | Revenue | ← |\n"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("| ↑ | 2023 | 2024 |"));
     }
 
     #[test]
-    fn test_xlsx_invalid_data_returns_error() {
-        let converter = XlsxConverter;
-        let result = converter.convert(b"not a valid xlsx file", &ConversionOptions::default());
-        assert!(result.is_err());
+    fn test_xlsx_apply_merged_regions_clamps_to_used_range() {
+        let mut grid = vec![vec!["Total".to_string(), String::new()]];
+        // A whole-sheet merge must not grow the grid past the used range
+        let merges = [Dimensions::new((0, 0), (1_048_575, 16_383))];
+        apply_merged_regions(&mut grid, (0, 0), &merges, MergedCells::Fill);
+        assert_eq!(grid, vec![vec!["Total".to_string(); 2]]);
     }
 
-    // -- col_letter tests --
-
     #[test]
-    fn test_col_letter_single() {
-        assert_eq!(col_letter(0), "A");
-        assert_eq!(col_letter(1), "B");
-        assert_eq!(col_letter(25), "Z");
+    fn test_xlsx_apply_merged_regions_respects_origin() {
+        // Used range starts at B2; merge B2:C2
+        let mut grid = vec![vec!["Q1".to_string(), String::new()]];
+        let merges = [Dimensions::new((1, 1), (1, 2))];
+        apply_merged_regions(&mut grid, (1, 1), &merges, MergedCells::Mark);
+        assert_eq!(grid, vec![vec!["Q1".to_string(), "←".to_string()]]);
     }
 
-    #[test]
-    fn test_col_letter_multi() {
-        assert_eq!(col_letter(26), "AA");
-        assert_eq!(col_letter(27), "AB");
-        assert_eq!(col_letter(51), "AZ");
-        assert_eq!(col_letter(52), "BA");
-        assert_eq!(col_letter(701), "ZZ");
-        assert_eq!(col_letter(702), "AAA");
+    // -- Hidden content tests --
+
+    /// Three columns (B hidden) and three rows (row 3 hidden).
+    fn hidden_rows_cols_sheet() -> String {
+        format!(
+            r#"<cols><col min="2" max="2" width="0" hidden="1"/></cols><sheetData><row r="1">{}{}{}</row><row r="2">{}{}{}</row><row r="3" hidden="1">{}{}{}</row></sheetData>"#,
+            str_cell("A1", "Item"),
+            str_cell("B1", "Lookup"),
+            str_cell("C1", "Price"),
+            str_cell("A2", "Pen"),
+            str_cell("B2", "x1"),
+            str_cell("C2", "2"),
+            str_cell("A3", "Scratch"),
+            str_cell("B3", "x2"),
+            str_cell("C3", "9"),
+        )
     }
 
-    // -- Error cell tests --
+    fn hidden_workbook() -> Vec<u8> {
+        let visible = format!(
+            "<sheetData><row r=\"1\">{}</row></sheetData>",
+            str_cell("A1", "Visible")
+        );
+        let lookup = format!(
+            "<sheetData><row r=\"1\">{}</row></sheetData>",
+            str_cell("A1", "Secret")
+        );
+        build_raw_xlsx(
+            &[
+                ("Data", "", &hidden_rows_cols_sheet()),
+                ("Summary", "", &visible),
+                ("Lookup", r#"state="veryHidden""#, &lookup),
+            ],
+            &[],
+        )
+    }
 
     #[test]
-    fn test_xlsx_format_cell_error_displays_text() {
-        let mut warnings = Vec::new();
-        let cell = Data::Error(calamine::CellErrorType::Div0);
-        let result = format_cell(&cell, "Sheet1!A1", &mut warnings);
-        assert!(
-            result.contains("DIV"),
-            "expected error text containing 'DIV', got: {result}"
+    fn test_xlsx_parse_hidden_rows_cols() {
+        let hidden = parse_hidden_rows_cols(
+            r#"<worksheet><cols><col min="1" max="1"/><col min="3" max="5" hidden="true"/></cols><sheetData><row r="2" hidden="1"/><row/><row hidden="1"/></sheetData></worksheet>"#,
+        );
+        assert_eq!(hidden.cols, HashSet::from([2, 3, 4]));
+        // Rows without `r` follow the previous row: 2 (index 1), then 3, then 4
+        assert_eq!(hidden.rows, HashSet::from([1, 3]));
+
+        // Column ranges stop at the last worksheet column
+        let hidden = parse_hidden_rows_cols(
+            r#"<worksheet><cols><col min="16380" max="4294967295" hidden="1"/><col min="20000" max="20001" hidden="1"/></cols></worksheet>"#,
         );
+        assert_eq!(hidden.cols, (16379..16384).collect());
     }
 
     #[test]
-    fn test_xlsx_format_cell_error_na() {
-        let mut warnings = Vec::new();
-        let cell = Data::Error(calamine::CellErrorType::NA);
-        let result = format_cell(&cell, "Sheet1!B2", &mut warnings);
-        assert!(
-            result.contains("N/A"),
-            "expected error text containing 'N/A', got: {result}"
+    fn test_xlsx_resolve_sheet_paths() {
+        let data = hidden_workbook();
+        let mut archive = ZipArchive::new(Cursor::new(data.as_slice())).unwrap();
+        let paths = resolve_sheet_paths(&mut archive);
+        assert_eq!(
+            paths.get("Lookup").map(String::as_str),
+            Some("xl/worksheets/sheet3.xml")
         );
+        assert_eq!(paths.len(), 3);
     }
 
     #[test]
-    fn test_xlsx_format_cell_error_emits_warning() {
-        let mut warnings = Vec::new();
-        let cell = Data::Error(calamine::CellErrorType::Div0);
-        format_cell(&cell, "Sheet1!C3", &mut warnings);
-        assert_eq!(warnings.len(), 1);
-        assert_eq!(warnings[0].code, WarningCode::MalformedSegment);
-        assert_eq!(warnings[0].location.as_deref(), Some("Sheet1!C3"));
-        assert!(warnings[0].message.contains("error"));
-    }
+    fn test_xlsx_resolve_sheet_paths_any_relationships_prefix() {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
 
-    // -- DateTime formatting tests --
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let opts = SimpleFileOptions::default();
+        zip.start_file("xl/workbook.xml", opts).unwrap();
+        zip.write_all(br#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:rel="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:x="urn:other"><sheets><sheet name="Data" sheetId="1" x:id="rId9" rel:id="rId1"/></sheets></workbook>"#).unwrap();
+        zip.start_file("xl/_rels/workbook.xml.rels", opts).unwrap();
+        zip.write_all(br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId9" Type="urn:other" Target="other.xml"/></Relationships>"#).unwrap();
+        let data = zip.finish().unwrap().into_inner();
 
-    #[test]
-    fn test_xlsx_format_cell_datetime_date_only() {
-        use calamine::ExcelDateTimeType;
-        let mut warnings = Vec::new();
-        // Excel serial date for 2024-01-15 = 45306
-        let dt = Data::DateTime(calamine::ExcelDateTime::new(
-            45306.0,
-            ExcelDateTimeType::DateTime,
-            false,
-        ));
-        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
-        assert!(warnings.is_empty());
-        assert_eq!(result, "2024-01-15");
+        let mut archive = ZipArchive::new(Cursor::new(data.as_slice())).unwrap();
+        let paths = resolve_sheet_paths(&mut archive);
+        assert_eq!(
+            paths.get("Data").map(String::as_str),
+            Some("xl/worksheets/sheet1.xml")
+        );
     }
 
     #[test]
-    fn test_xlsx_format_cell_datetime_full() {
-        use calamine::ExcelDateTimeType;
-        let mut warnings = Vec::new();
-        // 45306.5 = 2024-01-15 12:00:00
-        let dt = Data::DateTime(calamine::ExcelDateTime::new(
-            45306.5,
-            ExcelDateTimeType::DateTime,
-            false,
-        ));
-        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
-        assert!(warnings.is_empty());
-        assert_eq!(result, "2024-01-15 12:00:00");
+    fn test_xlsx_hidden_content_included_by_default() {
+        let result = XlsxConverter
+            .convert(&hidden_workbook(), &ConversionOptions::default())
+            .unwrap();
+        assert!(result.markdown.contains("## Lookup"));
+        assert!(result.markdown.contains("| Item | Lookup | Price |"));
+        assert!(result.markdown.contains("| Scratch | x2 | 9 |"));
+
+        let messages: Vec<&str> = result.warnings.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "included 1 hidden row(s): 3",
+                "included 1 hidden column(s): B",
+                "included hidden sheet 'Lookup'",
+            ]
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .all(|w| w.code == WarningCode::UnsupportedFeature)
+        );
     }
 
     #[test]
-    fn test_xlsx_format_cell_datetime_with_time() {
-        use calamine::ExcelDateTimeType;
-        let mut warnings = Vec::new();
-        // 45306.0 + 14h30m15s = 45306 + (14*3600+30*60+15)/86400
-        let fractional = (14.0 * 3600.0 + 30.0 * 60.0 + 15.0) / 86400.0;
-        let dt = Data::DateTime(calamine::ExcelDateTime::new(
-            45306.0 + fractional,
-            ExcelDateTimeType::DateTime,
-            false,
-        ));
-        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
-        assert!(warnings.is_empty());
-        assert_eq!(result, "2024-01-15 14:30:15");
+    fn test_xlsx_hidden_content_skipped() {
+        let result = convert_xlsx_with(
+            &hidden_workbook(),
+            XlsxOptions {
+                hidden: HiddenContent::Skip,
+                ..Default::default()
+            },
+        );
+        assert!(!result.markdown.contains("Lookup"));
+        assert!(!result.markdown.contains("Secret"));
+        assert!(!result.markdown.contains("Scratch"));
+        assert!(
+            result
+                .markdown
+                .contains("| Item | Price |\n|---|---|\n| Pen | 2 |"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("## Summary"));
+
+        let messages: Vec<&str> = result.warnings.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "skipped 1 hidden row(s): 3",
+                "skipped 1 hidden column(s): B",
+                "skipped hidden sheet 'Lookup'",
+            ]
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .all(|w| w.code == WarningCode::SkippedElement)
+        );
     }
 
     #[test]
-    fn test_xlsx_format_cell_datetime_time_only() {
-        use calamine::ExcelDateTimeType;
-        let mut warnings = Vec::new();
-        // Time-only: fractional day < 1.0, e.g. 0.5 = 12:00:00
-        let dt = Data::DateTime(calamine::ExcelDateTime::new(
-            0.5,
-            ExcelDateTimeType::TimeDelta,
-            false,
-        ));
-        let result = format_cell(&dt, "Sheet1!A1", &mut warnings);
-        assert!(warnings.is_empty());
-        // Could be either "1899-12-30 12:00:00" or "12:00:00" depending on calamine behavior
+    fn test_xlsx_hidden_content_marked() {
+        let result = convert_xlsx_with(
+            &hidden_workbook(),
+            XlsxOptions {
+                hidden: HiddenContent::Mark,
+                ..Default::default()
+            },
+        );
         assert!(
-            result.contains("12:00:00"),
-            "expected time 12:00:00 in output, got: {result}"
+            result
+                .markdown
+                .contains("| Item | Lookup (hidden) | Price |"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("| (hidden) Scratch | x2 | 9 |"));
+        assert!(result.markdown.contains("## Lookup (hidden)"));
+        assert!(result.plain_text.contains("Lookup (hidden)\n"));
+        assert_eq!(result.warnings.len(), 3);
+        assert!(
+            result.warnings[0]
+                .message
+                .starts_with("marked 1 hidden row(s)")
         );
     }
 
-    // -- Image extraction tests --
-
-    use crate::converter::ImageDescriber;
-    use std::sync::Arc;
+    // -- Number format tests --
 
-    struct MockDescriber {
-        description: String,
+    fn fmt(value: f64, code: &str) -> Option<String> {
+        apply_number_format(&Data::Float(value), code)
     }
 
-    impl ImageDescriber for MockDescriber {
-        fn describe(
-            &self,
-            _image_bytes: &[u8],
-            _mime_type: &str,
-            _prompt: &str,
-        ) -> Result<String, ConvertError> {
-            Ok(self.description.clone())
-        }
+    #[test]
+    fn test_xlsx_parse_cell_ref() {
+        assert_eq!(parse_cell_ref("A1"), Some((0, 0)));
+        assert_eq!(parse_cell_ref("$AB$12"), Some((11, 27)));
+        assert_eq!(parse_cell_ref("12"), None);
+        assert_eq!(parse_cell_ref("A0"), None);
     }
 
-    struct FailingDescriber;
-
-    impl ImageDescriber for FailingDescriber {
-        fn describe(
-            &self,
-            _image_bytes: &[u8],
-            _mime_type: &str,
-            _prompt: &str,
-        ) -> Result<String, ConvertError> {
-            Err(ConvertError::ImageDescriptionError {
-                reason: "API error".to_string(),
-            })
-        }
+    #[test]
+    fn test_xlsx_number_format_percent() {
+        assert_eq!(fmt(0.125, "0%").as_deref(), Some("13%"));
+        assert_eq!(fmt(0.125, "0.0%").as_deref(), Some("12.5%"));
     }
 
-    /// Build a minimal XLSX file with an embedded image in sheet 1.
-    ///
-    /// The ZIP contains:
-    /// - Standard XLSX structure (workbook, sheet with data)
-    /// - `xl/worksheets/_rels/sheet1.xml.rels` → points to `../drawings/drawing1.xml`
-    /// - `xl/drawings/drawing1.xml` → contains `<a:blip r:embed="rId1"/>`
-    /// - `xl/drawings/_rels/drawing1.xml.rels` → points to `../media/image1.png`
-    /// - `xl/media/image1.png` → fake image bytes
-    fn build_test_xlsx_with_image(
-        sheets: &[(&str, &[&[TestCell]])],
-        image_filename: &str,
-        image_data: &[u8],
-    ) -> Vec<u8> {
-        use std::io::Write;
-        use zip::ZipWriter;
-        use zip::write::SimpleFileOptions;
-
-        let buf = Vec::new();
-        let mut zip = ZipWriter::new(Cursor::new(buf));
-        let opts = SimpleFileOptions::default();
+    #[test]
+    fn test_xlsx_number_format_fixed_and_thousands() {
+        assert_eq!(fmt(4.5678, "0.00").as_deref(), Some("4.57"));
+        assert_eq!(fmt(1.005, "0.00").as_deref(), Some("1.01"));
+        assert_eq!(fmt(1234567.891, "#,##0").as_deref(), Some("1,234,568"));
+        assert_eq!(
+            fmt(1234567.891, "#,##0.00").as_deref(),
+            Some("1,234,567.89")
+        );
+        assert_eq!(fmt(0.5, "#,##0.00").as_deref(), Some("0.50"));
+        assert_eq!(fmt(7.0, "000").as_deref(), Some("007"));
+        assert_eq!(fmt(2.5, "0.##").as_deref(), Some("2.5"));
+    }
 
-        // [Content_Types].xml — add image content type
-        let mut ct = String::from(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
-             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
-             <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
-             <Default Extension=\"png\" ContentType=\"image/png\"/>\
-             <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
+    #[test]
+    fn test_xlsx_number_format_currency() {
+        assert_eq!(
+            fmt(1234.5, "$#,##0.00_);($#,##0.00)").as_deref(),
+            Some("$1,234.50")
         );
-        for (i, _) in sheets.iter().enumerate() {
-            ct.push_str(&format!(
-                "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
-                 ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
-                i + 1
-            ));
-        }
-        ct.push_str("</Types>");
-        zip.start_file("[Content_Types].xml", opts).unwrap();
-        zip.write_all(ct.as_bytes()).unwrap();
+        assert_eq!(
+            fmt(-1234.5, "$#,##0.00_);($#,##0.00)").as_deref(),
+            Some("($1,234.50)")
+        );
+        assert_eq!(fmt(99.0, "[$€-407] #,##0.00").as_deref(), Some("€ 99.00"));
+        assert_eq!(fmt(-5.0, r#"#,##0.00 "EUR""#).as_deref(), Some("-5.00 EUR"));
+    }
 
-        // _rels/.rels
-        zip.start_file("_rels/.rels", opts).unwrap();
-        zip.write_all(
-            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
-              <Relationship Id=\"rId1\" \
-              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" \
-              Target=\"xl/workbook.xml\"/>\
-              </Relationships>",
-        )
-        .unwrap();
+    #[test]
+    fn test_xlsx_number_format_sections_and_scaling() {
+        assert_eq!(fmt(0.0, r#"0.00;-0.00;"-""#).as_deref(), Some("-"));
+        assert_eq!(fmt(1_500_000.0, r#"#,##0.0,,"M""#).as_deref(), Some("1.5M"));
+        assert_eq!(fmt(12345.0, "0.00E+00").as_deref(), Some("1.23E+04"));
+        assert_eq!(fmt(0.00012, "0.0E+00").as_deref(), Some("1.2E-04"));
+    }
 
-        // xl/workbook.xml
-        let mut wb = String::from(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
-             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
-             <sheets>",
-        );
-        for (i, (name, _)) in sheets.iter().enumerate() {
-            wb.push_str(&format!(
-                "<sheet name=\"{name}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
-                i + 1,
-                i + 1
-            ));
-        }
-        wb.push_str("</sheets></workbook>");
-        zip.start_file("xl/workbook.xml", opts).unwrap();
-        zip.write_all(wb.as_bytes()).unwrap();
+    #[test]
+    fn test_xlsx_number_format_unsupported_falls_back() {
+        assert_eq!(fmt(0.5, "# ?/?"), None);
+        assert_eq!(apply_number_format(&Data::String("x".into()), "0.00"), None);
+    }
+
+    #[test]
+    fn test_xlsx_number_format_custom_dates() {
+        // 45306.75 = 2024-01-15 18:00
+        assert_eq!(fmt(45306.75, "yyyy-mm-dd").as_deref(), Some("2024-01-15"));
+        assert_eq!(fmt(45306.75, "d mmm yyyy").as_deref(), Some("15 Jan 2024"));
+        assert_eq!(
+            fmt(45306.75, "dddd, mmmm d").as_deref(),
+            Some("Monday, January 15")
+        );
+        assert_eq!(
+            fmt(45306.75, "dd/mm/yy hh:mm").as_deref(),
+            Some("15/01/24 18:00")
+        );
+        assert_eq!(fmt(45306.75, "h:mm AM/PM").as_deref(), Some("6:00 PM"));
+        assert_eq!(fmt(1.5, "[h]:mm").as_deref(), Some("36:00"));
+    }
 
-        // xl/_rels/workbook.xml.rels
-        let mut rels = String::from(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    #[test]
+    fn test_xlsx_number_formats_parse_styles() {
+        let formats = NumberFormats::parse(
+            r#"<styleSheet><numFmts count="1"><numFmt numFmtId="164" formatCode="&quot;$&quot;#,##0.00"/></numFmts><cellStyleXfs count="1"><xf numFmtId="0"/></cellStyleXfs><cellXfs count="4"><xf numFmtId="0"/><xf numFmtId="10" applyNumberFormat="1"/><xf numFmtId="164"/><xf numFmtId="14"/></cellXfs></styleSheet>"#,
         );
-        for (i, _) in sheets.iter().enumerate() {
-            rels.push_str(&format!(
-                "<Relationship Id=\"rId{}\" \
-                 Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
-                 Target=\"worksheets/sheet{}.xml\"/>",
-                i + 1,
-                i + 1
-            ));
-        }
-        rels.push_str("</Relationships>");
-        zip.start_file("xl/_rels/workbook.xml.rels", opts).unwrap();
-        zip.write_all(rels.as_bytes()).unwrap();
+        assert_eq!(formats.cell_xfs, vec![0, 10, 164, 14]);
+        assert_eq!(formats.code_for_style(0), None);
+        assert_eq!(formats.code_for_style(1), Some("0.00%"));
+        assert_eq!(formats.code_for_style(2), Some("\"$\"#,##0.00"));
+        // Locale-dependent built-in dates keep the ISO rendering
+        assert_eq!(formats.code_for_style(3), None);
+        assert_eq!(formats.code_for_style(9), None);
+    }
 
-        // Each worksheet
-        for (i, (_, rows)) in sheets.iter().enumerate() {
-            let mut ws = String::from(
-                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-                 <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
-                 <sheetData>",
-            );
-            for (ri, row) in rows.iter().enumerate() {
-                ws.push_str(&format!("<row r=\"{}\">", ri + 1));
-                for (ci, cell) in row.iter().enumerate() {
-                    let col = test_col_letter(ci);
-                    let r = ri + 1;
-                    match cell {
-                        TestCell::Str(s) => {
-                            let escaped = s
-                                .replace('&', "&amp;")
-                                .replace('<', "&lt;")
-                                .replace('>', "&gt;")
-                                .replace('"', "&quot;");
-                            ws.push_str(&format!(
-                                "<c r=\"{col}{r}\" t=\"inlineStr\"><is><t>{escaped}</t></is></c>"
-                            ));
-                        }
-                        TestCell::Num(f) => {
-                            ws.push_str(&format!("<c r=\"{col}{r}\"><v>{f}</v></c>"));
-                        }
-                        TestCell::Bool(b) => {
-                            let v = if *b { 1 } else { 0 };
-                            ws.push_str(&format!("<c r=\"{col}{r}\" t=\"b\"><v>{v}</v></c>"));
-                        }
-                        TestCell::Empty => {}
-                    }
-                }
-                ws.push_str("</row>");
-            }
-            ws.push_str("</sheetData></worksheet>");
-            zip.start_file(format!("xl/worksheets/sheet{}.xml", i + 1), opts)
-                .unwrap();
-            zip.write_all(ws.as_bytes()).unwrap();
-        }
+    const FORMATTED_STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8"?><styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="2"><numFmt numFmtId="164" formatCode="&quot;$&quot;#,##0.00"/><numFmt numFmtId="165" formatCode="d mmm yyyy"/></numFmts><cellXfs count="5"><xf numFmtId="0"/><xf numFmtId="10" applyNumberFormat="1"/><xf numFmtId="164" applyNumberFormat="1"/><xf numFmtId="3" applyNumberFormat="1"/><xf numFmtId="165" applyNumberFormat="1"/></cellXfs></styleSheet>"#;
 
-        // Sheet 1 rels — point to drawing1.xml
-        zip.start_file("xl/worksheets/_rels/sheet1.xml.rels", opts)
-            .unwrap();
-        zip.write_all(
-            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
-              <Relationship Id=\"rId1\" \
-              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing\" \
-              Target=\"../drawings/drawing1.xml\"/>\
-              </Relationships>",
+    fn formatted_workbook() -> Vec<u8> {
+        let sheet = format!(
+            r#"<sheetData><row r="1">{}{}{}{}</row><row r="2"><c r="A2" s="1"><v>0.125</v></c><c r="B2" s="2"><v>1234.5</v></c><c r="C2" s="3"><v>48210</v></c><c r="D2" s="4"><v>45306</v></c></row></sheetData>"#,
+            str_cell("A1", "Growth"),
+            str_cell("B1", "Revenue"),
+            str_cell("C1", "Units"),
+            str_cell("D1", "Closed"),
+        );
+        build_raw_xlsx(
+            &[("Sales", "", &sheet)],
+            &[("xl/styles.xml", FORMATTED_STYLES)],
         )
-        .unwrap();
+    }
 
-        // xl/drawings/drawing1.xml — contains a blip reference
-        let drawing_xml = format!(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <xdr:wsDr xmlns:xdr=\"http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing\" \
-             xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
-             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
-             <xdr:twoCellAnchor>\
-             <xdr:pic>\
-             <xdr:nvPicPr><xdr:cNvPr id=\"1\" name=\"Picture 1\"/><xdr:cNvPicPr/></xdr:nvPicPr>\
-             <xdr:blipFill><a:blip r:embed=\"rId1\"/></xdr:blipFill>\
-             </xdr:pic>\
-             </xdr:twoCellAnchor>\
-             </xdr:wsDr>"
+    #[test]
+    fn test_xlsx_number_formats_applied_by_default() {
+        let result = XlsxConverter
+            .convert(&formatted_workbook(), &ConversionOptions::default())
+            .unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("| 12.50% | $1,234.50 | 48,210 | 15 Jan 2024 |"),
+            "markdown was: {}",
+            result.markdown
         );
-        zip.start_file("xl/drawings/drawing1.xml", opts).unwrap();
-        zip.write_all(drawing_xml.as_bytes()).unwrap();
+    }
 
-        // xl/drawings/_rels/drawing1.xml.rels — resolve blip rId1 to media file
-        let drawing_rels = format!(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
-             <Relationship Id=\"rId1\" \
-             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" \
-             Target=\"../media/{image_filename}\"/>\
-             </Relationships>"
+    #[test]
+    fn test_xlsx_raw_cell_values() {
+        let result = convert_xlsx_with(
+            &formatted_workbook(),
+            XlsxOptions {
+                cell_values: CellValues::Raw,
+                ..Default::default()
+            },
         );
-        zip.start_file("xl/drawings/_rels/drawing1.xml.rels", opts)
-            .unwrap();
-        zip.write_all(drawing_rels.as_bytes()).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("| 0.125 | 1234.5 | 48210 | 2024-01-15 |"),
+            "markdown was: {}",
+            result.markdown
+        );
+    }
 
-        // xl/media/image1.png — actual image bytes
-        zip.start_file(format!("xl/media/{image_filename}"), opts)
+    // -- Formula tests --
+
+    fn formula_workbook() -> Vec<u8> {
+        let sheet = format!(
+            r#"<sheetData><row r="1">{}{}</row><row r="2">{}<c r="B2"><v>10</v></c></row><row r="3">{}<c r="B3"><v>20</v></c></row><row r="4">{}<c r="B4"><f>SUM(B2:B3)</f><v>30</v></c></row></sheetData>"#,
+            str_cell("A1", "Item"),
+            str_cell("B1", "Amount"),
+            str_cell("A2", "Rent"),
+            str_cell("A3", "Power"),
+            str_cell("A4", "Total"),
+        );
+        build_raw_xlsx(&[("Budget", "", &sheet)], &[])
+    }
+
+    #[test]
+    fn test_xlsx_formulas_off_by_default() {
+        let result = XlsxConverter
+            .convert(&formula_workbook(), &ConversionOptions::default())
             .unwrap();
-        zip.write_all(image_data).unwrap();
+        assert!(result.markdown.contains("| Total | 30 |"));
+        assert!(!result.markdown.contains("SUM"));
+    }
 
-        let cursor = zip.finish().unwrap();
-        cursor.into_inner()
+    #[test]
+    fn test_xlsx_formulas_inline() {
+        let result = convert_xlsx_with(
+            &formula_workbook(),
+            XlsxOptions {
+                formulas: Formulas::Inline,
+                ..Default::default()
+            },
+        );
+        assert!(
+            result.markdown.contains("| Total | 30 (=SUM(B2:B3)) |"),
+            "markdown was: {}",
+            result.markdown
+        );
+        assert!(result.markdown.contains("| Rent | 10 |"));
+        assert!(result.plain_text.contains("Total\t30 (=SUM(B2:B3))"));
     }
 
     #[test]
-    fn test_xlsx_image_extraction_disabled_by_default() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_formulas_appendix() {
+        let result = convert_xlsx_with(
+            &formula_workbook(),
+            XlsxOptions {
+                formulas: Formulas::Appendix,
+                ..Default::default()
+            },
         );
-        let converter = XlsxConverter;
-        let result = converter
-            .convert(&data, &ConversionOptions::default())
-            .unwrap();
-        // Default options: extract_images=false, image_describer=None
-        assert!(result.images.is_empty());
+        assert!(result.markdown.contains("| Total | 30 |"));
         assert!(
-            !result.markdown.contains("!["),
-            "markdown should not contain image refs by default: {}",
+            result
+                .markdown
+                .contains("\n### Formulas\n- `B4`: `=SUM(B2:B3)`\n"),
+            "markdown was: {}",
             result.markdown
         );
+        assert!(result.plain_text.contains("Formulas\nB4: =SUM(B2:B3)\n"));
     }
 
     #[test]
-    fn test_xlsx_image_extraction_with_extract_images() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_annotate_formulas_after_merge_fill_marks_anchor_only() {
+        // B1:C1 merged and filled; the formula annotation stays on the anchor
+        let mut grid = vec![vec!["Total".to_string(), "3".to_string(), String::new()]];
+        let merges = [Dimensions::new((0, 1), (0, 2))];
+        apply_merged_regions(&mut grid, (0, 0), &merges, MergedCells::Fill);
+        annotate_formulas(
+            &mut grid,
+            (0, 0),
+            &[
+                ((0, 1), "=1+2".to_string()),
+                ((0, 2), "=STALE()".to_string()),
+            ],
+            &merges,
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            extract_images: true,
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
-        assert_eq!(result.images.len(), 1);
-        assert_eq!(result.images[0].0, "image1.png");
-        assert_eq!(result.images[0].1, b"fake-png-data");
+        assert_eq!(grid[0], vec!["Total", "3 (=1+2)", "3"]);
     }
 
     #[test]
-    fn test_xlsx_image_in_markdown() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_code_span_fence_longer_than_backtick_runs() {
+        assert_eq!(code_span("=SUM(A1)"), "`=SUM(A1)`");
+        assert_eq!(code_span("=\"a`b\""), "``=\"a`b\"``");
+        assert_eq!(code_span("=\"``\"&A1"), "```=\"``\"&A1```");
+        assert_eq!(code_span("`x"), "`` `x ``");
+    }
+
+    #[test]
+    fn test_xlsx_annotate_formulas_uses_origin() {
+        let mut grid = vec![vec!["5".to_string(), String::new()]];
+        annotate_formulas(
+            &mut grid,
+            (2, 1),
+            &[
+                ((2, 1), "=A3+1".to_string()),
+                ((2, 2), "=NA()".to_string()),
+                ((0, 0), "=OUTSIDE()".to_string()),
+            ],
+            &[],
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            extract_images: true,
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
+        assert_eq!(
+            grid,
+            vec![vec!["5 (=A3+1)".to_string(), "=NA()".to_string()]]
+        );
+    }
+
+    // -- Excel Table and named range tests --
+
+    const TABLE_SHEET_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table2.xml"/></Relationships>"#;
+
+    fn table_xml(name: &str, range: &str, columns: &[&str], extra_attrs: &str) -> String {
+        let cols: String = columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!(r#"<tableColumn id="{}" name="{c}"/>"#, i + 1))
+            .collect();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="1" name="{name}" displayName="{name}" ref="{range}" {extra_attrs}><tableColumns count="{}">{cols}</tableColumns></table>"#,
+            columns.len()
+        )
+    }
+
+    /// A title cell in A1, a "Sales" table at A3:B5 and a "Costs" table at D3:E4.
+    fn two_table_sheet() -> String {
+        format!(
+            r#"<sheetData><row r="1">{}</row><row r="3">{}{}{}{}</row><row r="4">{}<c r="B4"><v>10</v></c>{}<c r="E4"><v>7</v></c></row><row r="5">{}<c r="B5"><v>12</v></c></row></sheetData>"#,
+            str_cell("A1", "Quarterly report"),
+            str_cell("A3", "Region"),
+            str_cell("B3", "Revenue"),
+            str_cell("D3", "Item"),
+            str_cell("E3", "Cost"),
+            str_cell("A4", "North"),
+            str_cell("D4", "Rent"),
+            str_cell("A5", "South"),
+        )
+    }
+
+    #[test]
+    fn test_xlsx_parse_table_definition() {
+        let xml = table_xml("Sales", "A3:B5", &["Region", "Revenue"], "");
+        let table = parse_table_definition(&xml).unwrap();
+        assert_eq!(table.name, "Sales");
+        assert_eq!(table.area, Dimensions::new((2, 0), (4, 1)));
+        assert!(table.header_row);
+        assert_eq!(table.columns, vec!["Region", "Revenue"]);
+
+        let xml = table_xml("Raw", "C1:C2", &["Column1"], r#"headerRowCount="0""#);
+        assert!(!parse_table_definition(&xml).unwrap().header_row);
+    }
+
+    #[test]
+    fn test_xlsx_parse_range_ref() {
+        assert_eq!(
+            parse_range_ref("$B$2:E10"),
+            Some(Dimensions::new((1, 1), (9, 4)))
+        );
+        assert_eq!(parse_range_ref("C3"), Some(Dimensions::new((2, 2), (2, 2))));
+        assert_eq!(parse_range_ref("nope"), None);
+    }
+
+    #[test]
+    fn test_xlsx_excel_tables_rendered_as_titled_tables() {
+        let sheet = two_table_sheet();
+        let table1 = table_xml("Sales", "A3:B5", &["Region", "Revenue"], "");
+        let table2 = table_xml("Costs", "D3:E4", &["Item", "Cost"], "");
+        let data = build_raw_xlsx(
+            &[("Report", "", &sheet)],
+            &[
+                ("xl/worksheets/_rels/sheet1.xml.rels", TABLE_SHEET_RELS),
+                ("xl/tables/table1.xml", &table1),
+                ("xl/tables/table2.xml", &table2),
+            ],
+        );
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        let md = &result.markdown;
+
+        let title_pos = md.find("| Quarterly report |").unwrap();
+        let sales_pos = md.find("### Sales\n| Region | Revenue |").unwrap();
+        let costs_pos = md.find("### Costs\n| Item | Cost |").unwrap();
+        assert!(title_pos < sales_pos && sales_pos < costs_pos, "{md}");
+        assert!(md.contains("| North | 10 |"));
+        assert!(md.contains("| South | 12 |"));
+        assert!(md.contains("| Rent | 7 |"));
+        // Table cells are not repeated in the untitled remainder
+        assert_eq!(md.matches("North").count(), 1, "{md}");
+        assert!(result.plain_text.contains("Sales\nRegion\tRevenue"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_xlsx_excel_table_area_clamped_to_used_range() {
+        let sheet = format!(
+            r#"<sheetData><row r="1">{}{}</row><row r="2">{}<c r="B2"><v>1</v></c></row></sheetData>"#,
+            str_cell("A1", "Key"),
+            str_cell("B1", "Value"),
+            str_cell("A2", "a"),
+        );
+        // A whole-sheet ref must not allocate past the used range
+        let table = table_xml("Huge", "A1:XFD1048576", &["Key", "Value"], "");
+        let rels = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table1.xml"/></Relationships>"#;
+        let data = build_raw_xlsx(
+            &[("Data", "", &sheet)],
+            &[
+                ("xl/worksheets/_rels/sheet1.xml.rels", rels),
+                ("xl/tables/table1.xml", &table),
+            ],
+        );
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
         assert!(
-            result.markdown.contains("![](image1.png)"),
-            "markdown was: {}",
+            result
+                .markdown
+                .contains("### Huge\n| Key | Value |\n|---|---|\n| a | 1 |\n"),
+            "{}",
             result.markdown
         );
-        // Image refs should appear after the table
-        assert!(result.markdown.contains("## Sheet1"));
     }
 
     #[test]
-    fn test_xlsx_image_describer_replaces_alt_text() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_or_warn_degrades_auxiliary_part_errors() {
+        let mut warnings = Vec::new();
+        let tables: Vec<ExcelTable> = or_warn(
+            Err(ConvertError::ZipError(zip::result::ZipError::FileNotFound)),
+            "tables",
+            Some("Data"),
+            &mut warnings,
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            image_describer: Some(Arc::new(MockDescriber {
-                description: "A chart showing sales data".to_string(),
-            })),
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
+        assert!(tables.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::SkippedElement);
+        assert!(
+            warnings[0]
+                .message
+                .starts_with("failed to read tables of sheet 'Data':")
+        );
+    }
+
+    #[test]
+    fn test_xlsx_excel_table_without_header_row_uses_column_names() {
+        let sheet = format!(
+            r#"<sheetData><row r="1">{}<c r="B1"><v>1</v></c></row><row r="2">{}<c r="B2"><v>2</v></c></row></sheetData>"#,
+            str_cell("A1", "a"),
+            str_cell("A2", "b"),
+        );
+        let table = table_xml("Raw", "A1:B2", &["Key", "Value"], r#"headerRowCount="0""#);
+        let rels = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table1.xml"/></Relationships>"#;
+        let data = build_raw_xlsx(
+            &[("Data", "", &sheet)],
+            &[
+                ("xl/worksheets/_rels/sheet1.xml.rels", rels),
+                ("xl/tables/table1.xml", &table),
+            ],
+        );
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
         assert!(
             result
-                .markdown
-                .contains("![A chart showing sales data](image1.png)"),
-            "markdown was: {}",
+                .markdown
+                .contains("### Raw\n| Key | Value |\n|---|---|\n| a | 1 |\n| b | 2 |"),
+            "{}",
             result.markdown
         );
-        // Without extract_images, images vec should be empty
-        assert!(result.images.is_empty());
     }
 
     #[test]
-    fn test_xlsx_image_describer_error_keeps_original() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_named_ranges_listed_in_workbook_summary() {
+        let sheet = format!(
+            r#"<sheetData><row r="1">{}<c r="B1"><v>0.2</v></c></row></sheetData>"#,
+            str_cell("A1", "Tax rate"),
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            image_describer: Some(Arc::new(FailingDescriber)),
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
-        // Original empty alt text should be preserved
+        let names = r#"<definedNames><definedName name="TaxRate">Inputs!$B$1</definedName><definedName name="_xlnm.Print_Area" localSheetId="0">Inputs!$A$1:$B$1</definedName></definedNames>"#;
+        let data = build_raw_xlsx_with_names(&[("Inputs", "", &sheet)], names, &[]);
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
         assert!(
-            result.markdown.contains("![](image1.png)"),
-            "markdown was: {}",
+            result.markdown.starts_with("## Inputs\n"),
+            "{}",
             result.markdown
         );
-        // Should have a warning about the failure
         assert!(
             result
-                .warnings
-                .iter()
-                .any(|w| w.code == WarningCode::SkippedElement
-                    && w.message.contains("image description failed"))
-        );
-    }
-
-    #[test]
-    fn test_xlsx_image_byte_budget_enforced() {
-        use TestCell::*;
-        // Create an image that exceeds a small budget
-        let large_image = vec![0u8; 1000];
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            &large_image,
+                .markdown
+                .ends_with("\n## Named Ranges\n- `TaxRate`: `Inputs!$B$1`\n"),
+            "{}",
+            result.markdown
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            extract_images: true,
-            max_total_image_bytes: 500, // budget smaller than image
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
-        // Image should not be extracted (exceeds budget)
-        assert!(result.images.is_empty());
-        // Should have a ResourceLimitReached warning
+        assert!(!result.markdown.contains("Print_Area"));
         assert!(
             result
-                .warnings
-                .iter()
-                .any(|w| w.code == WarningCode::ResourceLimitReached)
+                .plain_text
+                .ends_with("\nNamed Ranges\nTaxRate: Inputs!$B$1\n")
         );
     }
 
-    // -- Helper function unit tests --
+    #[test]
+    fn test_xlsx_no_named_ranges_no_summary() {
+        let data = build_test_xlsx(&[("Sheet1", &[&[TestCell::Str("a")]])]);
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(!result.markdown.contains("Named Ranges"));
+    }
+
+    // -- Region detection tests --
+
+    /// Title in A1, a table at A3:B5, and a note in A7 next to a stray value in D7.
+    fn report_sheet() -> String {
+        format!(
+            r#"<sheetData><row r="1">{}</row><row r="3">{}{}</row><row r="4">{}<c r="B4"><v>10</v></c></row><row r="5">{}<c r="B5"><v>12</v></c></row><row r="7">{}<c r="D7"><v>99</v></c></row></sheetData>"#,
+            str_cell("A1", "Quarterly report"),
+            str_cell("A3", "Region"),
+            str_cell("B3", "Revenue"),
+            str_cell("A4", "North"),
+            str_cell("A5", "South"),
+            str_cell("A7", "Figures are unaudited"),
+        )
+    }
 
     #[test]
-    fn test_parse_relationships_basic() {
-        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-            <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
-            <Relationship Id="rId1" Target="../drawings/drawing1.xml"
-             Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing"/>
-            </Relationships>"#;
-        let rels = parse_relationships(xml);
+    fn test_xlsx_find_regions_splits_on_blank_rows_and_columns() {
+        let filled: HashSet<(u32, u32)> = [(0, 0), (2, 0), (2, 1), (3, 0), (3, 1), (6, 0), (6, 3)]
+            .into_iter()
+            .collect();
+        let is_filled = |r: u32, c: u32| filled.contains(&(r, c));
+        let regions = find_regions(&is_filled, Dimensions::new((0, 0), (6, 3)));
         assert_eq!(
-            rels.get("rId1").map(|r| r.target.as_str()),
-            Some("../drawings/drawing1.xml")
+            regions,
+            vec![
+                Dimensions::new((0, 0), (0, 0)),
+                Dimensions::new((2, 0), (3, 1)),
+                Dimensions::new((6, 0), (6, 0)),
+                Dimensions::new((6, 3), (6, 3)),
+            ]
         );
     }
 
     #[test]
-    fn test_parse_drawing_blips_basic() {
-        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-            <xdr:wsDr xmlns:xdr="http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing"
-                      xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
-                      xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
-            <xdr:twoCellAnchor>
-            <xdr:pic><xdr:blipFill><a:blip r:embed="rId1"/></xdr:blipFill></xdr:pic>
-            </xdr:twoCellAnchor>
-            <xdr:oneCellAnchor>
-            <xdr:pic><xdr:blipFill><a:blip r:embed="rId2"/></xdr:blipFill></xdr:pic>
-            </xdr:oneCellAnchor>
-            </xdr:wsDr>"#;
-        let blips = parse_drawing_blips(xml);
-        assert_eq!(blips, vec!["rId1", "rId2"]);
+    fn test_xlsx_region_table_header_heuristic() {
+        let grid = vec![
+            vec!["1".to_string(), "2".to_string()],
+            vec!["3".to_string(), "4".to_string()],
+        ];
+        let table = region_table(&grid, (0, 0), Dimensions::new((0, 0), (1, 1)));
+        assert_eq!(table.rows[0], vec!["A", "B"]);
+        assert_eq!(table.rows.len(), 3);
+
+        let grid = vec![
+            vec!["Name".to_string(), "Qty".to_string()],
+            vec!["x".to_string(), "4".to_string()],
+        ];
+        let table = region_table(&grid, (0, 0), Dimensions::new((0, 0), (1, 1)));
+        assert_eq!(table.rows[0], vec!["Name", "Qty"]);
+        assert_eq!(table.rows.len(), 2);
     }
 
     #[test]
-    fn test_derive_rels_path() {
-        assert_eq!(
-            derive_rels_path("xl/drawings/drawing1.xml"),
-            "xl/drawings/_rels/drawing1.xml.rels"
+    fn test_xlsx_detect_regions_renders_islands_separately() {
+        let sheet = report_sheet();
+        let data = build_raw_xlsx(&[("Report", "", &sheet)], &[]);
+        let result = convert_xlsx_with(
+            &data,
+            XlsxOptions {
+                detect_regions: true,
+                ..Default::default()
+            },
         );
         assert_eq!(
-            derive_rels_path("xl/worksheets/sheet1.xml"),
-            "xl/worksheets/_rels/sheet1.xml.rels"
+            result.markdown,
+            "## Report\nQuarterly report\n\n| Region | Revenue |\n|---|---|\n| North | 10 |\n| South | 12 |\n\nFigures are unaudited\n\n99\n"
         );
-        assert_eq!(derive_rels_path("file.xml"), "_rels/file.xml.rels");
+        assert!(result.warnings.is_empty());
     }
 
     #[test]
-    fn test_resolve_relative_path_parent_dir() {
-        assert_eq!(
-            resolve_relative_path("xl/drawings", "../media/image1.png"),
-            "xl/media/image1.png"
+    fn test_xlsx_detect_regions_off_keeps_single_table() {
+        let sheet = report_sheet();
+        let data = build_raw_xlsx(&[("Report", "", &sheet)], &[]);
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(
+            result
+                .markdown
+                .starts_with("## Report\n| Quarterly report |")
+        );
+    }
+
+    // -- Hyperlink and comment tests --
+
+    const LINK_SHEET_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/q3" TargetMode="External"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml"/><Relationship Id="rId3" Type="http://schemas.microsoft.com/office/2017/10/relationships/threadedComment" Target="../threadedComments/threadedComment1.xml"/></Relationships>"#;
+
+    const NOTES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><authors><author>Jane Doe</author><author>tc={5D1F}</author></authors><commentList><comment ref="C3" authorId="0"><text><r><rPr><b/></rPr><t>Jane Doe:</t></r><r><t xml:space="preserve">
+check with finance</t></r></text></comment><comment ref="A2" authorId="1"><text><t>[Threaded comment] Your version of Excel allows you to read this threaded comment.</t></text></comment></commentList></comments>"#;
+
+    const THREADED_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><ThreadedComments xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><threadedComment ref="A2" personId="{P1}" id="{C1}"><text>Is this the final figure?</text></threadedComment><threadedComment ref="A2" personId="{P2}" id="{C2}" parentId="{C1}"><text>Yes</text></threadedComment></ThreadedComments>"#;
+
+    const PERSONS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><personList xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><person displayName="Ann Lee" id="{P1}" providerId="None"/><person displayName="Bob" id="{P2}" providerId="None"/></personList>"#;
+
+    fn build_linked_commented_xlsx() -> Vec<u8> {
+        let sheet = format!(
+            r#"<sheetData><row r="1">{}{}{}</row><row r="2">{}<c r="B2"><v>10</v></c>{}</row><row r="3">{}<c r="B3"><v>12</v></c><c r="C3"><v>5</v></c></row></sheetData><hyperlinks><hyperlink ref="C2" r:id="rId1"/><hyperlink ref="A3" location="Sheet2!A1"/></hyperlinks>"#,
+            str_cell("A1", "Region"),
+            str_cell("B1", "Revenue"),
+            str_cell("C1", "Source"),
+            str_cell("A2", "North"),
+            str_cell("C2", "Q3 report"),
+            str_cell("A3", "South"),
         );
+        build_raw_xlsx(
+            &[("Sales", "", &sheet)],
+            &[
+                ("xl/worksheets/_rels/sheet1.xml.rels", LINK_SHEET_RELS),
+                ("xl/comments1.xml", NOTES_XML),
+                ("xl/threadedComments/threadedComment1.xml", THREADED_XML),
+                ("xl/persons/person.xml", PERSONS_XML),
+            ],
+        )
     }
 
     #[test]
-    fn test_resolve_relative_path_same_dir() {
+    fn test_xlsx_parse_hyperlinks() {
+        let rels = parse_relationships(LINK_SHEET_RELS);
+        let xml = r#"<worksheet xmlns:r="r"><hyperlinks><hyperlink ref="B2:B3" r:id="rId1" location="top"/><hyperlink ref="A1" location="Sheet2!A1"/><hyperlink ref="D1" r:id="rId1"/></hyperlinks></worksheet>"#;
+        let links = parse_hyperlinks(xml, &rels);
+        assert_eq!(links.get(1, 1), Some("https://example.com/q3#top"));
+        assert_eq!(links.get(2, 1), Some("https://example.com/q3#top"));
+        assert_eq!(links.get(0, 3), Some("https://example.com/q3"));
+        assert_eq!(links.get(0, 0), None);
+        assert_eq!(links.get(3, 1), None);
+    }
+
+    #[test]
+    fn test_xlsx_parse_hyperlinks_whole_sheet_range_not_expanded() {
+        let rels = parse_relationships(LINK_SHEET_RELS);
+        let xml = r#"<worksheet xmlns:r="r"><hyperlinks><hyperlink ref="A1:XFD1048576" r:id="rId1"/></hyperlinks></worksheet>"#;
+        let links = parse_hyperlinks(xml, &rels);
+        assert!(links.cells.is_empty());
+        assert_eq!(links.ranges.len(), 1);
+        assert_eq!(links.get(1_048_575, 16_383), Some("https://example.com/q3"));
+    }
+
+    #[test]
+    fn test_xlsx_escape_link_text() {
+        assert_eq!(escape_link_text("Q3 report"), "Q3 report");
         assert_eq!(
-            resolve_relative_path("xl/drawings", "image1.png"),
-            "xl/drawings/image1.png"
+            escape_link_text(r"[draft] C:\reports\"),
+            "&#91;draft&#93; C:&#92;reports&#92;"
         );
     }
 
     #[test]
-    fn test_resolve_relative_path_empty_base() {
+    fn test_xlsx_escape_link_url() {
         assert_eq!(
-            resolve_relative_path("", "media/image1.png"),
-            "media/image1.png"
+            escape_link_url("https://example.com/q3#top"),
+            "https://example.com/q3#top"
+        );
+        assert_eq!(
+            escape_link_url("file:///C:/My Reports/q3 (final).xlsx"),
+            "file:///C:/My%20Reports/q3%20%28final%29.xlsx"
+        );
+        assert_eq!(
+            escape_link_url("https://x.test/<a>\n"),
+            "https://x.test/%3Ca%3E"
         );
     }
 
-    // -- Absolute path (openpyxl-style) tests --
-
-    /// Build a minimal XLSX with absolute paths in rels (openpyxl style).
-    ///
-    /// Uses `/xl/drawings/drawing1.xml` and `/xl/media/image1.png` instead of
-    /// relative `../drawings/...` and `../media/...` paths.
-    fn build_test_xlsx_with_image_absolute_paths(
-        sheets: &[(&str, &[&[TestCell]])],
-        image_filename: &str,
-        image_data: &[u8],
-    ) -> Vec<u8> {
-        use std::io::Write;
-        use zip::ZipWriter;
-        use zip::write::SimpleFileOptions;
-
-        let buf = Vec::new();
-        let mut zip = ZipWriter::new(Cursor::new(buf));
-        let opts = SimpleFileOptions::default();
-
-        // [Content_Types].xml
-        let mut ct = String::from(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
-             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
-             <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
-             <Default Extension=\"png\" ContentType=\"image/png\"/>\
-             <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
+    #[test]
+    fn test_xlsx_parse_notes_strips_author_prefix() {
+        let notes = parse_notes(NOTES_XML);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(
+            notes[0],
+            CellComment {
+                cell: (2, 2),
+                author: "Jane Doe".to_string(),
+                text: "check with finance".to_string(),
+                is_reply: false,
+            }
         );
-        for (i, _) in sheets.iter().enumerate() {
-            ct.push_str(&format!(
-                "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
-                 ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
-                i + 1
-            ));
-        }
-        ct.push_str("</Types>");
-        zip.start_file("[Content_Types].xml", opts).unwrap();
-        zip.write_all(ct.as_bytes()).unwrap();
-
-        // _rels/.rels
-        zip.start_file("_rels/.rels", opts).unwrap();
-        zip.write_all(
-            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
-              <Relationship Id=\"rId1\" \
-              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" \
-              Target=\"xl/workbook.xml\"/>\
-              </Relationships>",
-        )
-        .unwrap();
+    }
 
-        // xl/workbook.xml
-        let mut wb = String::from(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
-             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
-             <sheets>",
-        );
-        for (i, (name, _)) in sheets.iter().enumerate() {
-            wb.push_str(&format!(
-                "<sheet name=\"{name}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
-                i + 1,
-                i + 1
-            ));
-        }
-        wb.push_str("</sheets></workbook>");
-        zip.start_file("xl/workbook.xml", opts).unwrap();
-        zip.write_all(wb.as_bytes()).unwrap();
+    #[test]
+    fn test_xlsx_parse_threaded_comments() {
+        let persons = parse_persons(PERSONS_XML);
+        let comments = parse_threaded_comments(THREADED_XML, &persons);
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].author, "Ann Lee");
+        assert!(!comments[0].is_reply);
+        assert_eq!(comments[1].author, "Bob");
+        assert_eq!(comments[1].text, "Yes");
+        assert!(comments[1].is_reply);
+    }
 
-        // xl/_rels/workbook.xml.rels
-        let mut rels = String::from(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    #[test]
+    fn test_xlsx_hyperlinked_cells_become_markdown_links() {
+        let data = build_linked_commented_xlsx();
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("| North | 10 | [Q3 report](https://example.com/q3) |"),
+            "{}",
+            result.markdown
         );
-        for (i, _) in sheets.iter().enumerate() {
-            rels.push_str(&format!(
-                "<Relationship Id=\"rId{}\" \
-                 Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
-                 Target=\"worksheets/sheet{}.xml\"/>",
-                i + 1,
-                i + 1
-            ));
-        }
-        rels.push_str("</Relationships>");
-        zip.start_file("xl/_rels/workbook.xml.rels", opts).unwrap();
-        zip.write_all(rels.as_bytes()).unwrap();
-
-        // Each worksheet
-        for (i, (_, rows)) in sheets.iter().enumerate() {
-            let mut ws = String::from(
-                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-                 <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
-                 <sheetData>",
-            );
-            for (ri, row) in rows.iter().enumerate() {
-                ws.push_str(&format!("<row r=\"{}\">", ri + 1));
-                for (ci, cell) in row.iter().enumerate() {
-                    let col = test_col_letter(ci);
-                    let r = ri + 1;
-                    match cell {
-                        TestCell::Str(s) => {
-                            let escaped = s
-                                .replace('&', "&amp;")
-                                .replace('<', "&lt;")
-                                .replace('>', "&gt;")
-                                .replace('"', "&quot;");
-                            ws.push_str(&format!(
-                                "<c r=\"{col}{r}\" t=\"inlineStr\"><is><t>{escaped}</t></is></c>"
-                            ));
-                        }
-                        TestCell::Num(f) => {
-                            ws.push_str(&format!("<c r=\"{col}{r}\"><v>{f}</v></c>"));
-                        }
-                        TestCell::Bool(b) => {
-                            let v = if *b { 1 } else { 0 };
-                            ws.push_str(&format!("<c r=\"{col}{r}\" t=\"b\"><v>{v}</v></c>"));
-                        }
-                        TestCell::Empty => {}
-                    }
-                }
-                ws.push_str("</row>");
+        // Internal workbook locations are not linked
+        assert!(result.markdown.contains("| South | 12 | 5 |"));
+        assert!(result.plain_text.contains("North\t10\tQ3 report"));
+        // Comments are opt-in
+        assert!(!result.markdown.contains("Comments"));
+    }
+
+    #[test]
+    fn test_xlsx_comments_listed_per_sheet() {
+        let data = build_linked_commented_xlsx();
+        let result = convert_xlsx_with(
+            &data,
+            XlsxOptions {
+                comments: true,
+                ..Default::default()
+            },
+        );
+        assert!(
+            result.markdown.ends_with(
+                "\n### Comments\n- `A2` (Ann Lee): Is this the final figure?\n  - `A2` (Bob): Yes\n- `C3` (Jane Doe): check with finance\n"
+            ),
+            "{}",
+            result.markdown
+        );
+        assert!(result.plain_text.contains("Comments\nA2 (Ann Lee): Is this the final figure?\n  A2 (Bob): Yes\nC3 (Jane Doe): check with finance\n"));
+        assert!(!result.markdown.contains("[Threaded comment]"));
+    }
+
+    // -- XLSB and ODS tests --
+
+    /// Build a minimal ODS workbook with one sheet of string and float cells.
+    fn build_test_ods(sheet_name: &str, rows: &[&[TestCell]]) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let mut table = format!(r#"<table:table table:name="{sheet_name}">"#);
+        for row in rows {
+            table.push_str("<table:table-row>");
+            for cell in *row {
+                table.push_str(&match cell {
+                    TestCell::Str(s) => format!(
+                        r#"<table:table-cell office:value-type="string"><text:p>{s}</text:p></table:table-cell>"#
+                    ),
+                    TestCell::Num(n) => format!(
+                        r#"<table:table-cell office:value-type="float" office:value="{n}"><text:p>{n}</text:p></table:table-cell>"#
+                    ),
+                    TestCell::Bool(b) => format!(
+                        r#"<table:table-cell office:value-type="boolean" office:boolean-value="{b}"><text:p>{b}</text:p></table:table-cell>"#
+                    ),
+                    TestCell::Empty => "<table:table-cell/>".to_string(),
+                });
             }
-            ws.push_str("</sheetData></worksheet>");
-            zip.start_file(format!("xl/worksheets/sheet{}.xml", i + 1), opts)
-                .unwrap();
-            zip.write_all(ws.as_bytes()).unwrap();
+            table.push_str("</table:table-row>");
         }
+        table.push_str("</table:table>");
 
-        // Sheet 1 rels — absolute path to drawing (openpyxl style)
-        zip.start_file("xl/worksheets/_rels/sheet1.xml.rels", opts)
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("mimetype", stored).unwrap();
+        zip.write_all(b"application/vnd.oasis.opendocument.spreadsheet")
+            .unwrap();
+        zip.start_file("META-INF/manifest.xml", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?><manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0"><manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"#).unwrap();
+        zip.start_file("content.xml", SimpleFileOptions::default())
             .unwrap();
         zip.write_all(
-            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-              <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
-              <Relationship Id=\"rId1\" \
-              Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing\" \
-              Target=\"/xl/drawings/drawing1.xml\"/>\
-              </Relationships>",
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2"><office:body><office:spreadsheet>{table}</office:spreadsheet></office:body></office:document-content>"#
+            )
+            .as_bytes(),
         )
         .unwrap();
+        zip.finish().unwrap().into_inner()
+    }
 
-        // xl/drawings/drawing1.xml
-        zip.start_file("xl/drawings/drawing1.xml", opts).unwrap();
-        zip.write_all(
-            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <xdr:wsDr xmlns:xdr=\"http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing\" \
-             xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
-             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
-             <xdr:twoCellAnchor>\
-             <xdr:pic>\
-             <xdr:nvPicPr><xdr:cNvPr id=\"1\" name=\"Picture 1\"/><xdr:cNvPicPr/></xdr:nvPicPr>\
-             <xdr:blipFill><a:blip r:embed=\"rId1\"/></xdr:blipFill>\
-             </xdr:pic>\
-             </xdr:twoCellAnchor>\
-             </xdr:wsDr>",
-        )
-        .unwrap();
+    /// Encode one XLSB (BIFF12) record: variable-length type and size, then data.
+    fn xlsb_record(typ: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        if typ < 0x80 {
+            out.push(typ as u8);
+        } else {
+            out.push((typ & 0x7F) as u8 | 0x80);
+            out.push((typ >> 7) as u8);
+        }
+        let mut len = data.len();
+        loop {
+            let byte = (len & 0x7F) as u8;
+            len >>= 7;
+            if len == 0 {
+                out.push(byte);
+                break;
+            }
+            out.push(byte | 0x80);
+        }
+        out.extend_from_slice(data);
+        out
+    }
 
-        // xl/drawings/_rels/drawing1.xml.rels — absolute path to media (openpyxl style)
-        let drawing_rels = format!(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
-             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
-             <Relationship Id=\"rId1\" \
-             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" \
-             Target=\"/xl/media/{image_filename}\"/>\
-             </Relationships>"
+    /// XLSB wide string: u32 character count followed by UTF-16LE.
+    fn xlsb_wide_str(s: &str) -> Vec<u8> {
+        let units: Vec<u16> = s.encode_utf16().collect();
+        let mut out = (units.len() as u32).to_le_bytes().to_vec();
+        for unit in units {
+            out.extend_from_slice(&unit.to_le_bytes());
+        }
+        out
+    }
+
+    /// Build a minimal XLSB workbook with one sheet of string and float cells.
+    fn build_test_xlsb(sheet_name: &str, rows: &[&[TestCell]]) -> Vec<u8> {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        // workbook.bin: BrtBundleSh, BrtEndBundleShs, then BrtCalcProp
+        let mut bundle = Vec::new();
+        bundle.extend_from_slice(&0u32.to_le_bytes()); // visible
+        bundle.extend_from_slice(&1u32.to_le_bytes()); // tab id
+        bundle.extend_from_slice(&xlsb_wide_str("rId1"));
+        bundle.extend_from_slice(&xlsb_wide_str(sheet_name));
+        let mut workbook = xlsb_record(0x009C, &bundle);
+        workbook.extend(xlsb_record(0x0090, &[]));
+        workbook.extend(xlsb_record(0x009D, &[0; 4]));
+
+        // sheet1.bin: BrtWsDim, BrtBeginSheetData, rows of cells, BrtEndSheetData
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(1) as u32;
+        let mut dim = Vec::new();
+        for v in [0, rows.len() as u32 - 1, 0, width - 1] {
+            dim.extend_from_slice(&v.to_le_bytes());
+        }
+        let mut sheet = xlsb_record(0x0094, &dim);
+        sheet.extend(xlsb_record(0x0091, &[]));
+        for (ri, row) in rows.iter().enumerate() {
+            sheet.extend(xlsb_record(0x0000, &(ri as u32).to_le_bytes()));
+            for (ci, cell) in row.iter().enumerate() {
+                let mut data = (ci as u32).to_le_bytes().to_vec();
+                data.extend_from_slice(&0u32.to_le_bytes()); // style
+                match cell {
+                    TestCell::Str(s) => {
+                        data.extend(xlsb_wide_str(s));
+                        sheet.extend(xlsb_record(0x0006, &data));
+                    }
+                    TestCell::Num(n) => {
+                        data.extend_from_slice(&n.to_le_bytes());
+                        sheet.extend(xlsb_record(0x0005, &data));
+                    }
+                    TestCell::Bool(b) => {
+                        data.push(u8::from(*b));
+                        sheet.extend(xlsb_record(0x0004, &data));
+                    }
+                    TestCell::Empty => {}
+                }
+            }
+        }
+        sheet.extend(xlsb_record(0x0092, &[]));
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let opts = SimpleFileOptions::default();
+        zip.start_file("xl/workbook.bin", opts).unwrap();
+        zip.write_all(&workbook).unwrap();
+        zip.start_file("xl/_rels/workbook.bin.rels", opts).unwrap();
+        zip.write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.bin"/></Relationships>"#).unwrap();
+        zip.start_file("xl/worksheets/sheet1.bin", opts).unwrap();
+        zip.write_all(&sheet).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    const BUDGET_ROWS: &[&[TestCell]] = &[
+        &[TestCell::Str("Item"), TestCell::Str("Cost")],
+        &[TestCell::Str("Rent"), TestCell::Num(1200.5)],
+        &[TestCell::Str("Power"), TestCell::Num(80.0)],
+    ];
+
+    #[test]
+    fn test_xlsb_and_ods_supported_extensions() {
+        let converter = XlsxConverter;
+        assert!(converter.can_convert("xlsb", &[]));
+        assert!(converter.can_convert("ods", &[]));
+    }
+
+    #[test]
+    fn test_xlsb_convert() {
+        let data = build_test_xlsb("Budget", BUDGET_ROWS);
+        assert_eq!(crate::detection::detect_zip_format(&data), Some("xlsb"));
+        let result = crate::convert_bytes(&data, "xlsb", &ConversionOptions::default()).unwrap();
+        assert_eq!(
+            result.markdown,
+            "## Budget\n| Item | Cost |\n|---|---|\n| Rent | 1200.5 |\n| Power | 80 |\n"
         );
-        zip.start_file("xl/drawings/_rels/drawing1.xml.rels", opts)
-            .unwrap();
-        zip.write_all(drawing_rels.as_bytes()).unwrap();
+        assert!(result.warnings.is_empty());
+    }
 
-        // xl/media/image1.png
-        zip.start_file(format!("xl/media/{image_filename}"), opts)
-            .unwrap();
-        zip.write_all(image_data).unwrap();
+    #[test]
+    fn test_ods_convert() {
+        let data = build_test_ods("Budget", BUDGET_ROWS);
+        assert_eq!(crate::detection::detect_zip_format(&data), Some("ods"));
+        let result = crate::convert_bytes(&data, "ods", &ConversionOptions::default()).unwrap();
+        assert_eq!(
+            result.markdown,
+            "## Budget\n| Item | Cost |\n|---|---|\n| Rent | 1200.5 |\n| Power | 80 |\n"
+        );
+        assert!(result.warnings.is_empty());
+    }
 
-        let cursor = zip.finish().unwrap();
-        cursor.into_inner()
+    // -- Row and column cap tests --
+
+    /// Header `N` plus data rows `1..=count`, as a one-column sheet table.
+    fn numbered_table(count: u32) -> SheetTable {
+        let mut grid = vec![vec!["N".to_string()]];
+        grid.extend((1..=count).map(|n| vec![n.to_string()]));
+        SheetTable::from_grid(&grid, (0, 0), Dimensions::new((0, 0), (count, 0)))
+    }
+
+    fn column_values(table: &SheetTable) -> Vec<&str> {
+        table.rows.iter().map(|row| row[0].as_str()).collect()
     }
 
     #[test]
-    fn test_xlsx_image_extraction_absolute_paths() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image_absolute_paths(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_truncate_table_sampling() {
+        let mut table = numbered_table(10);
+        let truncation = truncate_table(&mut table, Some(3), None, RowSampling::Head).unwrap();
+        assert_eq!(column_values(&table), vec!["N", "1", "2", "3"]);
+        assert_eq!(table.row_ids, vec![Some(0), Some(1), Some(2), Some(3)]);
+        assert_eq!(truncation.omitted_rows, 7);
+        assert_eq!(truncation.details(), "7 more rows (showing first 3)");
+
+        let mut table = numbered_table(10);
+        truncate_table(&mut table, Some(3), None, RowSampling::Tail).unwrap();
+        assert_eq!(column_values(&table), vec!["N", "8", "9", "10"]);
+
+        let mut table = numbered_table(10);
+        let truncation = truncate_table(&mut table, Some(4), None, RowSampling::Evenly).unwrap();
+        assert_eq!(column_values(&table), vec!["N", "1", "3", "6", "8"]);
+        assert_eq!(
+            truncation.details(),
+            "6 more rows (showing 4 evenly spaced)"
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            extract_images: true,
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
-        assert_eq!(result.images.len(), 1);
-        assert_eq!(result.images[0].0, "image1.png");
-        assert_eq!(result.images[0].1, b"fake-png-data");
     }
 
     #[test]
-    fn test_xlsx_image_in_markdown_absolute_paths() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image_absolute_paths(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_max_rows_is_one_budget_per_sheet() {
+        let table1 = table_xml("Sales", "A3:B5", &["Region", "Revenue"], "");
+        let table2 = table_xml("Costs", "D3:E4", &["Item", "Cost"], "");
+        let data = build_raw_xlsx(
+            &[("Report", "", &two_table_sheet())],
+            &[
+                ("xl/worksheets/_rels/sheet1.xml.rels", TABLE_SHEET_RELS),
+                ("xl/tables/table1.xml", &table1),
+                ("xl/tables/table2.xml", &table2),
+            ],
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            extract_images: true,
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
+        let result = convert_xlsx_with(
+            &data,
+            XlsxOptions {
+                max_rows: Some(2),
+                ..Default::default()
+            },
+        );
+        // Sales uses up both rows; Costs keeps only its header
         assert!(
-            result.markdown.contains("![](image1.png)"),
-            "markdown was: {}",
+            result.markdown.contains(
+                "### Costs\n| Item | Cost |\n|---|---|\n\n… 1 more row (sheet row limit reached)\n"
+            ),
+            "{}",
             result.markdown
         );
+        assert!(result.markdown.contains("| South | 12 |"));
+        assert_eq!(result.warnings.len(), 1);
     }
 
     #[test]
-    fn test_xlsx_image_describer_absolute_paths() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image_absolute_paths(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_truncate_table_within_limits_is_untouched() {
+        let mut table = numbered_table(3);
+        assert_eq!(
+            truncate_table(&mut table, Some(3), Some(1), RowSampling::Head),
+            None
+        );
+        assert_eq!(table.rows.len(), 4);
+    }
+
+    #[test]
+    fn test_xlsx_truncate_table_columns_lists_omitted_headers() {
+        let grid = vec![
+            vec!["A".to_string(), "B".to_string(), String::new()],
+            vec!["1".to_string(), "2".to_string(), "3".to_string()],
+        ];
+        let mut table = SheetTable::from_grid(&grid, (0, 0), Dimensions::new((0, 0), (1, 2)));
+        let truncation = truncate_table(&mut table, None, Some(1), RowSampling::Head).unwrap();
+        assert_eq!(table.rows, vec![vec!["A"], vec!["1"]]);
+        assert_eq!(table.col_ids, vec![0]);
+        assert_eq!(truncation.details(), "2 more columns: B, C");
+    }
+
+    #[test]
+    fn test_xlsx_max_rows_and_columns() {
+        let rows: Vec<Vec<TestCell>> = std::iter::once(vec![
+            TestCell::Str("Id"),
+            TestCell::Str("Name"),
+            TestCell::Str("Notes"),
+        ])
+        .chain((1..=1205).map(|n| {
+            vec![
+                TestCell::Num(f64::from(n)),
+                TestCell::Str("x"),
+                TestCell::Str("y"),
+            ]
+        }))
+        .collect();
+        let row_refs: Vec<&[TestCell]> = rows.iter().map(Vec::as_slice).collect();
+        let data = build_test_xlsx(&[("Data", &row_refs)]);
+
+        let result = convert_xlsx_with(
+            &data,
+            XlsxOptions {
+                max_rows: Some(2),
+                max_columns: Some(2),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            result.markdown,
+            "## Data\n| Id | Name |\n|---|---|\n| 1 | x |\n| 2 | x |\n\n… 1,203 more rows (showing first 2), 1 more column: Notes\n"
+        );
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::ResourceLimitReached);
+        assert_eq!(
+            result.warnings[0].message,
+            "truncated sheet 'Data': 1,203 more rows (showing first 2), 1 more column: Notes"
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            image_describer: Some(Arc::new(MockDescriber {
-                description: "A chart from openpyxl".to_string(),
-            })),
-            ..Default::default()
-        };
-        let result = converter.convert(&data, &options).unwrap();
         assert!(
             result
-                .markdown
-                .contains("![A chart from openpyxl](image1.png)"),
-            "markdown was: {}",
-            result.markdown
+                .plain_text
+                .ends_with("\n… 1,203 more rows (showing first 2), 1 more column: Notes\n")
         );
     }
 
-    #[test]
-    fn test_xlsx_plain_text_contains_image_placeholder() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    // -- Chart and pivot table tests --
+
+    const CHART_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><c:chart><c:title><c:tx><c:rich><a:bodyPr/><a:p><a:r><a:t>Revenue by region</a:t></a:r></a:p></c:rich></c:tx></c:title><c:plotArea><c:barChart><c:barDir val="col"/><c:ser><c:idx val="0"/><c:tx><c:strRef><c:f>Data!$B$1</c:f><c:strCache><c:ptCount val="1"/><c:pt idx="0"><c:v>Q1</c:v></c:pt></c:strCache></c:strRef></c:tx><c:cat><c:strRef><c:f>Data!$A$2:$A$3</c:f><c:strCache><c:ptCount val="2"/><c:pt idx="0"><c:v>North</c:v></c:pt><c:pt idx="1"><c:v>South</c:v></c:pt></c:strCache></c:strRef></c:cat><c:val><c:numRef><c:f>Data!$B$2:$B$3</c:f><c:numCache><c:formatCode>General</c:formatCode><c:ptCount val="2"/><c:pt idx="0"><c:v>10</c:v></c:pt><c:pt idx="1"><c:v>12.5</c:v></c:pt></c:numCache></c:numRef></c:val></c:ser><c:ser><c:idx val="1"/><c:val><c:numRef><c:numCache><c:ptCount val="2"/><c:pt idx="1"><c:v>7</c:v></c:pt></c:numCache></c:numRef></c:val></c:ser></c:barChart><c:catAx><c:title><c:tx><c:rich><a:p><a:r><a:t>Region</a:t></a:r></a:p></c:rich></c:tx></c:title></c:catAx></c:plotArea></c:chart></c:chartSpace>"#;
+
+    const PIVOT_TABLE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><pivotTableDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" name="RevenuePivot" cacheId="1" dataCaption="Values"><location ref="A3:C6" firstHeaderRow="1" firstDataRow="2" firstDataCol="1"/><pivotFields count="3"><pivotField axis="axisRow" showAll="0"/><pivotField axis="axisCol" showAll="0"/><pivotField dataField="1" showAll="0"/></pivotFields><rowFields count="1"><field x="0"/></rowFields><colFields count="1"><field x="1"/></colFields><dataFields count="1"><dataField name="Sum of Revenue" fld="2" baseField="0" baseItem="0"/></dataFields></pivotTableDefinition>"#;
+
+    const PIVOT_CACHE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><pivotCacheDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" r:id="rId1" recordCount="4"><cacheSource type="worksheet"><worksheetSource ref="A1:C5" sheet="Data"/></cacheSource><cacheFields count="3"><cacheField name="Region" numFmtId="0"><sharedItems count="2"><s v="North"/><s v="South"/></sharedItems></cacheField><cacheField name="Quarter" numFmtId="0"><sharedItems count="2"><s v="Q1"/><s v="Q2"/></sharedItems></cacheField><cacheField name="Revenue" numFmtId="0"><sharedItems containsNumber="1" minValue="3" maxValue="12.5"/></cacheField></cacheFields></pivotCacheDefinition>"#;
+
+    const PIVOT_RECORDS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><pivotCacheRecords xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="4"><r><x v="0"/><x v="0"/><n v="10"/></r><r><x v="1"/><x v="0"/><n v="12.5"/></r><r><x v="0"/><x v="1"/><n v="3"/></r><r><x v="0"/><x v="1"/><n v="4"/></r></pivotCacheRecords>"#;
+
+    fn build_dashboard_xlsx(with_records: bool) -> Vec<u8> {
+        let data_sheet = format!(
+            r#"<sheetData><row r="1">{}{}</row><row r="2">{}<c r="B2"><v>10</v></c></row><row r="3">{}<c r="B3"><v>12.5</v></c></row></sheetData><drawing r:id="rId1"/>"#,
+            str_cell("A1", "Region"),
+            str_cell("B1", "Q1"),
+            str_cell("A2", "North"),
+            str_cell("A3", "South"),
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            extract_images: true,
-            ..Default::default()
+        // Pivot output as Excel rendered it at A3:C6, plus a note above it
+        let summary_sheet = format!(
+            r#"<sheetData><row r="1">{}</row><row r="3">{}{}{}</row><row r="4">{}<c r="B4"><v>10</v></c><c r="C4"><v>7</v></c></row><row r="5">{}<c r="B5"><v>12.5</v></c></row></sheetData>"#,
+            str_cell("A1", "Pivot below"),
+            str_cell("A3", "Sum of Revenue"),
+            str_cell("B3", "Q1"),
+            str_cell("C3", "Q2"),
+            str_cell("A4", "North"),
+            str_cell("A5", "South"),
+        );
+        let rels = |rel_type: &str, target: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="{rel_type}" Target="{target}"/></Relationships>"#
+            )
         };
-        // Use convert_inner to inspect pre-resolution state
-        let (result, _pending) = converter.convert_inner(&data, &options).unwrap();
-        // plain_text should contain the placeholder (before resolution)
-        assert!(
-            result.plain_text.contains("__img_0__"),
-            "plain_text should contain the image placeholder, was: {}",
-            result.plain_text
+        let sheet1_rels = rels(
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing",
+            "../drawings/drawing1.xml",
         );
-        // plain_text should NOT contain image markdown syntax
-        assert!(
-            !result.plain_text.contains("!["),
-            "plain_text should not contain ![ image syntax, was: {}",
-            result.plain_text
+        let drawing = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><xdr:wsDr xmlns:xdr="http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><xdr:twoCellAnchor><xdr:graphicFrame><a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/chart"><c:chart r:id="rId1"/></a:graphicData></a:graphic></xdr:graphicFrame></xdr:twoCellAnchor></xdr:wsDr>"#;
+        let drawing_rels = rels(
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart",
+            "../charts/chart1.xml",
+        );
+        let sheet2_rels = rels(
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotTable",
+            "../pivotTables/pivotTable1.xml",
+        );
+        let pivot_rels = rels(
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition",
+            "../pivotCache/pivotCacheDefinition1.xml",
+        );
+        let cache_rels = rels(
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheRecords",
+            "pivotCacheRecords1.xml",
         );
+        let mut parts: Vec<(&str, &str)> = vec![
+            ("xl/worksheets/_rels/sheet1.xml.rels", &sheet1_rels),
+            ("xl/drawings/drawing1.xml", drawing),
+            ("xl/drawings/_rels/drawing1.xml.rels", &drawing_rels),
+            ("xl/charts/chart1.xml", CHART_XML),
+            ("xl/worksheets/_rels/sheet2.xml.rels", &sheet2_rels),
+            ("xl/pivotTables/pivotTable1.xml", PIVOT_TABLE_XML),
+            ("xl/pivotTables/_rels/pivotTable1.xml.rels", &pivot_rels),
+            ("xl/pivotCache/pivotCacheDefinition1.xml", PIVOT_CACHE_XML),
+        ];
+        if with_records {
+            parts.push((
+                "xl/pivotCache/_rels/pivotCacheDefinition1.xml.rels",
+                &cache_rels,
+            ));
+            parts.push(("xl/pivotCache/pivotCacheRecords1.xml", PIVOT_RECORDS_XML));
+        }
+        build_raw_xlsx(
+            &[("Data", "", &data_sheet), ("Summary", "", &summary_sheet)],
+            &parts,
+        )
     }
 
     #[test]
-    fn test_xlsx_image_describer_updates_plain_text() {
-        use TestCell::*;
-        let data = build_test_xlsx_with_image(
-            &[("Sheet1", &[&[Str("Name")][..], &[Str("Alice")]])],
-            "image1.png",
-            b"fake-png-data",
+    fn test_xlsx_parse_chart_series() {
+        let chart = parse_chart(CHART_XML);
+        assert_eq!(chart.title.as_deref(), Some("Revenue by region"));
+        assert_eq!(chart.kind, Some("bar"));
+        assert_eq!(chart.series.len(), 2);
+        assert_eq!(chart.series[0].name.as_deref(), Some("Q1"));
+        assert_eq!(chart.series[0].categories[&1], "South");
+        assert_eq!(chart.series[0].values[&1], "12.5");
+        assert_eq!(chart.series[1].name, None);
+    }
+
+    #[test]
+    fn test_xlsx_render_chart_untitled() {
+        let chart = Chart {
+            title: None,
+            kind: Some("line"),
+            series: vec![ChartSeries {
+                name: None,
+                categories: BTreeMap::new(),
+                values: [(0, "1".to_string()), (1, "2.50".to_string())].into(),
+            }],
+        };
+        let (md, plain) = render_chart(&chart, 2);
+        assert_eq!(
+            md,
+            "### Chart 2 (line chart)\n| Category | Series 1 |\n|---|---|\n| 1 | 1 |\n| 2 | 2.5 |\n"
         );
-        let converter = XlsxConverter;
-        let options = ConversionOptions {
-            image_describer: Some(Arc::new(MockDescriber {
-                description: "A bar chart".to_string(),
-            })),
-            ..Default::default()
+        assert!(plain.starts_with("Chart 2 (line chart)\nCategory\tSeries 1\n"));
+    }
+
+    #[test]
+    fn test_xlsx_render_chart_only_cached_points() {
+        let chart = Chart {
+            title: None,
+            kind: Some("bar"),
+            series: vec![ChartSeries {
+                name: Some("Q1".to_string()),
+                categories: [(0, "North".to_string())].into(),
+                values: [(0, "10".to_string()), (1_000_000, "3".to_string())].into(),
+            }],
         };
-        let result = converter.convert(&data, &options).unwrap();
-        // plain_text should contain the description
+        let (md, _) = render_chart(&chart, 1);
+        assert_eq!(
+            md,
+            "### Chart 1 (bar chart)\n| Category | Q1 |\n|---|---|\n| North | 10 |\n| 1000001 | 3 |\n"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_parse_pivot_definition_and_cache() {
+        let pivot = parse_pivot_table_definition(PIVOT_TABLE_XML);
+        assert_eq!(pivot.name, "RevenuePivot");
+        assert_eq!(pivot.location, Some(Dimensions::new((2, 0), (5, 2))));
+        assert_eq!(pivot.row_fields, vec![0]);
+        assert_eq!(pivot.col_fields, vec![1]);
+        assert_eq!(
+            pivot.data_fields,
+            vec![PivotDataField {
+                name: "Sum of Revenue".to_string(),
+                field: 2,
+                subtotal: "sum".to_string(),
+            }]
+        );
+
+        let (cache, shared_items) = parse_pivot_cache_definition(PIVOT_CACHE_XML);
+        assert_eq!(cache.source.as_deref(), Some("Data!A1:C5"));
+        assert_eq!(cache.fields, vec!["Region", "Quarter", "Revenue"]);
+        assert_eq!(shared_items[2], Vec::<String>::new());
+        let records = parse_pivot_cache_records(PIVOT_RECORDS_XML, &shared_items);
+        assert_eq!(records[1], vec!["South", "Q1", "12.5"]);
+    }
+
+    #[test]
+    fn test_xlsx_pivot_aggregate_functions() {
+        let mut agg = PivotAggregate::default();
+        for text in ["2", "4", "", "n/a"] {
+            agg.add(text);
+        }
+        assert_eq!(agg.value("sum"), "6");
+        assert_eq!(agg.value("count"), "3");
+        assert_eq!(agg.value("countNums"), "2");
+        assert_eq!(agg.value("average"), "3");
+        assert_eq!(agg.value("max"), "4");
+        assert_eq!(agg.value("min"), "2");
+        assert_eq!(agg.value("product"), "8");
+        assert_eq!(agg.value("var"), "2");
+        assert_eq!(agg.value("varp"), "1");
+        assert_eq!(agg.value("stdDev"), std::f64::consts::SQRT_2.to_string());
+        assert_eq!(agg.value("stdDevp"), "1");
+
+        let mut single = PivotAggregate::default();
+        single.add("5");
+        assert_eq!(single.value("stdDev"), "#DIV/0!");
+        assert_eq!(single.value("varp"), "0");
+    }
+
+    #[test]
+    fn test_xlsx_chart_and_pivot_rendered() {
+        let data = build_dashboard_xlsx(true);
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        let md = &result.markdown;
         assert!(
-            result.plain_text.contains("A bar chart"),
-            "plain_text should contain the image description, was: {}",
-            result.plain_text
+            md.contains(
+                "## Data\n| Region | Q1 |\n|---|---|\n| North | 10 |\n| South | 12.5 |\n\n### Chart: Revenue by region (bar chart)\n| Category | Q1 | Series 2 |\n|---|---|---|\n| North | 10 |  |\n| South | 12.5 | 7 |\n"
+            ),
+            "{md}"
         );
-        // plain_text should NOT contain image markdown syntax
         assert!(
-            !result.plain_text.contains("!["),
-            "plain_text should not contain ![ image syntax, was: {}",
-            result.plain_text
+            md.contains(
+                "## Summary\n| Pivot below |\n|---|\n\n### Pivot Table: RevenuePivot\n- Source: Data!A1:C5\n- Rows: Region\n- Columns: Quarter\n- Values: Sum of Revenue\n\n| Region | Q1 | Q2 |\n|---|---|---|\n| North | 10 | 7 |\n| South | 12.5 |  |\n"
+            ),
+            "{md}"
+        );
+        assert!(
+            result
+                .plain_text
+                .contains("Pivot Table: RevenuePivot\nSource: Data!A1:C5\n")
+        );
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_xlsx_pivot_without_records_uses_rendered_cells() {
+        let data = build_dashboard_xlsx(false);
+        let result = XlsxConverter
+            .convert(&data, &ConversionOptions::default())
+            .unwrap();
+        assert!(
+            result.markdown.ends_with(
+                "- Values: Sum of Revenue\n\n| Sum of Revenue | Q1 | Q2 |\n|---|---|---|\n| North | 10 | 7 |\n| South | 12.5 |  |\n"
+            ),
+            "{}",
+            result.markdown
         );
     }
 }
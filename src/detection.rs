@@ -10,6 +10,9 @@ use std::path::Path;
 const ZIP_MAGIC: &[u8] = &[0x50, 0x4B, 0x03, 0x04];
 const PDF_MAGIC: &[u8] = b"%PDF";
 
+/// Content of the `mimetype` entry of an OpenDocument spreadsheet.
+const ODS_MIMETYPE: &[u8] = b"application/vnd.oasis.opendocument.spreadsheet";
+
 /// Detect the document format from a file path and optional header bytes.
 ///
 /// Priority: magic bytes → file extension → JSON heuristic (fallback).
@@ -49,12 +52,30 @@ pub fn detect_format(path: &Path, header_bytes: &[u8]) -> Option<&'static str> {
 
 /// Detect the specific format of a ZIP-based file by inspecting its internal paths.
 ///
-/// Returns "docx", "pptx", or "xlsx" based on the presence of characteristic
-/// internal files. Returns None if the ZIP does not match a known format.
+/// Returns "docx", "pptx", "xlsx", "xlsb", or "ods" based on the presence of
+/// characteristic internal files (for ODS, the `mimetype` entry). Returns None
+/// if the ZIP does not match a known format.
 pub fn detect_zip_format(data: &[u8]) -> Option<&'static str> {
+    use std::io::Read;
+
     let cursor = std::io::Cursor::new(data);
     let mut archive = zip::ZipArchive::new(cursor).ok()?;
 
+    if let Ok(file) = archive.by_name("mimetype") {
+        let mut mimetype = Vec::new();
+        if file
+            .take(ODS_MIMETYPE.len() as u64 + 1)
+            .read_to_end(&mut mimetype)
+            .is_ok()
+            && mimetype.trim_ascii() == ODS_MIMETYPE
+        {
+            return Some("ods");
+        }
+    }
+    if archive.index_for_name("xl/workbook.bin").is_some() {
+        return Some("xlsb");
+    }
+
     for i in 0..archive.len() {
         if let Ok(file) = archive.by_index_raw(i) {
             let name = file.name();
@@ -81,11 +102,14 @@ fn detect_by_extension(path: &Path) -> Option<&'static str> {
         "pptx" => Some("pptx"),
         "xlsx" => Some("xlsx"),
         "xls" => Some("xls"),
+        "xlsb" => Some("xlsb"),
+        "ods" => Some("ods"),
         "csv" => Some("csv"),
         "ipynb" => Some("ipynb"),
         "json" => Some("json"),
         "pdf" => Some("pdf"),
         "html" | "htm" => Some("html"),
+        "mhtml" | "mht" => Some("mhtml"),
         "xml" => Some("xml"),
         "txt" | "text" | "log" | "md" | "markdown" | "rst" | "ini" | "cfg" | "conf" | "toml"
         | "yaml" | "yml" => Some("txt"),
@@ -170,6 +194,14 @@ mod tests {
         assert_eq!(detect_format(&path2, &[]), Some("html"));
     }
 
+    #[test]
+    fn test_detect_format_mhtml_by_extension() {
+        let path = PathBuf::from("page.mhtml");
+        assert_eq!(detect_format(&path, &[]), Some("mhtml"));
+        let path2 = PathBuf::from("page.MHT");
+        assert_eq!(detect_format(&path2, &[]), Some("mhtml"));
+    }
+
     #[test]
     fn test_detect_format_unknown_returns_none() {
         let path = PathBuf::from("file.xyz");
@@ -313,4 +345,48 @@ mod tests {
             );
         }
     }
+
+    fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_detect_format_spreadsheet_variants_by_extension() {
+        assert_eq!(detect_format(&PathBuf::from("big.xlsb"), &[]), Some("xlsb"));
+        assert_eq!(detect_format(&PathBuf::from("sheet.ODS"), &[]), Some("ods"));
+    }
+
+    #[test]
+    fn test_detect_zip_format_xlsx_and_xlsb() {
+        let xlsx = build_zip(&[("xl/workbook.xml", b"<workbook/>")]);
+        assert_eq!(detect_zip_format(&xlsx), Some("xlsx"));
+        let xlsb = build_zip(&[
+            ("[Content_Types].xml", b"<Types/>"),
+            ("xl/workbook.bin", b""),
+        ]);
+        assert_eq!(detect_zip_format(&xlsb), Some("xlsb"));
+    }
+
+    #[test]
+    fn test_detect_zip_format_ods_by_mimetype() {
+        let ods = build_zip(&[
+            ("mimetype", ODS_MIMETYPE),
+            ("content.xml", b"<office:document-content/>"),
+        ]);
+        assert_eq!(detect_zip_format(&ods), Some("ods"));
+
+        let odt = build_zip(&[
+            ("mimetype", b"application/vnd.oasis.opendocument.text"),
+            ("content.xml", b"<office:document-content/>"),
+        ]);
+        assert_eq!(detect_zip_format(&odt), None);
+    }
 }
@@ -12,7 +12,10 @@
 //! | PPTX | `.pptx` |
 //! | XLSX | `.xlsx` |
 //! | XLS | `.xls` |
+//! | XLSB | `.xlsb` |
+//! | ODS | `.ods` |
 //! | HTML | `.html`, `.htm` |
+//! | MHTML | `.mhtml`, `.mht` |
 //! | CSV | `.csv` |
 //! | Jupyter Notebook | `.ipynb` |
 //! | JSON | `.json` |
@@ -64,7 +67,9 @@ pub(crate) mod zip_utils;
 #[cfg(feature = "async")]
 pub use converter::{AsyncConversionOptions, AsyncImageDescriber};
 pub use converter::{
-    ConversionOptions, ConversionResult, ConversionWarning, Converter, ImageDescriber, WarningCode,
+    CellValues, ComplexTables, ConversionOptions, ConversionResult, ConversionWarning, Converter,
+    DocumentMetadata, Formulas, HiddenContent, HiddenSlides, HtmlOptions, ImageDescriber,
+    LinkPolicy, MergedCells, PptxOptions, RowSampling, WarningCode, XlsxOptions,
 };
 pub use error::ConvertError;
 
@@ -135,9 +140,31 @@ pub fn convert_file(
         None => path.extension().and_then(|e| e.to_str()).unwrap_or(""),
     };
 
+    // HTML: read relative <img src> files from the document's directory
+    if let Some(options) = with_html_image_dir(options, path, extension) {
+        return convert_bytes(&data, extension, &options);
+    }
+
     convert_bytes(&data, extension, options)
 }
 
+/// Options with [`HtmlOptions::image_dir`] set to the directory of `path`,
+/// if `path` is an HTML file and no directory was configured.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_html_image_dir(
+    options: &ConversionOptions,
+    path: &Path,
+    extension: &str,
+) -> Option<ConversionOptions> {
+    let is_html = matches!(normalize_extension(extension).as_str(), "html" | "htm");
+    if !is_html || options.html.image_dir.is_some() {
+        return None;
+    }
+    let mut options = options.clone();
+    options.html.image_dir = Some(path.parent().unwrap_or(Path::new("")).to_path_buf());
+    Some(options)
+}
+
 /// Convert raw bytes to Markdown with an explicit format extension.
 pub fn convert_bytes(
     data: &[u8],
@@ -169,6 +196,7 @@ pub fn convert_bytes(
     use converter::image::ImageConverter;
     use converter::ipynb::IpynbConverter;
     use converter::json::JsonConverter;
+    use converter::mhtml::MhtmlConverter;
     use converter::plain_text::PlainTextConverter;
     use converter::pptx::PptxConverter;
     use converter::xlsx::XlsxConverter;
@@ -192,6 +220,7 @@ pub fn convert_bytes(
         Box::new(XmlConverter),
         Box::new(CsvConverter),
         Box::new(HtmlConverter),
+        Box::new(MhtmlConverter),
         Box::new(ImageConverter),
         Box::new(PlainTextConverter),
     ];
@@ -287,12 +316,20 @@ pub async fn convert_file_async(
         None => path.extension().and_then(|e| e.to_str()).unwrap_or(""),
     };
 
+    if let Some(base) = with_html_image_dir(&options.base, path, extension) {
+        let options = converter::AsyncConversionOptions {
+            base,
+            async_image_describer: options.async_image_describer.clone(),
+        };
+        return convert_bytes_async(&data, extension, &options).await;
+    }
+
     convert_bytes_async(&data, extension, options).await
 }
 
 /// Convert raw bytes to Markdown with async image description.
 ///
-/// For image-bearing formats (docx, pptx, xlsx, image), uses `convert_inner()`
+/// For image-bearing formats (docx, pptx, xlsx, html, mhtml, image), uses `convert_inner()`
 /// for parsing then resolves images concurrently via the async describer.
 /// For other formats, falls through to the sync `convert()`.
 ///
@@ -356,7 +393,7 @@ pub async fn convert_bytes_async(
                 }
                 return enforce_strict_mode(result, options.base.strict);
             }
-            "xlsx" | "xls" => {
+            "xlsx" | "xls" | "xlsb" | "ods" => {
                 let conv = converter::xlsx::XlsxConverter;
                 let (mut result, pending) = conv.convert_inner(data, &options.base)?;
                 if !pending.infos.is_empty() {
@@ -372,6 +409,38 @@ pub async fn convert_bytes_async(
                 }
                 return enforce_strict_mode(result, options.base.strict);
             }
+            "html" | "htm" => {
+                let conv = converter::html::HtmlConverter;
+                let (mut result, pending) = conv.convert_inner(data, &options.base)?;
+                if !pending.infos.is_empty() {
+                    converter::ooxml_utils::resolve_image_placeholders_async(
+                        &mut result.markdown,
+                        &mut result.plain_text,
+                        &pending.infos,
+                        &pending.bytes,
+                        describer.as_ref(),
+                        &mut result.warnings,
+                    )
+                    .await;
+                }
+                return enforce_strict_mode(result, options.base.strict);
+            }
+            "mhtml" | "mht" => {
+                let conv = converter::mhtml::MhtmlConverter;
+                let (mut result, pending) = conv.convert_inner(data, &options.base)?;
+                if !pending.infos.is_empty() {
+                    converter::ooxml_utils::resolve_image_placeholders_async(
+                        &mut result.markdown,
+                        &mut result.plain_text,
+                        &pending.infos,
+                        &pending.bytes,
+                        describer.as_ref(),
+                        &mut result.warnings,
+                    )
+                    .await;
+                }
+                return enforce_strict_mode(result, options.base.strict);
+            }
             ext if converter::image::ImageConverter.can_convert(ext, data) => {
                 let conv = converter::image::ImageConverter;
                 let (mut result, pending) = conv.convert_inner(data, &options.base)?;
@@ -98,3 +98,73 @@ fn test_html_convert_bytes_direct() {
     assert!(result.markdown.contains("# Hello"));
     assert!(result.markdown.contains("World"));
 }
+
+/// Integration test: sample_semantic.html covers definition lists, figures,
+/// details/summary, and inline semantic elements.
+#[test]
+fn test_html_convert_file_semantic_elements() {
+    let result = convert_file(
+        "tests/fixtures/sample_semantic.html",
+        &ConversionOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(result.title, Some("Semantic Elements".to_string()));
+
+    // Definition list
+    assert!(
+        result
+            .markdown
+            .contains("HTML\n:   The markup language of the web.")
+    );
+    assert!(result.markdown.contains(":   Also used for *layout*."));
+
+    // Figure caption and details summary
+    assert!(result.markdown.contains("*Figure 1: Revenue by quarter*"));
+    assert!(result.markdown.contains("**Show more**"));
+
+    // Inline semantics
+    assert!(result.markdown.contains("~~$20~~ <ins>$15</ins>"));
+    assert!(result.markdown.contains("mc<sup>2</sup>"));
+    assert!(result.markdown.contains("H<sub>2</sub>O"));
+    assert!(result.markdown.contains("<kbd>Ctrl</kbd>+<kbd>C</kbd>"));
+    assert!(result.markdown.contains("<mark>important</mark>"));
+    assert!(result.markdown.contains("WHO (World Health Organization)"));
+    assert!(result.markdown.contains("\"wash your hands\""));
+    assert!(result.markdown.contains("*The Daily Paper*"));
+
+    // Plain text keeps the words without markup
+    assert!(result.plain_text.contains("The price is $20 $15 today."));
+    assert!(result.plain_text.contains("E = mc2"));
+    assert!(!result.plain_text.contains("<kbd>"));
+}
+
+/// Golden test: compare normalized output against expected file.
+#[test]
+fn test_html_golden_semantic_elements() {
+    let result = convert_file(
+        "tests/fixtures/sample_semantic.html",
+        &ConversionOptions::default(),
+    )
+    .unwrap();
+    let expected = include_str!("fixtures/expected/sample_semantic.html.md");
+    assert_eq!(normalize(&result.markdown), normalize(expected));
+}
+
+/// Integration test: convert_file reads relative images next to the HTML file.
+#[test]
+fn test_html_convert_file_extracts_local_images() {
+    let dir = tempfile::tempdir().unwrap();
+    let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0];
+    std::fs::write(dir.path().join("photo.png"), png).unwrap();
+    let page = dir.path().join("page.html");
+    std::fs::write(&page, r#"<p><img src="photo.png" alt="Photo"></p>"#).unwrap();
+
+    let options = ConversionOptions {
+        extract_images: true,
+        ..Default::default()
+    };
+    let result = convert_file(&page, &options).unwrap();
+    assert!(result.markdown.contains("![Photo](photo.png)"));
+    assert_eq!(result.images, vec![("photo.png".to_string(), png.to_vec())]);
+}
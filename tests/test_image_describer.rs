@@ -1,6 +1,6 @@
 #![cfg(not(target_arch = "wasm32"))]
 
-//! Integration tests for the ImageDescriber trait and its effect on DOCX/PPTX/XLSX/standalone image conversion.
+//! Integration tests for the ImageDescriber trait and its effect on DOCX/PPTX/XLSX/MHTML/standalone image conversion.
 
 use std::io::{Cursor, Write};
 use std::sync::Arc;
@@ -287,6 +287,29 @@ fn test_xlsx_without_describer_has_empty_alt() {
     );
 }
 
+// ---- MHTML integration tests ----
+
+#[test]
+fn test_mhtml_with_mock_describer_replaces_alt_text() {
+    let data = std::fs::read("tests/fixtures/sample.mhtml").unwrap();
+    let options = ConversionOptions {
+        image_describer: Some(Arc::new(MockDescriber {
+            description: "A single transparent pixel".to_string(),
+        })),
+        ..Default::default()
+    };
+    let result = anytomd::convert_bytes(&data, "mhtml", &options).unwrap();
+    assert!(
+        result
+            .markdown
+            .contains("![A single transparent pixel](pixel.png)"),
+        "markdown was: {}",
+        result.markdown
+    );
+    // Images missing from the archive keep their alt text
+    assert!(result.markdown.contains("![Not archived]("));
+}
+
 // ---- Standalone image file integration tests ----
 
 /// Minimal PNG file header for standalone image tests.
@@ -0,0 +1,77 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use anytomd::{ConversionOptions, convert_file};
+use common::normalize;
+
+/// Integration test: sample.mhtml end-to-end conversion via convert_file.
+/// Fixture is a Blink-style archive with a quoted-printable HTML root, a CSS
+/// part and a base64 PNG part.
+#[test]
+fn test_mhtml_convert_file_sample() {
+    let result =
+        convert_file("tests/fixtures/sample.mhtml", &ConversionOptions::default()).unwrap();
+
+    assert_eq!(result.title, Some("Saved Web Page".to_string()));
+    assert_eq!(
+        result.metadata.description.as_deref(),
+        Some("A page saved as a single-file web archive.")
+    );
+
+    // Quoted-printable decoding keeps UTF-8 text intact
+    assert!(result.markdown.contains("# Saved Web Page"));
+    assert!(result.markdown.contains("café, 한국어, 🚀"));
+
+    // Relative links resolve against the root part's Content-Location
+    assert!(
+        result
+            .markdown
+            .contains("[guide](https://example.com/docs/guide.html)")
+    );
+
+    // Tables and lists from the root HTML
+    assert!(result.markdown.contains("| MHTML | .mhtml |"));
+    assert!(result.markdown.contains("- Second item"));
+
+    // The stylesheet part is not rendered
+    assert!(!result.markdown.contains("font-family"));
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+}
+
+/// Golden test: normalized comparison against expected output.
+#[test]
+fn test_mhtml_golden_sample() {
+    let result =
+        convert_file("tests/fixtures/sample.mhtml", &ConversionOptions::default()).unwrap();
+    let expected = include_str!("fixtures/expected/sample.mhtml.md");
+    assert_eq!(normalize(&result.markdown), normalize(expected));
+}
+
+/// Integration test: embedded images are extracted under the file name of
+/// their URL; images that were not archived are left as links.
+#[test]
+fn test_mhtml_convert_file_extracts_embedded_images() {
+    let options = ConversionOptions {
+        extract_images: true,
+        ..Default::default()
+    };
+    let result = convert_file("tests/fixtures/sample.mhtml", &options).unwrap();
+    assert_eq!(result.images.len(), 1);
+    assert_eq!(result.images[0].0, "pixel.png");
+    assert!(result.images[0].1.starts_with(b"\x89PNG"));
+    assert!(result.markdown.contains("![Embedded pixel](pixel.png)"));
+    assert!(
+        result
+            .markdown
+            .contains("![Not archived](https://cdn.example.com/missing.png)")
+    );
+}
+
+/// Integration test: convert_bytes with the short "mht" extension.
+#[test]
+fn test_mhtml_convert_bytes_mht_extension() {
+    let data = std::fs::read("tests/fixtures/sample.mhtml").unwrap();
+    let result = anytomd::convert_bytes(&data, "mht", &ConversionOptions::default()).unwrap();
+    assert!(result.markdown.contains("## Table"));
+}
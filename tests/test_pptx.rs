@@ -12,7 +12,7 @@ use std::io::Cursor;
 /// Contains:
 /// - Slide 1: Title "Sample Presentation", body "Welcome to the presentation."
 /// - Slide 2: Text "Data Overview", table (Name/Value/Status with 3 data rows),
-///            speaker notes "Remember to explain the data table."
+///   speaker notes "Remember to explain the data table."
 /// - Slide 3: Title "Multilingual", Korean text, emoji, speaker notes
 #[test]
 fn test_pptx_convert_file_sample() {